@@ -53,7 +53,7 @@ create() {
 update-allowed-code-ids() {
   sender=$1
   code_ids_json_array=$2
-  msg='{"update_allowed_code_ides":{"code_ids":'$code_ids_json_array'}}'
+  msg='{"admin":{"update_allowed_code_ids":{"code_ids":'$code_ids_json_array'}}}'
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -74,7 +74,7 @@ update-allowed-code-ids() {
 remove() {
   sender=$1
   contract_addr=$2
-  msg='{"remove":{"contract_addr":"'$contract_addr'"}}'
+  msg='{"admin":{"remove":{"contract_addr":"'$contract_addr'"}}}'
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -94,7 +94,7 @@ remove() {
 enable-acl() {
   sender=$1
   acl_contract_addr=$2
-  msg='{"set_acl":{"acl_contract_addr":"'$acl_contract_addr'"}}'
+  msg='{"admin":{"set_acl":{"acl_contract_addr":"'$acl_contract_addr'"}}}'
   flags="\
   --node $NODE \
   --gas-prices 0.025$DENOM \
@@ -0,0 +1,174 @@
+//! Constructors for the IndexBounds of common queries. Text values set
+//! through UpdateBuilder::set_string are stored base64 encoded, so the text
+//! bounds here expect values in that same encoded form.
+
+use cosmwasm_std::{Addr, Timestamp, Uint128, Uint64};
+
+use crate::models::{IndexBounds, Slot};
+
+impl IndexBounds {
+  pub fn u64_equals(
+    slot: Slot,
+    value: u64,
+  ) -> Self {
+    Self::Uint64 {
+      slot,
+      between: None,
+      equals: Some(value.into()),
+    }
+  }
+
+  pub fn u64_between(
+    slot: Slot,
+    lower: Option<u64>,
+    upper: Option<u64>,
+  ) -> Self {
+    Self::Uint64 {
+      slot,
+      between: Some((lower.map(Uint64::from), upper.map(Uint64::from))),
+      equals: None,
+    }
+  }
+
+  pub fn u128_equals(
+    slot: Slot,
+    value: u128,
+  ) -> Self {
+    Self::Uint128 {
+      slot,
+      between: None,
+      equals: Some(value.into()),
+    }
+  }
+
+  pub fn u128_between(
+    slot: Slot,
+    lower: Option<u128>,
+    upper: Option<u128>,
+  ) -> Self {
+    Self::Uint128 {
+      slot,
+      between: Some((lower.map(Uint128::from), upper.map(Uint128::from))),
+      equals: None,
+    }
+  }
+
+  pub fn timestamp_between(
+    slot: Slot,
+    lower: Option<Timestamp>,
+    upper: Option<Timestamp>,
+  ) -> Self {
+    Self::Timestamp {
+      slot,
+      between: Some((lower, upper)),
+      equals: None,
+      relative_to_now: None,
+    }
+  }
+
+  /// Timestamps in the same UTC hour as the given time
+  pub fn timestamp_in_hour(
+    slot: Slot,
+    time: Timestamp,
+  ) -> Self {
+    Self::timestamp_in_bucket(slot, time, 60 * 60)
+  }
+
+  /// Timestamps in the same UTC day as the given time
+  pub fn timestamp_in_day(
+    slot: Slot,
+    time: Timestamp,
+  ) -> Self {
+    Self::timestamp_in_bucket(slot, time, 24 * 60 * 60)
+  }
+
+  /// Timestamps in the bucket of the given width, in seconds, containing the
+  /// given time. The upper bound of between is exclusive, so adjacent buckets
+  /// don't overlap.
+  fn timestamp_in_bucket(
+    slot: Slot,
+    time: Timestamp,
+    width: u64,
+  ) -> Self {
+    let start = time.seconds() - time.seconds() % width;
+    Self::timestamp_between(
+      slot,
+      Some(Timestamp::from_seconds(start)),
+      Some(Timestamp::from_seconds(start + width)),
+    )
+  }
+
+  pub fn text_equals(
+    slot: Slot,
+    value: &str,
+  ) -> Self {
+    Self::Text {
+      slot,
+      between: None,
+      equals: Some(value.to_owned()),
+    }
+  }
+
+  /// Match text values that start with the given prefix.
+  pub fn text_prefix(
+    slot: Slot,
+    prefix: &str,
+  ) -> Self {
+    Self::Text {
+      slot,
+      between: Some((
+        Some(prefix.to_owned()),
+        Some(format!("{}{}", prefix, char::MAX)),
+      )),
+      equals: None,
+    }
+  }
+
+  pub fn boolean_is(
+    slot: Slot,
+    value: bool,
+  ) -> Self {
+    // stop is exclusive, and true is the greatest value
+    Self::Boolean {
+      slot,
+      start: Some(value),
+      stop: if value { None } else { Some(true) },
+    }
+  }
+
+  pub fn code_id_is(code_id: u64) -> Self {
+    Self::CodeId {
+      between: None,
+      equals: Some(code_id.into()),
+      equals_any: None,
+    }
+  }
+
+  pub fn created_by(addr: &Addr) -> Self {
+    Self::CreatedBy {
+      between: None,
+      equals: Some(addr.clone()),
+      equals_any: None,
+    }
+  }
+
+  pub fn created_between(
+    lower: Option<Timestamp>,
+    upper: Option<Timestamp>,
+  ) -> Self {
+    Self::CreatedAt {
+      between: Some((lower, upper)),
+      equals: None,
+    }
+  }
+
+  pub fn updated_between(
+    lower: Option<Timestamp>,
+    upper: Option<Timestamp>,
+  ) -> Self {
+    Self::UpdatedAt {
+      between: Some((lower, upper)),
+      equals: None,
+    }
+  }
+}
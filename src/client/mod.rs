@@ -1,15 +1,28 @@
+pub mod bounds;
+
 use std::collections::HashSet;
 
 use cosmwasm_std::{
-  to_binary, Addr, Binary, Empty, QuerierWrapper, StdResult, Storage, Timestamp, WasmMsg,
+  to_binary, Addr, Binary, Empty, QuerierWrapper, StdError, StdResult, Storage, Timestamp, WasmMsg,
 };
 
 use crate::{
   loader::RepositoryStateLoader,
-  models::{AddressTag, ContractID, IndexSlotValue, RelationshipUpdates, Slot, TagUpdates},
-  msg::{ExecuteMsg, QueryMsg, Since, Target},
+  models::{
+    AddressTag, ContractID, IndexBounds, IndexSlotValue, RelationshipUpdates, Slot, TagUpdates,
+  },
+  msg::{
+    CodeIdHistoryResponse, ContractStateEnvelope, CountResponse, ExecuteMsg, QueryMsg,
+    ReadResponse, SelectResponse, Since, Target, ValuesResponse,
+  },
 };
 
+/// Upper limit on the number of pages fetched by Repository::read_all
+pub const MAX_READ_ALL_PAGES: usize = 100;
+
+/// Page size used by helpers that read every matching contract
+pub const READ_ALL_PAGE_SIZE: u32 = 50;
+
 #[derive(Clone)]
 pub struct Repository {
   pub contract_addr: Addr,
@@ -56,6 +69,171 @@ impl Repository {
         meta,
         cursor,
         wallet,
+        raw_keys: None,
+        count_only: None,
+        tiebreak_desc: None,
+        with_tags: None,
+        filter: None,
+      },
+    )
+  }
+
+  pub fn read(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    target: &Target,
+    desc: Option<bool>,
+    limit: Option<u32>,
+    include: Option<Vec<String>>,
+    since: Option<Since>,
+    meta: Option<bool>,
+    wallet: Option<Addr>,
+    cursor: Option<(String, ContractID)>,
+  ) -> StdResult<ReadResponse> {
+    querier.query_wasm_smart(
+      self.contract_addr.clone(),
+      &QueryMsg::Read {
+        target: target.clone(),
+        desc,
+        limit,
+        fields: include,
+        since,
+        meta,
+        cursor,
+        wallet,
+        raw_keys: None,
+        count_only: None,
+        tiebreak_desc: None,
+        with_tags: None,
+        filter: None,
+      },
+    )
+  }
+
+  /// Read every contract matching the target, following the cursor from page
+  /// to page until there are no more. Fails rather than returning a partial
+  /// result if there are more than MAX_READ_ALL_PAGES pages.
+  pub fn read_all(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    target: &Target,
+    page_size: u32,
+  ) -> StdResult<Vec<ContractStateEnvelope>> {
+    let mut envelopes: Vec<ContractStateEnvelope> = vec![];
+    let mut cursor: Option<(String, ContractID)> = None;
+
+    for _ in 0..MAX_READ_ALL_PAGES {
+      let page = self.read(
+        querier,
+        target,
+        None,
+        Some(page_size),
+        None,
+        None,
+        Some(true),
+        None,
+        cursor,
+      )?;
+      let is_empty = page.page.is_empty();
+      envelopes.extend(page.page);
+      if is_empty || page.cursor.is_none() {
+        return Ok(envelopes);
+      }
+      cursor = page.cursor;
+    }
+
+    Err(StdError::generic_err(format!(
+      "more than {} pages to read",
+      MAX_READ_ALL_PAGES
+    )))
+  }
+
+  /// Read every contract created in the given block, ordered by ID. Like
+  /// read_all, fails if they span more than MAX_READ_ALL_PAGES pages.
+  pub fn created_in_block(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    height: u64,
+  ) -> StdResult<Vec<ContractStateEnvelope>> {
+    self.read_all(
+      querier,
+      &Target::Index(IndexBounds::Height {
+        between: None,
+        equals: Some(height.into()),
+      }),
+      READ_ALL_PAGE_SIZE,
+    )
+  }
+
+  /// Read the "hottest" contracts, i.e. those that have been updated at least
+  /// `min_rev` times, ordered from most to least revised.
+  pub fn select_by_rev(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    min_rev: u64,
+    limit: Option<u32>,
+    include: Option<Vec<String>>,
+    meta: Option<bool>,
+    wallet: Option<Addr>,
+    cursor: Option<(String, ContractID)>,
+  ) -> StdResult<ReadResponse> {
+    self.read(
+      querier,
+      &Target::Index(IndexBounds::Rev {
+        between: Some((Some(min_rev.into()), None)),
+        equals: None,
+      }),
+      Some(true),
+      limit,
+      include,
+      None,
+      meta,
+      wallet,
+      cursor,
+    )
+  }
+
+  pub fn select_config(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    fields: Option<Vec<String>>,
+    wallet: Option<Addr>,
+  ) -> StdResult<SelectResponse> {
+    querier.query_wasm_smart(
+      self.contract_addr.clone(),
+      &QueryMsg::Select { wallet, fields },
+    )
+  }
+
+  pub fn count(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+  ) -> StdResult<CountResponse> {
+    querier.query_wasm_smart(self.contract_addr.clone(), &QueryMsg::Count {})
+  }
+
+  pub fn values(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    contract_addr: &Addr,
+  ) -> StdResult<ValuesResponse> {
+    querier.query_wasm_smart(
+      self.contract_addr.clone(),
+      &QueryMsg::Values {
+        contract_addr: contract_addr.clone(),
+      },
+    )
+  }
+
+  pub fn code_id_history(
+    &self,
+    querier: &QuerierWrapper<Empty>,
+    contract_addr: &Addr,
+  ) -> StdResult<CodeIdHistoryResponse> {
+    querier.query_wasm_smart(
+      self.contract_addr.clone(),
+      &QueryMsg::CodeIdHistory {
+        contract_addr: contract_addr.clone(),
       },
     )
   }
@@ -69,6 +247,7 @@ pub struct UpdateBuilder {
   rels_to_remove: HashSet<AddressTag>,
   tags_to_add: HashSet<String>,
   tags_to_remove: HashSet<String>,
+  code_id: Option<u64>,
 }
 
 impl UpdateBuilder {
@@ -80,15 +259,28 @@ impl UpdateBuilder {
       tags_to_remove: HashSet::new(),
       rels_to_add: HashSet::new(),
       rels_to_remove: HashSet::new(),
+      code_id: None,
     }
   }
 
+  /// Report the contract's effective code ID, e.g. when it's a proxy.
+  pub fn set_code_id(
+    mut self,
+    code_id: u64,
+  ) -> Self {
+    self.code_id = Some(code_id);
+    self
+  }
+
   pub fn set_u64(
     mut self,
     slot: Slot,
     value: u64,
   ) -> Self {
-    self.values.push(IndexSlotValue::Uint64 { slot, value });
+    self.values.push(IndexSlotValue::Uint64 {
+      slot,
+      value: value.into(),
+    });
     self
   }
 
@@ -97,7 +289,10 @@ impl UpdateBuilder {
     slot: Slot,
     value: u128,
   ) -> Self {
-    self.values.push(IndexSlotValue::Uint128 { slot, value });
+    self.values.push(IndexSlotValue::Uint128 {
+      slot,
+      value: value.into(),
+    });
     self
   }
 
@@ -281,6 +476,7 @@ impl UpdateBuilder {
         relationships,
         tags,
         values,
+        code_id: self.code_id,
       })?,
     })
   }
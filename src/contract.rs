@@ -1,15 +1,14 @@
 use crate::error::ContractError;
-use crate::models::ContractMetadata;
+use crate::models::{ContractID, ReplyKind};
 use crate::msg::QueryMsg;
-use crate::msg::{ExecuteMsg, InstantiateMsg};
-use crate::state::{
-  self, ADDR_2_ID, ID_2_ADDR, IX_CODE_ID, IX_CREATED_AT, IX_HEIGHT, IX_REV, IX_UPDATED_AT, METADATA,
-};
+use crate::msg::{CreateReplyData, ExecuteMsg, InstantiateMsg, MigrateMsg};
+use crate::state::{self, set_code_id, ID_2_ADDR, METADATA, PENDING_CREATES, PENDING_MIGRATIONS};
 use crate::{execute, query};
 use cosmwasm_std::{
-  entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response
+  attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 const CONTRACT_NAME: &str = "cw-repo";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -44,6 +43,11 @@ pub fn execute(
       save_as,
       tags,
       relationships,
+      self_relationships,
+      counts_toward_total,
+      verify_interface,
+      reply_payload,
+      salt,
     } => execute::create_from_preset(
       deps,
       env,
@@ -57,36 +61,109 @@ pub fn execute(
       save_as,
       tags,
       relationships,
+      self_relationships,
+      counts_toward_total,
+      verify_interface,
+      reply_payload,
+      salt,
     ),
     ExecuteMsg::Update {
       values,
       relationships,
       tags,
-    } => execute::update(deps, env, info, values, relationships, tags),
-    ExecuteMsg::RenameIndex { name } => execute::rename_index(deps, env, info, name),
-    ExecuteMsg::Remove { contract_addr } => execute::remove(deps, env, info, &contract_addr),
-    ExecuteMsg::SetAcl { acl_contract_addr } => {
-      execute::set_acl(deps, env, info, &acl_contract_addr)
-    },
-    ExecuteMsg::UpdateAllowedCodeIds { code_ids } => {
-      execute::update_allowed_code_ids(deps, env, info, code_ids)
-    },
+      code_id,
+    } => execute::update(deps, env, info, values, relationships, tags, code_id),
+    ExecuteMsg::Admin(msg) => execute::admin(deps, env, info, msg),
     ExecuteMsg::RemovePreset {
       preset: preset_name,
     } => execute::remove_preset(deps, env, info, &preset_name),
+    ExecuteMsg::RemovePresets { names } => execute::remove_presets(deps, env, info, &names),
+    ExecuteMsg::ClearPresets {} => execute::clear_presets(deps, env, info),
+    ExecuteMsg::Deregister {} => execute::deregister(deps, env, info),
+    ExecuteMsg::SetDoc { contract_addr, doc } => {
+      execute::set_doc(deps, env, info, &contract_addr, &doc)
+    },
   }
 }
 
 #[entry_point]
 pub fn query(
   deps: Deps,
-  _env: Env,
+  env: Env,
   msg: QueryMsg,
 ) -> Result<Binary, ContractError> {
   let result = match msg {
     QueryMsg::Select { wallet, fields } => to_binary(&query::select(deps, fields, wallet)?),
     QueryMsg::Values { contract_addr } => to_binary(&query::values(deps, &contract_addr)?),
+    QueryMsg::Record { contract_addr } => to_binary(&query::record(deps, &contract_addr)?),
+    QueryMsg::Doc { contract_addr } => to_binary(&query::doc(deps, &contract_addr)?),
+    QueryMsg::ValuesByIds { ids } => to_binary(&query::values_by_ids(deps, &ids)?),
+    QueryMsg::WalletSummary { wallet } => to_binary(&query::wallet_summary(deps, &wallet)?),
     QueryMsg::Count {} => to_binary(&query::count(deps)?),
+    QueryMsg::NextId {} => to_binary(&query::next_id(deps)?),
+    QueryMsg::Stats {} => to_binary(&query::stats(deps)?),
+    QueryMsg::AnyMatch { target } => to_binary(&query::any_match(deps, &env, &target)?),
+    QueryMsg::Schema {} => to_binary(&query::schema(deps)?),
+    QueryMsg::SlotSize { kind, slot } => to_binary(&query::slot_size(deps, kind, slot)?),
+    QueryMsg::BusiestIndices { top_n } => to_binary(&query::busiest_indices(deps, top_n)?),
+    QueryMsg::IsLabelTaken { label } => to_binary(&query::is_label_taken(deps, &label)?),
+    QueryMsg::PredictAddress { code_id, salt } => {
+      to_binary(&query::predict_address(deps, &env, code_id, &salt)?)
+    },
+    QueryMsg::Rank {
+      contract_addr,
+      kind,
+      slot,
+      desc,
+    } => to_binary(&query::rank(deps, &contract_addr, kind, slot, desc)?),
+    QueryMsg::CodeIdHistory { contract_addr } => {
+      to_binary(&query::code_id_history(deps, &contract_addr)?)
+    },
+    QueryMsg::BoolBreakdown { slot } => to_binary(&query::bool_breakdown(deps, slot)?),
+    QueryMsg::Histogram {
+      kind,
+      slot,
+      bucket_width,
+      between,
+      max_scan,
+      cursor,
+    } => to_binary(&query::histogram(
+      deps,
+      kind,
+      slot,
+      bucket_width,
+      between,
+      max_scan,
+      cursor,
+    )?),
+    QueryMsg::CheckMappings { limit, cursor } => {
+      to_binary(&query::check_mappings(deps, limit, cursor)?)
+    },
+    QueryMsg::Unset {
+      kind,
+      slot,
+      limit,
+      cursor,
+      max_scan,
+    } => to_binary(&query::unset(deps, kind, slot, limit, cursor, max_scan)?),
+    QueryMsg::Feed {
+      desc,
+      limit,
+      cursor,
+    } => to_binary(&query::feed(deps, desc, limit, cursor)?),
+    QueryMsg::AllPresets {
+      limit,
+      cursor,
+      names_only,
+    } => to_binary(&query::all_presets(deps, limit, cursor, names_only)?),
+    QueryMsg::ReadUnion {
+      targets,
+      desc,
+      limit,
+      cursor,
+    } => to_binary(&query::read_union(
+      deps, &env, &targets, desc, limit, cursor,
+    )?),
     QueryMsg::Read {
       target,
       cursor,
@@ -96,8 +173,27 @@ pub fn query(
       since,
       meta,
       wallet,
+      raw_keys,
+      count_only,
+      tiebreak_desc,
+      with_tags,
+      filter,
     } => to_binary(&query::read(
-      deps, &target, desc, limit, fields, since, meta, wallet, cursor,
+      deps,
+      &env,
+      &target,
+      desc,
+      limit,
+      fields,
+      since,
+      meta,
+      wallet,
+      cursor,
+      raw_keys,
+      count_only,
+      tiebreak_desc,
+      with_tags,
+      filter,
     )?),
   }?;
   Ok(result)
@@ -108,80 +204,147 @@ pub fn reply(
   deps: DepsMut,
   env: Env,
   reply: Reply,
+) -> Result<Response, ContractError> {
+  match ReplyKind::decode(reply.id) {
+    Some((ReplyKind::Create, contract_id)) => create_reply(deps, env, contract_id, &reply),
+    Some((ReplyKind::Migrate, contract_id)) => migrate_reply(deps, env, contract_id),
+    _ => Err(ContractError::UnknownReplyId { id: reply.id }),
+  }
+}
+
+/// Register a contract instantiated by create, now that its address is known.
+fn create_reply(
+  mut deps: DepsMut,
+  env: Env,
+  contract_id: ContractID,
+  reply: &Reply,
 ) -> Result<Response, ContractError> {
   match &reply.result {
     cosmwasm_std::SubMsgResult::Ok(subcall_resp) => {
-      let contract_id = reply.id;
-      if let Some(e) = subcall_resp.events.iter().find(|e| e.ty == "instantiate") {
-        if let Some(attr) = e
-          .attributes
-          .iter()
-          .find(|attr| attr.key == "_contract_address")
-        {
-          let contract_addr = Addr::unchecked(attr.value.to_string());
-          let rev: u64 = 0;
-
-          if METADATA.has(deps.storage, contract_addr.clone()) {
-            return Ok(Response::default());
-          }
-
-          let mut contract_code_id = 0u64;
-
-          if let Some(attr) = e.attributes.iter().find(|attr| attr.key == "code_id") {
-            if let Ok(code_id) = u64::from_str_radix(&attr.value, 10) {
-              IX_CODE_ID.save(deps.storage, (code_id, contract_id), &true)?;
-              contract_code_id = code_id;
-            } else {
-              return Err(ContractError::CreateFailed {});
-            }
-          }
-
-          IX_REV.save(deps.storage, (rev, contract_id), &true)?;
-          IX_CREATED_AT.save(deps.storage, (env.block.time.nanos(), contract_id), &true)?;
-          IX_UPDATED_AT.save(deps.storage, (env.block.time.nanos(), contract_id), &true)?;
-          IX_HEIGHT.save(deps.storage, (env.block.height, contract_id), &true)?;
-
-          ID_2_ADDR.save(deps.storage, reply.id, &contract_addr)?;
-          ADDR_2_ID.save(deps.storage, contract_addr.clone(), &reply.id)?;
-
-          METADATA.save(
-            deps.storage,
-            contract_addr.clone(),
-            &ContractMetadata {
-              id: reply.id,
-              code_id: contract_code_id,
-              height: env.block.height,
-              created_at: env.block.time,
-              updated_at: env.block.time,
-              rev,
-            },
-          )?;
-
-          deps.api.debug(
-            format!(
-              "created contract: {} at time {} with id {}",
-              contract_addr, env.block.time, reply.id
-            )
-            .as_str(),
-          );
+      // a successful create must report the address of the new contract.
+      // Otherwise, we'd leave COUNT incremented with no contract recorded.
+      let e = subcall_resp
+        .events
+        .iter()
+        .find(|e| e.ty == "instantiate")
+        .ok_or(ContractError::MissingInstantiateEvent {})?;
+      let attr = e
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "_contract_address")
+        .ok_or(ContractError::MissingContractAddress {})?;
+
+      let contract_addr = Addr::unchecked(attr.value.to_string());
+
+      if METADATA.has(deps.storage, contract_addr.clone()) {
+        return Ok(Response::default());
+      }
+
+      let mut maybe_code_id: Option<u64> = None;
+
+      if let Some(attr) = e.attributes.iter().find(|attr| attr.key == "code_id") {
+        if let Ok(code_id) = u64::from_str_radix(&attr.value, 10) {
+          maybe_code_id = Some(code_id);
+        } else {
+          return Err(ContractError::InvalidCodeId {
+            value: attr.value.clone(),
+          });
         }
       }
+
+      // registering consumes the pending create, so get its payload first
+      let maybe_reply_payload = PENDING_CREATES
+        .may_load(deps.storage, contract_id)?
+        .and_then(|p| p.reply_payload);
+
+      execute::register_contract(
+        deps.branch(),
+        &env,
+        contract_id,
+        &contract_addr,
+        maybe_code_id,
+      )?;
+
+      deps.api.debug(
+        format!(
+          "created contract: {} at time {} with id {}",
+          contract_addr, env.block.time, contract_id
+        )
+        .as_str(),
+      );
+
+      // echo the caller's payload back, in case the create was itself a
+      // submsg of a contract that needs to correlate the reply.
+      return Ok(Response::new().set_data(to_binary(&CreateReplyData {
+        contract_addr,
+        payload: maybe_reply_payload,
+      })?));
     },
     cosmwasm_std::SubMsgResult::Err(err_reason) => {
+      // failing here, rather than cleaning up, reverts everything create
+      // wrote, so a failed create leaves no trace, not even in COUNT.
       deps
         .api
         .debug(format!("execute of 'create' submsg error: {}", err_reason).as_str());
       return Err(ContractError::CreateFailed {});
     },
   }
+}
+
+/// Move a managed contract to its new code ID, now that it's been migrated.
+fn migrate_reply(
+  deps: DepsMut,
+  env: Env,
+  contract_id: ContractID,
+) -> Result<Response, ContractError> {
+  let code_id = PENDING_MIGRATIONS.load(deps.storage, contract_id)?;
+  PENDING_MIGRATIONS.remove(deps.storage, contract_id);
+
+  let contract_addr = ID_2_ADDR.load(deps.storage, contract_id)?;
+  let mut meta = METADATA.load(deps.storage, contract_addr.clone())?;
+  if meta.code_id != code_id {
+    set_code_id(deps.storage, &mut meta, code_id, env.block.time)?;
+    METADATA.save(deps.storage, contract_addr, &meta)?;
+  }
+
   Ok(Response::default())
 }
 
 #[entry_point]
 pub fn migrate(
-  _deps: DepsMut,
+  deps: DepsMut,
   _env: Env,
   _msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
-  Ok(Response::default())
+  let stored = get_contract_version(deps.storage)?;
+  if stored.contract != CONTRACT_NAME {
+    return Err(ContractError::InvalidMigration {
+      msg: format!("can't migrate from contract {}", stored.contract),
+    });
+  }
+
+  // only upgrades are allowed, since older code can't be expected to read
+  // state written by newer code.
+  let parse = |v: &str| -> Result<Version, ContractError> {
+    Version::parse(v).map_err(|err| ContractError::InvalidMigration {
+      msg: err.to_string(),
+    })
+  };
+  let stored_version = parse(&stored.version)?;
+  let new_version = parse(CONTRACT_VERSION)?;
+  if stored_version > new_version {
+    return Err(ContractError::InvalidMigration {
+      msg: format!("can't downgrade from {} to {}", stored_version, new_version),
+    });
+  }
+
+  // state transforms between versions go here
+
+  set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "migrate"),
+    attr("from_version", stored.version),
+    attr("to_version", CONTRACT_VERSION),
+  ]))
 }
@@ -1,7 +1,7 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
 
-use crate::models::Slot;
+use crate::models::{IndexType, Slot};
 
 #[derive(Debug, Error)]
 pub enum ContractError {
@@ -11,11 +11,26 @@ pub enum ContractError {
   #[error("NotAuthorized")]
   NotAuthorized {},
 
+  #[error("ContractNotRegistered")]
+  ContractNotRegistered {},
+
   #[error("CreateFailed")]
   CreateFailed {},
 
+  #[error("UnknownReplyId")]
+  UnknownReplyId { id: u64 },
+
+  #[error("MissingInstantiateEvent")]
+  MissingInstantiateEvent {},
+
+  #[error("MissingContractAddress")]
+  MissingContractAddress {},
+
+  #[error("InvalidCodeId")]
+  InvalidCodeId { value: String },
+
   #[error("InvalidLabel")]
-  InvalidLabel {},
+  InvalidLabel { len: usize },
 
   #[error("NoAllowedCodeIds")]
   NoAllowedCodeIds {},
@@ -26,12 +41,18 @@ pub enum ContractError {
   #[error("NotFound")]
   NotFound {},
 
+  #[error("InvalidAclAddress")]
+  InvalidAclAddress {},
+
   #[error("AclAlreadyEnabled")]
   AclAlreadyEnabled {},
 
   #[error("PresetExists")]
   PresetExists {},
 
+  #[error("PresetNotFound")]
+  PresetNotFound { name: String },
+
   #[error("AlreadyExists")]
   AlreadyExists {},
 
@@ -41,15 +62,88 @@ pub enum ContractError {
   #[error("QueryStateError")]
   QueryStateError { msg: String },
 
-  #[error("ValidationError")]
-  ValidationError { msg: String },
+  #[error("ConflictingParams")]
+  ConflictingParams { msg: String },
+
+  #[error("ConflictingBounds")]
+  ConflictingBounds {},
+
+  #[error("InvalidSetSize")]
+  InvalidSetSize { max: u32 },
+
+  #[error("InvalidBucketWidth")]
+  InvalidBucketWidth {},
+
+  #[error("NumericIndexRequired")]
+  NumericIndexRequired {},
+
+  #[error("InvalidJson")]
+  InvalidJson { msg: String },
+
+  #[error("NotAnOrphan")]
+  NotAnOrphan { contract_id: u64 },
+
+  #[error("SameSlot")]
+  SameSlot { slot: Slot },
+
+  #[error("SlotSwapInProgress")]
+  SlotSwapInProgress {},
+
+  #[error("NoSlotSwapInProgress")]
+  NoSlotSwapInProgress {},
+
+  #[error("NoRecountInProgress")]
+  NoRecountInProgress {},
+
+  #[error("CursorMismatch")]
+  CursorMismatch {},
+
+  #[error("InvalidCursor")]
+  InvalidCursor {},
 
   #[error("LabelRequired")]
   LabelRequired {},
 
+  #[error("DocTooLarge")]
+  DocTooLarge { max: u32 },
+
+  #[error("ImplausibleTimestamp")]
+  ImplausibleTimestamp { nanos: u64 },
+
+  #[error("InvalidTag")]
+  InvalidTag { msg: String },
+
+  #[error("InterfaceUnverified")]
+  InterfaceUnverified {},
+
+  #[error("LabelTaken")]
+  LabelTaken {},
+
   #[error("InvalidIndexSlot")]
   InvalidIndexSlot {},
 
+  #[error("BatchSizeExceeded")]
+  BatchSizeExceeded { max: u32 },
+
   #[error("SlotOutOfBounds")]
   SlotOutOfBounds { slot: Slot },
+
+  #[error("IndexWriteFailed")]
+  IndexWriteFailed {
+    kind: IndexType,
+    slot: Slot,
+    msg: String,
+  },
+
+  #[error("DenomNotAccepted")]
+  DenomNotAccepted { denom: String },
+
+  #[error("InvalidMigration")]
+  InvalidMigration { msg: String },
+
+  #[error("NotContractAdmin")]
+  NotContractAdmin {},
+
+  #[error("InvalidSalt")]
+  InvalidSalt { msg: String },
 }
@@ -0,0 +1,101 @@
+use crate::{
+  error::ContractError,
+  execute::{
+    backfill_reverse_indices, batch_set_indices, clear_acl, finalize_create, migrate, purge_orphan,
+    recount_total, remove, rename_index, retag_contract, set_acl, set_children_admin,
+    set_required_denoms, set_self_heal_indices, set_slot_writer, swap_slots,
+    update_allowed_code_ids, update_contract_admin,
+  },
+  msg::AdminMsg,
+  state::is_allowed,
+};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+/// Name of the ACL action required to execute the given admin message.
+fn action(msg: &AdminMsg) -> &'static str {
+  match msg {
+    AdminMsg::BackfillReverseIndices { .. } => "backfill_reverse_indices",
+    AdminMsg::BatchSetIndices { .. } => "batch_set_indices",
+    AdminMsg::ClearAcl {} => "set_acl",
+    AdminMsg::FinalizeCreate { .. } => "finalize_create",
+    AdminMsg::Migrate { .. } => "migrate",
+    AdminMsg::PurgeOrphan { .. } => "purge_orphan",
+    AdminMsg::RecountTotal { .. } => "recount_total",
+    AdminMsg::Remove { .. } => "remove",
+    AdminMsg::RenameIndex { .. } => "rename_index",
+    AdminMsg::RetagContract { .. } => "retag_contract",
+    AdminMsg::SetAcl { .. } => "set_acl",
+    AdminMsg::SetChildrenAdmin { .. } => "set_children_admin",
+    AdminMsg::SetRequiredDenoms { .. } => "set_required_denoms",
+    AdminMsg::SetSelfHealIndices { .. } => "set_self_heal_indices",
+    AdminMsg::SetSlotWriter { .. } => "set_slot_writer",
+    AdminMsg::SwapSlots { .. } => "swap_slots",
+    AdminMsg::UpdateAllowedCodeIds { .. } => "update_allowed_code_ids",
+    AdminMsg::UpdateContractAdmin { .. } => "update_admin",
+  }
+}
+
+/// Authorize the sender for the admin action and dispatch to its handler.
+pub fn admin(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  msg: AdminMsg,
+) -> Result<Response, ContractError> {
+  // clearing the ACL is up to the creator alone, whether or not the ACL
+  // allows it, so that a misconfigured ACL can't lock the repo.
+  let is_creator_only = matches!(msg, AdminMsg::ClearAcl {});
+
+  if !is_creator_only && !is_allowed(deps.storage, &deps.querier, &info.sender, action(&msg))? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  match msg {
+    AdminMsg::BackfillReverseIndices { limit } => backfill_reverse_indices(deps, env, info, limit),
+    AdminMsg::BatchSetIndices { updates } => batch_set_indices(deps, env, info, updates),
+    AdminMsg::ClearAcl {} => clear_acl(deps, env, info),
+    AdminMsg::FinalizeCreate {
+      contract_id,
+      contract_addr,
+      code_id,
+    } => finalize_create(deps, env, info, contract_id, &contract_addr, code_id),
+    AdminMsg::Migrate {
+      contract_addr,
+      new_code_id,
+      msg,
+    } => migrate(deps, env, info, &contract_addr, new_code_id, &msg),
+    AdminMsg::PurgeOrphan { contract_id } => purge_orphan(deps, env, info, contract_id),
+    AdminMsg::RecountTotal { limit, cursor } => recount_total(deps, env, info, limit, cursor),
+    AdminMsg::Remove { contract_addr } => remove(deps, env, info, &contract_addr),
+    AdminMsg::RenameIndex { name } => rename_index(deps, env, info, name),
+    AdminMsg::RetagContract {
+      contract_addr,
+      old_tag,
+      new_tag,
+    } => retag_contract(deps, env, info, &contract_addr, &old_tag, &new_tag),
+    AdminMsg::SetAcl { acl_contract_addr } => set_acl(deps, env, info, &acl_contract_addr),
+    AdminMsg::SetChildrenAdmin {
+      contract_addrs,
+      new_admin,
+    } => set_children_admin(deps, env, info, &contract_addrs, &new_admin),
+    AdminMsg::SetRequiredDenoms { denoms } => set_required_denoms(deps, env, info, denoms),
+    AdminMsg::SetSelfHealIndices { enabled } => set_self_heal_indices(deps, env, info, enabled),
+    AdminMsg::SetSlotWriter { kind, slot, action } => {
+      set_slot_writer(deps, env, info, kind, slot, action)
+    },
+    AdminMsg::SwapSlots {
+      kind,
+      a,
+      b,
+      limit,
+      cursor,
+    } => swap_slots(deps, env, info, kind, a, b, limit, cursor),
+    AdminMsg::UpdateAllowedCodeIds { code_ids } => {
+      update_allowed_code_ids(deps, env, info, code_ids)
+    },
+    AdminMsg::UpdateContractAdmin {
+      contract_addr,
+      admin,
+    } => update_contract_admin(deps, env, info, &contract_addr, admin),
+  }
+}
@@ -0,0 +1,96 @@
+use crate::{
+  error::ContractError,
+  models::{ContractID, ReverseIndexBackfill, MAX_BATCH_SIZE},
+  state::{
+    CONTRACT_RELATIONSHIPS, CONTRACT_TAGS, RELATIONSHIPS, REL_BY_NAME, REVERSE_INDEX_BACKFILL,
+    TAGGED_CONTRACT_IDS,
+  },
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Order, Response, StdResult};
+use cw_storage_plus::Bound;
+
+/// Write the reverse index entries missing for relationships and tags made
+/// before the reverse indices existed, i.e. CONTRACT_RELATIONSHIPS and
+/// REL_BY_NAME, then CONTRACT_TAGS. Until then, such contracts are invisible
+/// to RelationshipByName reads and with_tags, and removing one leaves its
+/// relationships and tags behind, so an upgraded repo should run this before
+/// removing anything. Rows are processed in chunks, with
+/// progress saved between txs; repeat the call until it's done. Only missing
+/// entries are written, so rerunning a finished backfill changes nothing.
+pub fn backfill_reverse_indices(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  maybe_limit: Option<u32>,
+) -> Result<Response, ContractError> {
+  let limit = maybe_limit
+    .and_then(|n| Some(n as usize))
+    .unwrap_or(MAX_BATCH_SIZE)
+    .clamp(1, MAX_BATCH_SIZE);
+
+  let progress = REVERSE_INDEX_BACKFILL
+    .may_load(deps.storage)?
+    .unwrap_or(ReverseIndexBackfill::Relationships(None));
+
+  let mut n_written: usize = 0;
+  let next_progress = match progress {
+    ReverseIndexBackfill::Relationships(cursor) => {
+      let start = cursor.and_then(|key| Some(Bound::exclusive(key)));
+      let keys = RELATIONSHIPS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Addr, String, ContractID)>>>()?;
+      for (subject, name, id) in keys.iter() {
+        let reverse_key = (*id, subject.clone(), name.clone());
+        if !CONTRACT_RELATIONSHIPS.has(deps.storage, reverse_key.clone()) {
+          CONTRACT_RELATIONSHIPS.save(deps.storage, reverse_key, &true)?;
+          REL_BY_NAME.update(
+            deps.storage,
+            (name.clone(), *id),
+            |n| -> Result<u32, ContractError> { Ok(n.unwrap_or(0) + 1) },
+          )?;
+          n_written += 1;
+        }
+      }
+      if keys.len() == limit {
+        keys
+          .last()
+          .and_then(|key| Some(ReverseIndexBackfill::Relationships(Some(key.clone()))))
+      } else {
+        Some(ReverseIndexBackfill::Tags(None))
+      }
+    },
+    ReverseIndexBackfill::Tags(cursor) => {
+      let start = cursor.and_then(|key| Some(Bound::exclusive(key)));
+      let keys = TAGGED_CONTRACT_IDS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(String, ContractID)>>>()?;
+      for (tag, id) in keys.iter() {
+        if !CONTRACT_TAGS.has(deps.storage, (*id, tag.clone())) {
+          CONTRACT_TAGS.save(deps.storage, (*id, tag.clone()), &true)?;
+          n_written += 1;
+        }
+      }
+      if keys.len() == limit {
+        keys
+          .last()
+          .and_then(|key| Some(ReverseIndexBackfill::Tags(Some(key.clone()))))
+      } else {
+        None
+      }
+    },
+  };
+
+  if let Some(progress) = &next_progress {
+    REVERSE_INDEX_BACKFILL.save(deps.storage, progress)?;
+  } else {
+    REVERSE_INDEX_BACKFILL.remove(deps.storage);
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "backfill_reverse_indices"),
+    attr("written", n_written.to_string()),
+    attr("done", next_progress.is_none().to_string()),
+  ]))
+}
@@ -0,0 +1,46 @@
+use crate::{
+  error::ContractError,
+  models::{IndexSlotValue, MAX_BATCH_SIZE},
+  state::{ensure_slots_writable, get_contract_id},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+use super::update::update_contract;
+
+/// Set index values for a batch of managed contracts, as if each contract had
+/// called update with them itself.
+pub fn batch_set_indices(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  updates: Vec<(Addr, Vec<IndexSlotValue>)>,
+) -> Result<Response, ContractError> {
+  if updates.len() > MAX_BATCH_SIZE {
+    return Err(ContractError::BatchSizeExceeded {
+      max: MAX_BATCH_SIZE as u32,
+    });
+  }
+
+  let n_updated = updates.len();
+
+  for (contract_addr, values) in updates.into_iter() {
+    ensure_slots_writable(deps.storage, &deps.querier, &info.sender, &values)?;
+    let contract_id = get_contract_id(deps.storage, &contract_addr)?;
+    update_contract(
+      deps.storage,
+      deps.api,
+      &env,
+      &contract_addr,
+      contract_id,
+      Some(values),
+      None,
+      None,
+      None,
+    )?;
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "batch_set_indices"),
+    attr("updated_count", n_updated.to_string()),
+  ]))
+}
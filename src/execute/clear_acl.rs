@@ -0,0 +1,21 @@
+use crate::{
+  error::ContractError,
+  state::{ACL_CONTRACT_ADDR, CREATED_BY},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Stop using the ACL, reverting to creator-only authorization. Only the
+/// creator may do this, since afterwards no one else is authorized.
+pub fn clear_acl(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  if CREATED_BY.load(deps.storage)? != info.sender {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  ACL_CONTRACT_ADDR.save(deps.storage, &None)?;
+
+  Ok(Response::new().add_attributes(vec![attr("action", "clear_acl")]))
+}
@@ -0,0 +1,23 @@
+use crate::{error::ContractError, state::PRESETS};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Order, Response, StdResult};
+
+/// Remove every preset owned by the sender.
+pub fn clear_presets(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  let preset_names: Vec<String> = PRESETS
+    .prefix(info.sender.clone())
+    .keys(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<String>>>()?;
+
+  for preset_name in preset_names.iter() {
+    PRESETS.remove(deps.storage, (info.sender.clone(), preset_name.clone()));
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "clear_presets"),
+    attr("removed_count", preset_names.len().to_string()),
+  ]))
+}
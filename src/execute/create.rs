@@ -1,11 +1,16 @@
 use crate::{
   error::ContractError,
-  models::{AddressTag, IndexSlotValue, IndexedValues, InstantiationPreset, SLOT_COUNT},
+  models::{
+    AddressTag, IndexSlotValue, IndexType, IndexedValues, InstantiationPreset, PendingCreate,
+    ReplyKind, Slot, MAX_LABEL_LEN, SLOT_COUNT,
+  },
   state::{
-    get_bool_index, get_next_contract_id, get_text_index, get_timestamp_index, get_u128_index,
-    get_u64_index, increment_index_size, is_allowed, ALLOWED_CODE_IDS, DEFAULT_CODE_ID,
-    DEFAULT_LABEL, ID_2_INDEXED_VALUES, IX_CREATED_BY, IX_META_BOOL, IX_META_STRING,
-    IX_META_TIMESTAMP, IX_META_U128, IX_META_U64, PRESETS, RELATIONSHIPS, TAGGED_CONTRACT_IDS,
+    ensure_slots_writable, get_bool_index, get_next_contract_id, get_next_virtual_contract_id,
+    get_text_index, get_timestamp_index, get_u128_index, get_u64_index, increment_index_size,
+    is_allowed, save_relationship, save_tag, validate_tag, ADDR_2_ID, ALLOWED_CODE_IDS,
+    CONTRACT_CREATORS, CONTRACT_LABELS, DEFAULT_CODE_ID, DEFAULT_LABEL, ID_2_INDEXED_VALUES,
+    IX_CREATED_BY, IX_META_BOOL, IX_META_STRING, IX_META_TIMESTAMP, IX_META_U128, IX_META_U64,
+    LABELS, PENDING_CREATES, PRESETS, REQUIRED_DENOMS, VALIDATE_JSON,
   },
 };
 use cosmwasm_std::{
@@ -25,12 +30,24 @@ fn create(
   maybe_save_as_preset_name: Option<String>,
   maybe_tags: Option<Vec<String>>,
   maybe_address_tags: Option<Vec<AddressTag>>,
+  maybe_self_address_tags: Option<Vec<AddressTag>>,
+  maybe_counts_toward_total: Option<bool>,
+  maybe_verify_interface: Option<bool>,
+  maybe_reply_payload: Option<Binary>,
+  maybe_salt: Option<Binary>,
 ) -> Result<Response, ContractError> {
   // the signer must be authorized to this method by the ACL
   if !is_allowed(deps.storage, &deps.querier, &info.sender, "create")? {
     return Err(ContractError::NotAuthorized {});
   }
 
+  // virtual contracts don't count toward COUNT, so only those authorized by
+  // the ACL may register them.
+  let is_virtual = !maybe_counts_toward_total.unwrap_or(true);
+  if is_virtual && !is_allowed(deps.storage, &deps.querier, &info.sender, "create_virtual")? {
+    return Err(ContractError::NotAuthorized {});
+  }
+
   // use specified code ID for fall back on default
   let code_id = maybe_code_id.unwrap_or(DEFAULT_CODE_ID.load(deps.storage)?);
 
@@ -41,55 +58,110 @@ fn create(
       .debug(format!("code ID {} not allowed", code_id).as_str());
     return Err(ContractError::CodeIdNotAllowed {});
   }
+
+  // protected slots are as closed to the creator as they are to updates
+  if let Some(indices) = &maybe_indices {
+    ensure_slots_writable(deps.storage, &deps.querier, &info.sender, indices)?;
+  }
+
+  // guard against funds attached in a denom the children don't expect
+  if let Some(denoms) = REQUIRED_DENOMS.may_load(deps.storage)? {
+    if let Some(coin) = info.funds.iter().find(|c| !denoms.contains(&c.denom)) {
+      return Err(ContractError::DenomNotAccepted {
+        denom: coin.denom.clone(),
+      });
+    }
+  }
+
+  // fail early with a clear error rather than deep in the child's instantiate
+  if VALIDATE_JSON.may_load(deps.storage)?.unwrap_or(false) {
+    if let Err(err) = serde_json::from_slice::<serde_json::Value>(instantiate_msg.as_slice()) {
+      return Err(ContractError::InvalidJson {
+        msg: err.to_string(),
+      });
+    }
+  }
+
   // we use the existing count AKA size of the collection as the ID
   // of the instantiate submsg as well as for its default label, if
-  // necessary.
-  let contract_id = get_next_contract_id(deps.storage)?;
+  // necessary. Virtual contracts get an ID from a separate namespace and
+  // aren't numbered by the default label, so they need a custom one.
+  let contract_id = if is_virtual {
+    if maybe_label.is_none() {
+      return Err(ContractError::LabelRequired {});
+    }
+    get_next_virtual_contract_id(deps.storage)?
+  } else {
+    get_next_contract_id(deps.storage)?
+  };
 
   // store contract in association with the given tags
   for tag in maybe_tags.clone().unwrap_or_else(|| vec![]).iter() {
-    TAGGED_CONTRACT_IDS.save(deps.storage, (tag.clone(), contract_id), &true)?;
+    save_tag(deps.storage, tag, contract_id)?;
   }
 
   // store tagged addresses
-  for addr_tag in maybe_address_tags.unwrap_or(vec![]).iter() {
-    let key = (addr_tag.address.clone(), addr_tag.tag.clone(), contract_id);
-    RELATIONSHIPS.save(deps.storage, key, &true)?;
+  for addr_tag in maybe_address_tags.clone().unwrap_or(vec![]).iter() {
+    save_relationship(deps.storage, &addr_tag.address, &addr_tag.tag, contract_id)?;
+  }
+
+  // the new contract can only be the subject of a relationship with other
+  // contracts in the repo, written in the reply once its address is known.
+  for addr_tag in maybe_self_address_tags.clone().unwrap_or(vec![]).iter() {
+    if !ADDR_2_ID.has(deps.storage, addr_tag.address.clone()) {
+      return Err(ContractError::ContractNotRegistered {});
+    }
+    validate_tag(deps.storage, &addr_tag.tag)?;
   }
 
   IX_CREATED_BY.save(deps.storage, (info.sender.clone(), contract_id), &true)?;
+  CONTRACT_CREATORS.save(deps.storage, contract_id, &info.sender)?;
 
   // we use "keys" to keep track of which custom index keys are associated
   // with the new contract ID because we'll need this for the sake up updating
   // and removing contracts from the repo.
   let mut keys = IndexedValues::new();
 
+  // name the index that failed if a write doesn't go through, and why
+  let write_failed =
+    |kind: IndexType, slot: Slot, msg: String| ContractError::IndexWriteFailed { kind, slot, msg };
+
   // initialize custom indices
   if let Some(indices) = &maybe_indices {
     for params in indices.iter() {
       match params.clone() {
         IndexSlotValue::Uint64 { slot, value } => {
+          let value = value.u64();
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
-          increment_index_size(deps.storage, &IX_META_U64, slot)?;
-          get_u64_index(slot)?.save(deps.storage, (value, contract_id), &true)?;
+          increment_index_size(deps.storage, &IX_META_U64, slot)
+            .map_err(|err| write_failed(IndexType::Uint64, slot, err.to_string()))?;
+          get_u64_index(slot)?
+            .save(deps.storage, (value, contract_id), &true)
+            .map_err(|err| write_failed(IndexType::Uint64, slot, err.to_string()))?;
           keys.uint64[slot as usize] = Some(value);
         },
         IndexSlotValue::Timestamp { slot, value } => {
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
-          increment_index_size(deps.storage, &IX_META_TIMESTAMP, slot)?;
-          get_timestamp_index(slot)?.save(deps.storage, (value.nanos(), contract_id), &true)?;
+          increment_index_size(deps.storage, &IX_META_TIMESTAMP, slot)
+            .map_err(|err| write_failed(IndexType::Timestamp, slot, err.to_string()))?;
+          get_timestamp_index(slot)?
+            .save(deps.storage, (value.nanos(), contract_id), &true)
+            .map_err(|err| write_failed(IndexType::Timestamp, slot, err.to_string()))?;
           keys.timestamp[slot as usize] = Some(value.nanos());
         },
         IndexSlotValue::Text { slot, value } => {
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
-          increment_index_size(deps.storage, &IX_META_STRING, slot)?;
-          get_text_index(slot)?.save(deps.storage, (value.clone(), contract_id), &true)?;
+          increment_index_size(deps.storage, &IX_META_STRING, slot)
+            .map_err(|err| write_failed(IndexType::Text, slot, err.to_string()))?;
+          get_text_index(slot)?
+            .save(deps.storage, (value.clone(), contract_id), &true)
+            .map_err(|err| write_failed(IndexType::Text, slot, err.to_string()))?;
           keys.text[slot as usize] = Some(value.clone());
         },
         IndexSlotValue::Boolean { slot, value } => {
@@ -97,16 +169,23 @@ fn create(
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let u8_bool = if value { 1 } else { 0 };
-          increment_index_size(deps.storage, &IX_META_BOOL, slot)?;
-          get_bool_index(slot)?.save(deps.storage, (u8_bool, contract_id), &true)?;
+          increment_index_size(deps.storage, &IX_META_BOOL, slot)
+            .map_err(|err| write_failed(IndexType::Boolean, slot, err.to_string()))?;
+          get_bool_index(slot)?
+            .save(deps.storage, (u8_bool, contract_id), &true)
+            .map_err(|err| write_failed(IndexType::Boolean, slot, err.to_string()))?;
           keys.boolean[slot as usize] = Some(u8_bool);
         },
         IndexSlotValue::Uint128 { slot, value } => {
+          let value = value.u128();
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
-          increment_index_size(deps.storage, &IX_META_U128, slot)?;
-          get_u128_index(slot)?.save(deps.storage, (value, contract_id), &true)?;
+          increment_index_size(deps.storage, &IX_META_U128, slot)
+            .map_err(|err| write_failed(IndexType::Uint128, slot, err.to_string()))?;
+          get_u128_index(slot)?
+            .save(deps.storage, (value, contract_id), &true)
+            .map_err(|err| write_failed(IndexType::Uint128, slot, err.to_string()))?;
           keys.uint128[slot as usize] = Some(value);
         },
       }
@@ -115,21 +194,63 @@ fn create(
 
   ID_2_INDEXED_VALUES.save(deps.storage, contract_id, &keys)?;
 
-  let computed_label = build_label(deps.storage, maybe_label, contract_id)?;
+  let computed_label = build_label(deps.storage, maybe_label.clone(), contract_id)?;
+  if LABELS.has(deps.storage, computed_label.clone()) {
+    return Err(ContractError::LabelTaken {});
+  }
+  LABELS.save(deps.storage, computed_label.clone(), &contract_id)?;
+  CONTRACT_LABELS.save(deps.storage, contract_id, &computed_label)?;
   let computed_admin = maybe_admin
     .clone()
     .and_then(|addr| Some(addr.to_string()))
     .or(Some(env.contract.address.into()));
 
+  // save what the reply needs to know about the new contract
+  PENDING_CREATES.save(
+    deps.storage,
+    contract_id,
+    &PendingCreate {
+      admin: computed_admin
+        .clone()
+        .and_then(|s| Some(Addr::unchecked(s))),
+      created_by: info.sender.clone(),
+      tags: maybe_tags.clone().unwrap_or_default(),
+      relationships: maybe_address_tags.clone().unwrap_or_default(),
+      self_relationships: maybe_self_address_tags.unwrap_or_default(),
+      verify_interface: maybe_verify_interface.unwrap_or(false),
+      reply_payload: maybe_reply_payload,
+    },
+  )?;
+
   // create instantiation submsg. The instantiated contract should store the
   // sender address (of this repository contract) for it to use when calling update or
-  // other methods defined by the Repository.
-  let wasm_instantiate_msg = WasmMsg::Instantiate {
-    code_id,
-    msg: instantiate_msg.clone(),
-    funds: info.funds,
-    label: computed_label.clone(),
-    admin: computed_admin.clone(),
+  // other methods defined by the Repository. Given a salt, instantiate2 gives
+  // the contract an address callers can compute ahead of time. Either way,
+  // the reply gets the address from the instantiate event. Instantiate2
+  // needs CosmWasm 1.2 on the chain, so it's behind the cosmwasm_1_2 feature.
+  let wasm_instantiate_msg = match maybe_salt {
+    #[cfg(feature = "cosmwasm_1_2")]
+    Some(salt) => WasmMsg::Instantiate2 {
+      code_id,
+      msg: instantiate_msg.clone(),
+      funds: info.funds,
+      label: computed_label.clone(),
+      admin: computed_admin.clone(),
+      salt,
+    },
+    #[cfg(not(feature = "cosmwasm_1_2"))]
+    Some(_) => {
+      return Err(ContractError::InvalidSalt {
+        msg: "salted creates require the cosmwasm_1_2 feature".to_string(),
+      })
+    },
+    None => WasmMsg::Instantiate {
+      code_id,
+      msg: instantiate_msg.clone(),
+      funds: info.funds,
+      label: computed_label.clone(),
+      admin: computed_admin.clone(),
+    },
   };
 
   if let Some(preset_name) = &maybe_save_as_preset_name {
@@ -144,7 +265,11 @@ fn create(
             msg: instantiate_msg.clone(),
             tags: maybe_tags.clone(),
             indices: maybe_indices.clone(),
-            label: Some(computed_label.clone()),
+            // a default label is numbered per create, so only a custom
+            // label is saved. Otherwise the preset's next create would
+            // reuse this one's label.
+            label: maybe_label.clone(),
+            relationships: maybe_address_tags.clone(),
             admin: computed_admin
               .clone()
               .and_then(|s| Some(Addr::unchecked(s))),
@@ -156,6 +281,8 @@ fn create(
     )?;
   }
 
+  // everything written above is provisional. If the instantiate fails, the
+  // reply fails too, which rolls back the whole tx, COUNT included.
   Ok(
     Response::new()
       .add_attributes(vec![
@@ -164,7 +291,10 @@ fn create(
         attr("admin", computed_admin.clone().unwrap()),
         attr("label", computed_label.clone()),
       ])
-      .add_submessage(SubMsg::reply_always(wasm_instantiate_msg, contract_id)),
+      .add_submessage(SubMsg::reply_always(
+        wasm_instantiate_msg,
+        ReplyKind::Create.encode(contract_id),
+      )),
   )
 }
 
@@ -181,9 +311,18 @@ pub fn create_from_preset(
   maybe_save_as_preset_name: Option<String>,
   maybe_tags: Option<Vec<String>>,
   maybe_address_tags: Option<Vec<AddressTag>>,
+  maybe_self_address_tags: Option<Vec<AddressTag>>,
+  maybe_counts_toward_total: Option<bool>,
+  maybe_verify_interface: Option<bool>,
+  maybe_reply_payload: Option<Binary>,
+  maybe_salt: Option<Binary>,
 ) -> Result<Response, ContractError> {
   if let Some(preset_name) = maybe_preset_name {
-    let preset = PRESETS.load(deps.storage, (info.sender.clone(), preset_name.clone()))?;
+    let preset = PRESETS
+      .may_load(deps.storage, (info.sender.clone(), preset_name.clone()))?
+      .ok_or(ContractError::PresetNotFound {
+        name: preset_name.clone(),
+      })?;
     create(
       deps,
       env,
@@ -195,7 +334,12 @@ pub fn create_from_preset(
       maybe_indices.or(preset.indices),
       maybe_save_as_preset_name,
       maybe_tags,
-      maybe_address_tags,
+      merge_address_tags(preset.relationships, maybe_address_tags),
+      maybe_self_address_tags,
+      maybe_counts_toward_total,
+      maybe_verify_interface,
+      maybe_reply_payload,
+      maybe_salt,
     )
   } else {
     create(
@@ -210,24 +354,53 @@ pub fn create_from_preset(
       maybe_save_as_preset_name,
       maybe_tags,
       maybe_address_tags,
+      maybe_self_address_tags,
+      maybe_counts_toward_total,
+      maybe_verify_interface,
+      maybe_reply_payload,
+      maybe_salt,
     )
   }
 }
 
-/// Build or use default label for instantiated contract.
+/// Combine a preset's relationships with those given explicitly, skipping
+/// duplicates.
+fn merge_address_tags(
+  maybe_preset_address_tags: Option<Vec<AddressTag>>,
+  maybe_address_tags: Option<Vec<AddressTag>>,
+) -> Option<Vec<AddressTag>> {
+  if maybe_preset_address_tags.is_none() && maybe_address_tags.is_none() {
+    return None;
+  }
+  let mut merged = maybe_preset_address_tags.unwrap_or_default();
+  for addr_tag in maybe_address_tags.unwrap_or_default().into_iter() {
+    if !merged.contains(&addr_tag) {
+      merged.push(addr_tag);
+    }
+  }
+  Some(merged)
+}
+
+/// Build or use default label for instantiated contract. Numbered default
+/// labels grow with the repo, so the label is checked against the chain's
+/// max length rather than failing in the instantiate.
 fn build_label(
   storage: &dyn Storage,
   custom_label: Option<String>,
   n: u64,
 ) -> Result<String, ContractError> {
-  if let Some(label) = custom_label {
-    Ok(label)
+  let label = if let Some(label) = custom_label {
+    label
   } else {
-    let some_default_label = DEFAULT_LABEL.load(storage)?;
+    let some_default_label = DEFAULT_LABEL.may_load(storage)?.unwrap_or(None);
     if let Some(default_label) = some_default_label {
-      Ok(format!("{}-{:?}", default_label, n))
+      format!("{}-{}", default_label, n)
     } else {
-      Err(ContractError::LabelRequired {})
+      return Err(ContractError::LabelRequired {});
     }
+  };
+  if label.len() > MAX_LABEL_LEN {
+    return Err(ContractError::InvalidLabel { len: label.len() });
   }
+  Ok(label)
 }
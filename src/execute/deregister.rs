@@ -0,0 +1,31 @@
+use crate::{
+  error::ContractError,
+  state::{owns_contract, ADDR_2_ID},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use super::remove::remove_contract;
+
+/// Remove the calling contract from the repo, for a managed contract that's
+/// winding itself down.
+pub fn deregister(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+) -> Result<Response, ContractError> {
+  let contract_addr = &info.sender;
+
+  if !ADDR_2_ID.has(deps.storage, contract_addr.clone()) {
+    return Err(ContractError::ContractNotRegistered {});
+  }
+  if !owns_contract(deps.storage, contract_addr) {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  remove_contract(deps.storage, contract_addr)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "deregister"),
+    attr("removed_contract_addr", contract_addr),
+  ]))
+}
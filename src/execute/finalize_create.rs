@@ -0,0 +1,123 @@
+use crate::{
+  error::ContractError,
+  models::{ContractID, ContractMetadata},
+  query::query_smart_no_deserialize,
+  state::{
+    record_code_id, save_relationship, ADDR_2_ID, ALLOWED_CODE_IDS, ID_2_ADDR, IX_CODE_ID,
+    IX_CODE_ID_CREATED, IX_CREATED_AT, IX_HEIGHT, IX_REV, IX_UPDATED_AT, METADATA, PENDING_CREATES,
+    REJECT_UNVERIFIED, SEED_UPDATED_AT,
+  },
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+/// Register a contract whose create reply was lost, performing the same
+/// writes the reply would have made.
+pub fn finalize_create(
+  deps: DepsMut,
+  env: Env,
+  _info: MessageInfo,
+  contract_id: ContractID,
+  contract_addr: &Addr,
+  code_id: u64,
+) -> Result<Response, ContractError> {
+  let contract_addr = deps.api.addr_validate(contract_addr.as_str())?;
+
+  if ID_2_ADDR.has(deps.storage, contract_id) {
+    return Err(ContractError::NotAnOrphan { contract_id });
+  }
+  if ADDR_2_ID.has(deps.storage, contract_addr.clone()) {
+    return Err(ContractError::AlreadyExists {});
+  }
+  if !PENDING_CREATES.has(deps.storage, contract_id) {
+    return Err(ContractError::NotFound {});
+  }
+  if !ALLOWED_CODE_IDS.has(deps.storage, code_id) {
+    return Err(ContractError::CodeIdNotAllowed {});
+  }
+
+  register_contract(deps, &env, contract_id, &contract_addr, Some(code_id))?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "finalize_create"),
+    attr("contract_id", contract_id.to_string()),
+    attr("contract_addr", contract_addr),
+  ]))
+}
+
+/// Record the metadata and indices of a newly instantiated contract and
+/// consume its pending create.
+pub fn register_contract(
+  deps: DepsMut,
+  env: &Env,
+  contract_id: ContractID,
+  contract_addr: &Addr,
+  maybe_code_id: Option<u64>,
+) -> Result<(), ContractError> {
+  let rev: u64 = 0;
+
+  let pending = PENDING_CREATES.may_load(deps.storage, contract_id)?;
+  PENDING_CREATES.remove(deps.storage, contract_id);
+
+  if let Some(code_id) = maybe_code_id {
+    IX_CODE_ID.save(deps.storage, (code_id, contract_id), &true)?;
+    IX_CODE_ID_CREATED.save(
+      deps.storage,
+      (code_id, env.block.time.nanos(), contract_id),
+      &true,
+    )?;
+    record_code_id(deps.storage, contract_id, code_id, env.block.time)?;
+  }
+
+  IX_REV.save(deps.storage, (rev, contract_id), &true)?;
+  IX_CREATED_AT.save(deps.storage, (env.block.time.nanos(), contract_id), &true)?;
+  if SEED_UPDATED_AT.may_load(deps.storage)?.unwrap_or(true) {
+    IX_UPDATED_AT.save(deps.storage, (env.block.time.nanos(), contract_id), &true)?;
+  }
+  IX_HEIGHT.save(deps.storage, (env.block.height, contract_id), &true)?;
+
+  ID_2_ADDR.save(deps.storage, contract_id, contract_addr)?;
+  ADDR_2_ID.save(deps.storage, contract_addr.clone(), &contract_id)?;
+
+  // make sure the new contract answers the Select query that Read relies
+  // on, if asked to.
+  let mut interface_unverified: Option<bool> = None;
+  if pending
+    .as_ref()
+    .map(|p| p.verify_interface)
+    .unwrap_or(false)
+  {
+    let is_verified =
+      query_smart_no_deserialize(deps.api, deps.querier, contract_addr, &Some(vec![]), &None)
+        .is_ok();
+    if !is_verified && REJECT_UNVERIFIED.may_load(deps.storage)?.unwrap_or(false) {
+      return Err(ContractError::InterfaceUnverified {});
+    }
+    interface_unverified = Some(!is_verified);
+  }
+
+  METADATA.save(
+    deps.storage,
+    contract_addr.clone(),
+    &ContractMetadata {
+      id: contract_id,
+      code_id: maybe_code_id.unwrap_or(0),
+      height: env.block.height,
+      created_at: env.block.time,
+      updated_at: env.block.time,
+      rev,
+      admin: pending.clone().and_then(|p| p.admin),
+      interface_unverified,
+    },
+  )?;
+
+  // relate the new contract, as subject, to the contracts given at create
+  if let Some(pending) = &pending {
+    for rel in pending.self_relationships.iter() {
+      if let Some(id) = ADDR_2_ID.may_load(deps.storage, rel.address.clone())? {
+        save_relationship(deps.storage, contract_addr, &rel.tag, id)?;
+      }
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::{
+  error::ContractError,
+  models::ReplyKind,
+  state::{get_contract_id, ALLOWED_CODE_IDS, PENDING_MIGRATIONS},
+};
+use cosmwasm_std::{attr, Addr, Binary, DepsMut, Env, MessageInfo, Response, SubMsg, WasmMsg};
+
+/// Migrate a managed contract to a new code ID. The repo must be the
+/// contract's admin. Its code ID is updated in the reply, once the migration
+/// has succeeded.
+pub fn migrate(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  contract_addr: &Addr,
+  new_code_id: u64,
+  msg: &Binary,
+) -> Result<Response, ContractError> {
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+
+  if !ALLOWED_CODE_IDS.has(deps.storage, new_code_id) {
+    return Err(ContractError::CodeIdNotAllowed {});
+  }
+
+  PENDING_MIGRATIONS.save(deps.storage, contract_id, &new_code_id)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "migrate"),
+        attr("contract_addr", contract_addr),
+        attr("new_code_id", new_code_id.to_string()),
+      ])
+      .add_submessage(SubMsg::reply_on_success(
+        WasmMsg::Migrate {
+          contract_addr: contract_addr.to_string(),
+          new_code_id,
+          msg: msg.clone(),
+        },
+        ReplyKind::Migrate.encode(contract_id),
+      )),
+  )
+}
@@ -1,15 +1,53 @@
+mod admin;
+mod backfill_reverse_indices;
+mod batch_set_indices;
+mod clear_acl;
+mod clear_presets;
 mod create;
+mod deregister;
+mod finalize_create;
+mod migrate;
+mod purge_orphan;
+mod recount_total;
 mod remove;
 mod remove_preset;
+mod remove_presets;
 mod rename_index;
+mod retag_contract;
 mod set_acl;
+mod set_children_admin;
+mod set_doc;
+mod set_required_denoms;
+mod set_self_heal_indices;
+mod set_slot_writer;
+mod swap_slots;
 mod update;
 mod update_allowed_code_ids;
+mod update_contract_admin;
 
+pub use admin::admin;
+pub use backfill_reverse_indices::backfill_reverse_indices;
+pub use batch_set_indices::batch_set_indices;
+pub use clear_acl::clear_acl;
+pub use clear_presets::clear_presets;
 pub use create::create_from_preset;
+pub use deregister::deregister;
+pub use finalize_create::{finalize_create, register_contract};
+pub use migrate::migrate;
+pub use purge_orphan::purge_orphan;
+pub use recount_total::recount_total;
 pub use remove::remove;
 pub use remove_preset::remove_preset;
+pub use remove_presets::remove_presets;
 pub use rename_index::rename_index;
+pub use retag_contract::retag_contract;
 pub use set_acl::set_acl;
+pub use set_children_admin::set_children_admin;
+pub use set_doc::set_doc;
+pub use set_required_denoms::set_required_denoms;
+pub use set_self_heal_indices::set_self_heal_indices;
+pub use set_slot_writer::set_slot_writer;
+pub use swap_slots::swap_slots;
 pub use update::update;
 pub use update_allowed_code_ids::update_allowed_code_ids;
+pub use update_contract_admin::update_contract_admin;
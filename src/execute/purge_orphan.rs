@@ -0,0 +1,68 @@
+use crate::{
+  error::ContractError,
+  models::ContractID,
+  state::{
+    is_virtual, remove_indexed_values, remove_relationship, remove_tag, CONTRACT_CREATORS,
+    CONTRACT_LABELS, COUNT, ID_2_ADDR, ID_2_INDEXED_VALUES, IX_CREATED_BY, LABELS, PENDING_CREATES,
+    VIRTUAL_COUNT,
+  },
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Clean up the entries written by a create whose reply never registered the
+/// new contract, i.e. a contract ID with no address.
+pub fn purge_orphan(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  contract_id: ContractID,
+) -> Result<Response, ContractError> {
+  if ID_2_ADDR.has(deps.storage, contract_id) {
+    return Err(ContractError::NotAnOrphan { contract_id });
+  }
+
+  let maybe_pending = PENDING_CREATES.may_load(deps.storage, contract_id)?;
+  let maybe_values = ID_2_INDEXED_VALUES.may_load(deps.storage, contract_id)?;
+
+  if maybe_pending.is_none() && maybe_values.is_none() {
+    return Err(ContractError::NotFound {});
+  }
+
+  if let Some(pending) = maybe_pending {
+    IX_CREATED_BY.remove(deps.storage, (pending.created_by, contract_id));
+    for tag in pending.tags.iter() {
+      remove_tag(deps.storage, tag, contract_id);
+    }
+    for rel in pending.relationships.iter() {
+      remove_relationship(deps.storage, &rel.address, &rel.tag, contract_id)?;
+    }
+    PENDING_CREATES.remove(deps.storage, contract_id);
+  }
+  CONTRACT_CREATORS.remove(deps.storage, contract_id);
+
+  if let Some(values) = maybe_values {
+    remove_indexed_values(deps.storage, contract_id, &values)?;
+    ID_2_INDEXED_VALUES.remove(deps.storage, contract_id);
+  }
+
+  if let Some(label) = CONTRACT_LABELS.may_load(deps.storage, contract_id)? {
+    LABELS.remove(deps.storage, label);
+    CONTRACT_LABELS.remove(deps.storage, contract_id);
+  }
+
+  // create counted the orphan, even though it was never registered
+  if is_virtual(contract_id) {
+    VIRTUAL_COUNT.update(deps.storage, |count| -> Result<u64, ContractError> {
+      Ok(count.saturating_sub(1))
+    })?;
+  } else {
+    COUNT.update(deps.storage, |count| -> Result<u64, ContractError> {
+      Ok(count.saturating_sub(1))
+    })?;
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "purge_orphan"),
+    attr("contract_id", contract_id.to_string()),
+  ]))
+}
@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, Recount},
+  state::{COUNT, ID_2_ADDR, RECOUNT, VIRTUAL_ID_OFFSET},
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Order, Response};
+use cw_storage_plus::Bound;
+
+/// Max number of contract ID's counted per RecountTotal tx
+const MAX_RECOUNT_LIMIT: usize = 1000;
+
+/// Recompute COUNT from the contracts actually registered in ID_2_ADDR, not
+/// including virtual ones. Contracts are counted in chunks, ordered by ID, so
+/// the recount can span multiple txs. The running total is saved between txs
+/// and only written to COUNT once the last chunk is counted. Like SwapSlots,
+/// each subsequent call must pass the cursor returned by the previous one.
+/// Removing a contract the recount has already passed takes it out of the
+/// running total, so the repo needn't be idle while a recount is under way.
+pub fn recount_total(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<ContractID>,
+) -> Result<Response, ContractError> {
+  let total = if let Some(recount) = RECOUNT.may_load(deps.storage)? {
+    if recount.cursor != maybe_cursor {
+      return Err(ContractError::CursorMismatch {});
+    }
+    recount.total
+  } else if maybe_cursor.is_some() {
+    return Err(ContractError::NoRecountInProgress {});
+  } else {
+    0
+  };
+
+  let limit = maybe_limit
+    .and_then(|n| Some(n as usize))
+    .unwrap_or(MAX_RECOUNT_LIMIT)
+    .clamp(1, MAX_RECOUNT_LIMIT);
+
+  let start = maybe_cursor
+    .and_then(|id| Some(Bound::Exclusive((id, PhantomData))))
+    .or(None);
+  let stop = Some(Bound::Exclusive((VIRTUAL_ID_OFFSET, PhantomData)));
+
+  let mut n_counted: usize = 0;
+  let mut last_id: Option<ContractID> = None;
+  for result in ID_2_ADDR
+    .keys(deps.storage, start, stop, Order::Ascending)
+    .take(limit)
+  {
+    last_id = Some(result?);
+    n_counted += 1;
+  }
+
+  let total = total + n_counted as u64;
+  let next_cursor = if n_counted == limit { last_id } else { None };
+
+  if next_cursor.is_some() {
+    RECOUNT.save(
+      deps.storage,
+      &Recount {
+        total,
+        cursor: next_cursor,
+      },
+    )?;
+  } else {
+    RECOUNT.remove(deps.storage);
+    COUNT.save(deps.storage, &total)?;
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "recount_total"),
+    attr("total", total.to_string()),
+    attr(
+      "cursor",
+      next_cursor
+        .and_then(|id| Some(id.to_string()))
+        .unwrap_or_default(),
+    ),
+    attr("done", next_cursor.is_none().to_string()),
+  ]))
+}
@@ -1,14 +1,14 @@
 use crate::{
   error::ContractError,
-  models::Slot,
   state::{
-    decrement_index_size, get_bool_index, get_contract_id, get_text_index, get_timestamp_index,
-    get_u64_index, is_allowed, ADDR_2_ID, COUNT, ID_2_ADDR, ID_2_INDEXED_VALUES, IX_CODE_ID,
-    IX_CREATED_AT, IX_HEIGHT, IX_META_BOOL, IX_META_STRING, IX_META_TIMESTAMP, IX_META_U64, IX_REV,
-    IX_UPDATED_AT, METADATA,
+    get_contract_id, get_contract_relationships, get_contract_tags, is_virtual,
+    remove_indexed_values, remove_relationship, remove_tag, ADDR_2_ID, CODE_ID_HISTORY,
+    CONTRACT_CREATORS, CONTRACT_LABELS, COUNT, DOCS, ID_2_ADDR, ID_2_INDEXED_VALUES, IX_CODE_ID,
+    IX_CODE_ID_CREATED, IX_CREATED_AT, IX_CREATED_BY, IX_HEIGHT, IX_REV, IX_UPDATED_AT, LABELS,
+    METADATA, RECOUNT, VIRTUAL_COUNT,
   },
 };
-use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
 
 pub fn remove(
   deps: DepsMut,
@@ -16,58 +16,87 @@ pub fn remove(
   _info: MessageInfo,
   contract_addr: &Addr,
 ) -> Result<Response, ContractError> {
-  if !is_allowed(deps.storage, &deps.querier, contract_addr, "remove")? {
-    return Err(ContractError::NotAuthorized {});
-  }
+  remove_contract(deps.storage, contract_addr)?;
 
-  let contract_id = get_contract_id(deps.storage, contract_addr)?;
-  let meta = METADATA.load(deps.storage, contract_addr.clone())?;
-  let prefixes = ID_2_INDEXED_VALUES.load(deps.storage, contract_id)?;
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "remove"),
+    attr("removed_contract_addr", contract_addr),
+  ]))
+}
 
-  ADDR_2_ID.remove(deps.storage, contract_addr.clone());
-  ID_2_ADDR.remove(deps.storage, contract_id);
+/// Remove a contract from the repo, along with everything indexed under it.
+pub fn remove_contract(
+  storage: &mut dyn Storage,
+  contract_addr: &Addr,
+) -> Result<(), ContractError> {
+  let contract_id = get_contract_id(storage, contract_addr)?;
+  let meta = METADATA.load(storage, contract_addr.clone())?;
+  let prefixes = ID_2_INDEXED_VALUES.load(storage, contract_id)?;
 
-  for (i, some_value) in prefixes.uint64.iter().enumerate() {
-    if let Some(value) = some_value {
-      let slot = i as Slot;
-      get_u64_index(slot)?.remove(deps.storage, (*value, contract_id));
-      decrement_index_size(deps.storage, &IX_META_U64, slot)?;
-    }
+  ADDR_2_ID.remove(storage, contract_addr.clone());
+  ID_2_ADDR.remove(storage, contract_id);
+
+  // free up the contract's label for reuse
+  if let Some(label) = CONTRACT_LABELS.may_load(storage, contract_id)? {
+    LABELS.remove(storage, label);
+    CONTRACT_LABELS.remove(storage, contract_id);
   }
-  for (i, some_value) in prefixes.text.iter().enumerate() {
-    if let Some(value) = some_value {
-      let slot = i as Slot;
-      get_text_index(slot)?.remove(deps.storage, (value.clone(), contract_id));
-      decrement_index_size(deps.storage, &IX_META_STRING, slot)?;
-    }
+  METADATA.remove(storage, contract_addr.clone());
+  DOCS.remove(storage, contract_id);
+
+  for tag in get_contract_tags(storage, contract_id)?.iter() {
+    remove_tag(storage, tag, contract_id);
   }
-  for (i, some_value) in prefixes.timestamp.iter().enumerate() {
-    if let Some(value) = some_value {
-      let slot = i as Slot;
-      get_timestamp_index(slot)?.remove(deps.storage, (*value, contract_id));
-      decrement_index_size(deps.storage, &IX_META_TIMESTAMP, slot)?;
-    }
+  for rel in get_contract_relationships(storage, contract_id)?.iter() {
+    remove_relationship(storage, &rel.address, &rel.tag, contract_id)?;
   }
-  for (i, some_value) in prefixes.boolean.iter().enumerate() {
-    if let Some(value) = some_value {
-      let slot = i as Slot;
-      get_bool_index(slot)?.remove(deps.storage, (*value, contract_id));
-      decrement_index_size(deps.storage, &IX_META_BOOL, slot)?;
-    }
+
+  remove_indexed_values(storage, contract_id, &prefixes)?;
+  ID_2_INDEXED_VALUES.remove(storage, contract_id);
+
+  IX_CREATED_AT.remove(storage, (meta.created_at.nanos(), contract_id));
+  IX_UPDATED_AT.remove(storage, (meta.updated_at.nanos(), contract_id));
+  IX_REV.remove(storage, (meta.rev, contract_id));
+  IX_HEIGHT.remove(storage, (meta.height, contract_id));
+  IX_CODE_ID.remove(storage, (meta.code_id, contract_id));
+  IX_CODE_ID_CREATED.remove(
+    storage,
+    (meta.code_id, meta.created_at.nanos(), contract_id),
+  );
+
+  // contracts created before creators were recorded stay in IX_CREATED_BY
+  if let Some(creator) = CONTRACT_CREATORS.may_load(storage, contract_id)? {
+    IX_CREATED_BY.remove(storage, (creator, contract_id));
+    CONTRACT_CREATORS.remove(storage, contract_id);
   }
 
-  IX_CREATED_AT.remove(deps.storage, (meta.created_at.nanos(), contract_id));
-  IX_UPDATED_AT.remove(deps.storage, (meta.updated_at.nanos(), contract_id));
-  IX_REV.remove(deps.storage, (meta.rev, contract_id));
-  IX_HEIGHT.remove(deps.storage, (meta.height, contract_id));
-  IX_CODE_ID.remove(deps.storage, (meta.code_id, contract_id));
+  let seqs: Vec<u64> = CODE_ID_HISTORY
+    .prefix(contract_id)
+    .keys(storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<u64>>>()?;
+  for seq in seqs.iter() {
+    CODE_ID_HISTORY.remove(storage, (contract_id, *seq));
+  }
+
+  // a RecountTotal in progress has already counted contracts up to its
+  // cursor, so its running total has to drop along with the count.
+  if let Some(mut recount) = RECOUNT.may_load(storage)? {
+    if recount.cursor.map_or(false, |cursor| contract_id <= cursor) {
+      recount.total = recount.total.saturating_sub(1);
+      RECOUNT.save(storage, &recount)?;
+    }
+  }
 
-  COUNT.update(deps.storage, |count| -> Result<u64, ContractError> {
-    Ok(count - 1)
-  })?;
+  // the count may already be 0, e.g. after a RecountTotal
+  if is_virtual(contract_id) {
+    VIRTUAL_COUNT.update(storage, |count| -> Result<u64, ContractError> {
+      Ok(count.saturating_sub(1))
+    })?;
+  } else {
+    COUNT.update(storage, |count| -> Result<u64, ContractError> {
+      Ok(count.saturating_sub(1))
+    })?;
+  }
 
-  Ok(Response::new().add_attributes(vec![
-    attr("action", "remove"),
-    attr("removed_contract_addr", contract_addr),
-  ]))
+  Ok(())
 }
@@ -0,0 +1,26 @@
+use crate::{error::ContractError, state::PRESETS};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Remove the sender's presets with the given names, ignoring any that don't
+/// exist.
+pub fn remove_presets(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  preset_names: &[String],
+) -> Result<Response, ContractError> {
+  let mut removed: Vec<String> = vec![];
+
+  for preset_name in preset_names.iter() {
+    let key = (info.sender.clone(), preset_name.clone());
+    if PRESETS.has(deps.storage, key.clone()) {
+      PRESETS.remove(deps.storage, key);
+      removed.push(preset_name.clone());
+    }
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "remove_presets"),
+    attr("removed", removed.join(",")),
+  ]))
+}
@@ -1,7 +1,7 @@
 use crate::{
   error::ContractError,
   models::{IndexMetadata, IndexSlotName, Slot, SLOT_COUNT},
-  state::{is_allowed, IX_META_BOOL, IX_META_STRING, IX_META_TIMESTAMP, IX_META_U128, IX_META_U64},
+  state::{IX_META_BOOL, IX_META_STRING, IX_META_TIMESTAMP, IX_META_U128, IX_META_U64},
 };
 use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdError, Storage};
 use cw_storage_plus::Map;
@@ -9,13 +9,9 @@ use cw_storage_plus::Map;
 pub fn rename_index(
   deps: DepsMut,
   _env: Env,
-  info: MessageInfo,
+  _info: MessageInfo,
   name: IndexSlotName,
 ) -> Result<Response, ContractError> {
-  if !is_allowed(deps.storage, &deps.querier, &info.sender, "rename_index")? {
-    return Err(ContractError::NotAuthorized {});
-  }
-
   let (slot, (old_name, new_name)) = match name {
     IndexSlotName::Uint64 { slot, name } => {
       if slot >= SLOT_COUNT {
@@ -93,7 +89,7 @@ fn update_index_name<'a>(
         // should have been initialized in instantiate.
         Err(
           StdError::GenericErr {
-            msg: format!("index metadata not initialized"),
+            msg: "index metadata not initialized".to_string(),
           }
           .into(),
         )
@@ -0,0 +1,32 @@
+use crate::{
+  error::ContractError,
+  state::{get_contract_id, remove_tag, save_tag, TAGGED_CONTRACT_IDS},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
+
+/// Replace one of a contract's tags with another, e.g. to correct a typo,
+/// without requiring the contract itself to call update.
+pub fn retag_contract(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  contract_addr: &Addr,
+  old_tag: &String,
+  new_tag: &String,
+) -> Result<Response, ContractError> {
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+
+  if !TAGGED_CONTRACT_IDS.has(deps.storage, (old_tag.clone(), contract_id)) {
+    return Err(ContractError::NotFound {});
+  }
+
+  remove_tag(deps.storage, old_tag, contract_id);
+  save_tag(deps.storage, new_tag, contract_id)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "retag_contract"),
+    attr("contract_addr", contract_addr),
+    attr("old_tag", old_tag),
+    attr("new_tag", new_tag),
+  ]))
+}
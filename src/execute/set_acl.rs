@@ -1,18 +1,16 @@
 use crate::{
   error::ContractError,
-  state::{is_allowed, ACL_CONTRACT_ADDR},
+  state::{validate_acl_address, ACL_CONTRACT_ADDR},
 };
 use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response};
 
 pub fn set_acl(
   deps: DepsMut,
-  _env: Env,
-  info: MessageInfo,
+  env: Env,
+  _info: MessageInfo,
   acl_contract_addr: &Addr,
 ) -> Result<Response, ContractError> {
-  if !is_allowed(deps.storage, &deps.querier, &info.sender, "set_acl")? {
-    return Err(ContractError::NotAuthorized {});
-  }
+  validate_acl_address(deps.api, &env, acl_contract_addr)?;
 
   ACL_CONTRACT_ADDR.save(deps.storage, &Some(acl_contract_addr.clone()))?;
 
@@ -0,0 +1,54 @@
+use crate::{
+  error::ContractError,
+  models::MAX_BATCH_SIZE,
+  state::{get_contract_id, METADATA},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, WasmMsg};
+
+/// Transfer admin rights over a batch of managed contracts to a new admin.
+/// Contracts the repo doesn't currently admin are skipped.
+pub fn set_children_admin(
+  deps: DepsMut,
+  env: Env,
+  _info: MessageInfo,
+  contract_addrs: &[Addr],
+  new_admin: &Addr,
+) -> Result<Response, ContractError> {
+  if contract_addrs.len() > MAX_BATCH_SIZE {
+    return Err(ContractError::BatchSizeExceeded {
+      max: MAX_BATCH_SIZE as u32,
+    });
+  }
+
+  let new_admin = deps.api.addr_validate(new_admin.as_str())?;
+  let mut msgs: Vec<WasmMsg> = Vec::with_capacity(contract_addrs.len());
+
+  for contract_addr in contract_addrs.iter() {
+    get_contract_id(deps.storage, contract_addr)?;
+
+    let mut meta = METADATA.load(deps.storage, contract_addr.clone())?;
+    if meta.admin != Some(env.contract.address.clone()) {
+      continue;
+    }
+
+    msgs.push(WasmMsg::UpdateAdmin {
+      contract_addr: contract_addr.to_string(),
+      admin: new_admin.to_string(),
+    });
+
+    // the UpdateAdmin msgs execute atomically with this tx, so if any of them
+    // fail, so does this metadata update.
+    meta.admin = Some(new_admin.clone());
+    METADATA.save(deps.storage, contract_addr.clone(), &meta)?;
+  }
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "set_children_admin"),
+        attr("new_admin", new_admin.to_string()),
+        attr("updated_count", msgs.len().to_string()),
+      ])
+      .add_messages(msgs),
+  )
+}
@@ -0,0 +1,43 @@
+use crate::{
+  error::ContractError,
+  models::MAX_DOC_SIZE,
+  state::{get_contract_id, is_allowed, DOCS},
+};
+use cosmwasm_std::{attr, Addr, Binary, DepsMut, Env, MessageInfo, Response};
+
+/// Attach a JSON document to a contract, replacing any existing one. Either
+/// the contract itself or those authorized by the ACL may set it.
+pub fn set_doc(
+  deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  contract_addr: &Addr,
+  doc: &Binary,
+) -> Result<Response, ContractError> {
+  if info.sender != *contract_addr
+    && !is_allowed(deps.storage, &deps.querier, &info.sender, "set_doc")?
+  {
+    return Err(ContractError::NotAuthorized {});
+  }
+
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+
+  if doc.len() > MAX_DOC_SIZE {
+    return Err(ContractError::DocTooLarge {
+      max: MAX_DOC_SIZE as u32,
+    });
+  }
+  if let Err(err) = serde_json::from_slice::<serde_json::Value>(doc.as_slice()) {
+    return Err(ContractError::InvalidJson {
+      msg: err.to_string(),
+    });
+  }
+
+  DOCS.save(deps.storage, contract_id, doc)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_doc"),
+    attr("contract_addr", contract_addr),
+    attr("size", doc.len().to_string()),
+  ]))
+}
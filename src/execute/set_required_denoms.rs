@@ -0,0 +1,22 @@
+use crate::{error::ContractError, state::REQUIRED_DENOMS};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Restrict the denoms of funds sent with create, or lift the restriction if
+/// no denoms are given.
+pub fn set_required_denoms(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  maybe_denoms: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+  if let Some(denoms) = &maybe_denoms {
+    REQUIRED_DENOMS.save(deps.storage, denoms)?;
+  } else {
+    REQUIRED_DENOMS.remove(deps.storage);
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_required_denoms"),
+    attr("denoms", format!("{:?}", maybe_denoms)),
+  ]))
+}
@@ -0,0 +1,18 @@
+use crate::{error::ContractError, state::SELF_HEAL_INDICES};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+/// Turn self-healing of the updated_at and rev indices on or off, e.g. for a
+/// repo whose indices diverged from its metadata in a botched migration.
+pub fn set_self_heal_indices(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  enabled: bool,
+) -> Result<Response, ContractError> {
+  SELF_HEAL_INDICES.save(deps.storage, &enabled)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_self_heal_indices"),
+    attr("enabled", enabled.to_string()),
+  ]))
+}
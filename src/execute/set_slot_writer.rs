@@ -0,0 +1,38 @@
+use crate::{
+  error::ContractError,
+  models::{IndexType, Slot, SLOT_COUNT},
+  state::get_index_meta_map,
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response, StdError};
+
+/// Require the given ACL action of anyone writing to the index slot, the
+/// managed contract included. A None action lifts the requirement.
+pub fn set_slot_writer(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  kind: IndexType,
+  slot: Slot,
+  maybe_action: Option<String>,
+) -> Result<Response, ContractError> {
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+
+  let map = get_index_meta_map(kind);
+  let mut meta = map.may_load(deps.storage, slot)?.ok_or_else(|| {
+    // index metadata should have been initialized in instantiate.
+    StdError::GenericErr {
+      msg: "index metadata not initialized".to_string(),
+    }
+  })?;
+
+  meta.writable_by = maybe_action.clone();
+  map.save(deps.storage, slot, &meta)?;
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "set_slot_writer"),
+    attr("slot", slot.to_string()),
+    attr("writable_by", maybe_action.unwrap_or_default()),
+  ]))
+}
@@ -0,0 +1,213 @@
+use std::marker::PhantomData;
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, IndexType, IndexedValues, Slot, SlotSwap, MAX_BATCH_SIZE, SLOT_COUNT},
+  state::{
+    decrement_index_size, get_bool_index, get_index_meta_map, get_text_index, get_timestamp_index,
+    get_u128_index, get_u64_index, increment_index_size, ID_2_INDEXED_VALUES, SLOT_SWAP,
+  },
+};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+use cw_storage_plus::{Bound, Map, PrimaryKey};
+
+/// Swap the values held in two slots of the same index type for every
+/// contract in the repo, along with the slots' names. Contracts are processed
+/// in chunks, ordered by ID, so the swap can span multiple txs. Progress is
+/// saved between txs, and each subsequent call must pass the cursor returned
+/// by the previous one, so resubmitting a chunk can't swap it back.
+pub fn swap_slots(
+  deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  kind: IndexType,
+  a: Slot,
+  b: Slot,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<ContractID>,
+) -> Result<Response, ContractError> {
+  for slot in [a, b] {
+    if slot >= SLOT_COUNT {
+      return Err(ContractError::SlotOutOfBounds { slot });
+    }
+  }
+  if a == b {
+    return Err(ContractError::SameSlot { slot: a });
+  }
+
+  let meta_map = get_index_meta_map(kind);
+  if !(meta_map.has(deps.storage, a) && meta_map.has(deps.storage, b)) {
+    return Err(ContractError::InvalidIndexSlot {});
+  }
+
+  if let Some(swap) = SLOT_SWAP.may_load(deps.storage)? {
+    if swap.kind != kind || swap.a != a || swap.b != b {
+      return Err(ContractError::SlotSwapInProgress {});
+    }
+    if swap.cursor != maybe_cursor {
+      return Err(ContractError::CursorMismatch {});
+    }
+  } else if maybe_cursor.is_some() {
+    return Err(ContractError::NoSlotSwapInProgress {});
+  }
+
+  let limit = maybe_limit
+    .and_then(|n| Some(n as usize))
+    .unwrap_or(MAX_BATCH_SIZE)
+    .clamp(1, MAX_BATCH_SIZE);
+
+  let start = maybe_cursor
+    .and_then(|id| Some(Bound::Exclusive((id, PhantomData))))
+    .or(None);
+
+  let entries: Vec<(ContractID, IndexedValues)> = ID_2_INDEXED_VALUES
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .collect::<StdResult<Vec<(ContractID, IndexedValues)>>>()?;
+
+  let next_cursor = if entries.len() == limit {
+    entries.last().and_then(|(id, _)| Some(*id))
+  } else {
+    None
+  };
+
+  for (id, mut values) in entries.into_iter() {
+    let (has_a, has_b) = swap_contract_values(deps.storage, kind, a, b, id, &mut values)?;
+    if has_a && !has_b {
+      decrement_index_size(deps.storage, &meta_map, a)?;
+      increment_index_size(deps.storage, &meta_map, b)?;
+    } else if has_b && !has_a {
+      decrement_index_size(deps.storage, &meta_map, b)?;
+      increment_index_size(deps.storage, &meta_map, a)?;
+    }
+    ID_2_INDEXED_VALUES.save(deps.storage, id, &values)?;
+  }
+
+  if next_cursor.is_some() {
+    SLOT_SWAP.save(
+      deps.storage,
+      &SlotSwap {
+        kind,
+        a,
+        b,
+        cursor: next_cursor,
+      },
+    )?;
+  } else {
+    // all contracts processed, so the slot names go with their values
+    SLOT_SWAP.remove(deps.storage);
+    let mut meta_a = meta_map.load(deps.storage, a)?;
+    let mut meta_b = meta_map.load(deps.storage, b)?;
+    std::mem::swap(&mut meta_a.name, &mut meta_b.name);
+    meta_map.save(deps.storage, a, &meta_a)?;
+    meta_map.save(deps.storage, b, &meta_b)?;
+  }
+
+  Ok(Response::new().add_attributes(vec![
+    attr("action", "swap_slots"),
+    attr("a", a.to_string()),
+    attr("b", b.to_string()),
+    attr(
+      "cursor",
+      next_cursor
+        .and_then(|id| Some(id.to_string()))
+        .unwrap_or_default(),
+    ),
+    attr("done", next_cursor.is_none().to_string()),
+  ]))
+}
+
+/// Swap a single contract's values between slots a and b, returning whether
+/// the contract had a value in each slot before the swap.
+fn swap_contract_values(
+  storage: &mut dyn Storage,
+  kind: IndexType,
+  a: Slot,
+  b: Slot,
+  id: ContractID,
+  values: &mut IndexedValues,
+) -> Result<(bool, bool), ContractError> {
+  let (i, j) = (a as usize, b as usize);
+  Ok(match kind {
+    IndexType::Uint64 => {
+      let (va, vb) = (values.uint64[i], values.uint64[j]);
+      swap_keys(storage, &get_u64_index(a)?, &get_u64_index(b)?, va, vb, id)?;
+      values.uint64.swap(i, j);
+      (va.is_some(), vb.is_some())
+    },
+    IndexType::Uint128 => {
+      let (va, vb) = (values.uint128[i], values.uint128[j]);
+      swap_keys(
+        storage,
+        &get_u128_index(a)?,
+        &get_u128_index(b)?,
+        va,
+        vb,
+        id,
+      )?;
+      values.uint128.swap(i, j);
+      (va.is_some(), vb.is_some())
+    },
+    IndexType::Timestamp => {
+      let (va, vb) = (values.timestamp[i], values.timestamp[j]);
+      let (map_a, map_b) = (get_timestamp_index(a)?, get_timestamp_index(b)?);
+      swap_keys(storage, &map_a, &map_b, va, vb, id)?;
+      values.timestamp.swap(i, j);
+      (va.is_some(), vb.is_some())
+    },
+    IndexType::Text => {
+      let (va, vb) = (values.text[i].clone(), values.text[j].clone());
+      let (has_a, has_b) = (va.is_some(), vb.is_some());
+      swap_keys(
+        storage,
+        &get_text_index(a)?,
+        &get_text_index(b)?,
+        va,
+        vb,
+        id,
+      )?;
+      values.text.swap(i, j);
+      (has_a, has_b)
+    },
+    IndexType::Boolean => {
+      let (va, vb) = (values.boolean[i], values.boolean[j]);
+      swap_keys(
+        storage,
+        &get_bool_index(a)?,
+        &get_bool_index(b)?,
+        va,
+        vb,
+        id,
+      )?;
+      values.boolean.swap(i, j);
+      (va.is_some(), vb.is_some())
+    },
+  })
+}
+
+fn swap_keys<'a, K>(
+  storage: &mut dyn Storage,
+  map_a: &Map<'a, (K, ContractID), bool>,
+  map_b: &Map<'a, (K, ContractID), bool>,
+  value_a: Option<K>,
+  value_b: Option<K>,
+  id: ContractID,
+) -> Result<(), ContractError>
+where
+  K: Clone,
+  (K, ContractID): PrimaryKey<'a>,
+{
+  if let Some(x) = &value_a {
+    map_a.remove(storage, (x.clone(), id));
+  }
+  if let Some(x) = &value_b {
+    map_b.remove(storage, (x.clone(), id));
+  }
+  if let Some(x) = value_a {
+    map_b.save(storage, (x, id), &true)?;
+  }
+  if let Some(x) = value_b {
+    map_a.save(storage, (x, id), &true)?;
+  }
+  Ok(())
+}
@@ -4,15 +4,18 @@ use crate::{
     ContractID, IndexMetadata, IndexPrefix, IndexSlotValue, RelationshipUpdates, Slot, TagUpdates,
     SLOT_COUNT,
   },
+  state::{
+    ensure_slots_writable, owns_contract, IX_UPDATED_AT, SEED_UPDATED_AT, SELF_HEAL_INDICES,
+  },
   state::{
     get_bool_index, get_contract_id, get_text_index, get_timestamp_index, get_u128_index,
-    get_u64_index, increment_index_size, ID_2_INDEXED_VALUES, IX_META_BOOL, IX_META_STRING,
-    IX_META_TIMESTAMP, IX_META_U128, IX_META_U64, IX_REV, METADATA, RELATIONSHIPS,
+    get_u64_index, increment_index_size, remove_relationship, remove_tag, save_relationship,
+    save_tag, set_code_id, ADDR_2_ID, ALLOWED_CODE_IDS, ID_2_INDEXED_VALUES, IX_META_BOOL,
+    IX_META_STRING, IX_META_TIMESTAMP, IX_META_U128, IX_META_U64, IX_REV, METADATA,
     TAGGED_CONTRACT_IDS,
   },
-  state::{owns_contract, IX_UPDATED_AT},
 };
-use cosmwasm_std::{attr, Api, DepsMut, Env, MessageInfo, Response, Storage, Timestamp};
+use cosmwasm_std::{attr, Addr, Api, DepsMut, Env, MessageInfo, Response, Storage, Timestamp};
 use cw_storage_plus::Map;
 
 pub fn update(
@@ -22,9 +25,15 @@ pub fn update(
   maybe_index_updates: Option<Vec<IndexSlotValue>>,
   maybe_relationship_updates: Option<RelationshipUpdates>,
   maybe_tag_updates: Option<TagUpdates>,
+  maybe_code_id: Option<u64>,
 ) -> Result<Response, ContractError> {
   let contract_addr = &info.sender;
 
+  if !ADDR_2_ID.has(deps.storage, contract_addr.clone()) {
+    // a removed contract can no longer touch the indices.
+    return Err(ContractError::ContractNotRegistered {});
+  }
+
   if !owns_contract(deps.storage, contract_addr) {
     // this function can be executed only by a contract instantiated through
     // this repository.
@@ -35,99 +44,187 @@ pub fn update(
     .api
     .debug(format!("executing repository update for: {}", info.sender).as_str());
 
-  let contract_id = get_contract_id(deps.storage, contract_addr)?;
-
-  if let Some(rel_updates) = maybe_relationship_updates {
-    update_relationships(deps.storage, deps.api, contract_id, &rel_updates)?;
+  // some slots may only be written by those authorized by the ACL, even when
+  // it's the contract itself doing the writing.
+  if let Some(values) = &maybe_index_updates {
+    ensure_slots_writable(deps.storage, &deps.querier, contract_addr, values)?;
   }
 
-  if let Some(tag_updates) = maybe_tag_updates {
-    update_contract_tags(deps.storage, contract_id, &tag_updates)?;
-  }
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
 
-  let mut meta = METADATA.load(deps.storage, contract_addr.clone())?;
+  update_contract(
+    deps.storage,
+    deps.api,
+    &env,
+    contract_addr,
+    contract_id,
+    maybe_index_updates,
+    maybe_relationship_updates,
+    maybe_tag_updates,
+    maybe_code_id,
+  )?;
 
-  // update updated_at index
-  if IX_UPDATED_AT.has(deps.storage, (meta.updated_at.nanos(), contract_id)) {
-    IX_UPDATED_AT.remove(deps.storage, (meta.updated_at.nanos(), contract_id));
-    IX_UPDATED_AT.save(deps.storage, (env.block.time.nanos(), contract_id), &true)?;
-  } else {
-    // show never come here
-    return Err(ContractError::NotInIndex {
-      msg: format!("old value not in updated_at index"),
-    });
-  }
+  Ok(Response::new().add_attributes(vec![attr("action", "update")]))
+}
 
-  // update rev index
-  if IX_REV.has(deps.storage, (meta.rev, contract_id)) {
-    IX_REV.remove(deps.storage, (meta.rev, contract_id));
-    IX_REV.save(deps.storage, (meta.rev + 1, contract_id), &true)?;
-  } else {
-    // show never come here
-    return Err(ContractError::NotInIndex {
-      msg: format!("old value not in rev index"),
-    });
+/// Apply updates to a registered contract's indices, relationships, tags and
+/// code ID, bumping its rev and updated_at if anything actually changed.
+/// Setting a slot to the value it already holds is a no-op.
+pub fn update_contract(
+  storage: &mut dyn Storage,
+  api: &dyn Api,
+  env: &Env,
+  contract_addr: &Addr,
+  contract_id: ContractID,
+  maybe_index_updates: Option<Vec<IndexSlotValue>>,
+  maybe_relationship_updates: Option<RelationshipUpdates>,
+  maybe_tag_updates: Option<TagUpdates>,
+  maybe_code_id: Option<u64>,
+) -> Result<(), ContractError> {
+  let mut is_changed = false;
+  let mut meta = METADATA.load(storage, contract_addr.clone())?;
+
+  // a contract may report a code ID other than the one it was created with,
+  // e.g. if it's a proxy, in which case we move it in the code ID index.
+  if let Some(code_id) = maybe_code_id {
+    if code_id != meta.code_id {
+      if !ALLOWED_CODE_IDS.has(storage, code_id) {
+        return Err(ContractError::CodeIdNotAllowed {});
+      }
+      set_code_id(storage, &mut meta, code_id, env.block.time)?;
+      is_changed = true;
+    }
   }
 
-  // update managed contract metadata
-  meta.updated_at = env.block.time;
-  meta.rev += 1;
+  if let Some(rel_updates) = maybe_relationship_updates {
+    is_changed |= update_relationships(storage, api, contract_id, &rel_updates)?;
+  }
 
-  METADATA.save(deps.storage, contract_addr.clone(), &meta)?;
+  if let Some(tag_updates) = maybe_tag_updates {
+    is_changed |= update_contract_tags(storage, contract_id, &tag_updates)?;
+  }
 
   // update other indices
   if let Some(updates) = maybe_index_updates {
-    let mut ix_keys = ID_2_INDEXED_VALUES.load(deps.storage, contract_id)?;
+    let mut ix_keys = ID_2_INDEXED_VALUES.load(storage, contract_id)?;
 
     for u in updates.iter() {
       match u.clone() {
         IndexSlotValue::Uint64 { slot, value } => {
+          let value = value.u64();
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let old_val = ix_keys.uint64[slot as usize];
-          update_u64_index(deps.storage, &env, slot, old_val, value, contract_id)?;
-          ix_keys.uint64[slot as usize] = Some(value);
+          if old_val != Some(value) {
+            update_u64_index(storage, env, slot, old_val, value, contract_id)?;
+            ix_keys.uint64[slot as usize] = Some(value);
+            is_changed = true;
+          }
         },
         IndexSlotValue::Text { slot, value } => {
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let old_val = ix_keys.text[slot as usize].clone();
-          update_text_index(deps.storage, &env, slot, old_val, &value, contract_id)?;
-          ix_keys.text[slot as usize] = Some(value.clone());
+          if old_val.as_ref() != Some(&value) {
+            update_text_index(storage, env, slot, old_val, &value, contract_id)?;
+            ix_keys.text[slot as usize] = Some(value.clone());
+            is_changed = true;
+          }
         },
         IndexSlotValue::Timestamp { slot, value } => {
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let old_val = ix_keys.timestamp[slot as usize];
-          update_timestamp_index(deps.storage, &env, slot, old_val, &value, contract_id)?;
-          ix_keys.timestamp[slot as usize] = Some(value.nanos());
+          if old_val != Some(value.nanos()) {
+            update_timestamp_index(storage, env, slot, old_val, &value, contract_id)?;
+            ix_keys.timestamp[slot as usize] = Some(value.nanos());
+            is_changed = true;
+          }
         },
         IndexSlotValue::Boolean { slot, value } => {
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let old_val = ix_keys.boolean[slot as usize];
-          update_bool_index(deps.storage, &env, slot, old_val, &value, contract_id)?;
-          ix_keys.timestamp[slot as usize] = Some(if value { 1 } else { 0 });
+          if old_val != Some(if value { 1 } else { 0 }) {
+            update_bool_index(storage, env, slot, old_val, &value, contract_id)?;
+            ix_keys.boolean[slot as usize] = Some(if value { 1 } else { 0 });
+            is_changed = true;
+          }
         },
         IndexSlotValue::Uint128 { slot, value } => {
+          let value = value.u128();
           if slot >= SLOT_COUNT {
             return Err(ContractError::SlotOutOfBounds { slot });
           }
           let old_val = ix_keys.uint128[slot as usize];
-          update_u128_index(deps.storage, &env, slot, old_val, value, contract_id)?;
-          ix_keys.uint128[slot as usize] = Some(value);
+          if old_val != Some(value) {
+            update_u128_index(storage, env, slot, old_val, value, contract_id)?;
+            ix_keys.uint128[slot as usize] = Some(value);
+            is_changed = true;
+          }
         },
       }
     }
 
-    ID_2_INDEXED_VALUES.save(deps.storage, contract_id, &ix_keys)?;
+    ID_2_INDEXED_VALUES.save(storage, contract_id, &ix_keys)?;
   }
 
-  Ok(Response::new().add_attributes(vec![attr("action", "update")]))
+  if !is_changed {
+    return Ok(());
+  }
+
+  // when self-healing, a contract missing from an index it should be in is
+  // simply reinserted, so that one inconsistency can't block its updates.
+  let self_heal = SELF_HEAL_INDICES.may_load(storage)?.unwrap_or(false);
+
+  // update updated_at index. If the repo doesn't seed the updated_at index at
+  // creation, the contract's first update is what inserts it.
+  if IX_UPDATED_AT.has(storage, (meta.updated_at.nanos(), contract_id)) {
+    IX_UPDATED_AT.remove(storage, (meta.updated_at.nanos(), contract_id));
+    IX_UPDATED_AT.save(storage, (env.block.time.nanos(), contract_id), &true)?;
+  } else if meta.rev == 0 && !SEED_UPDATED_AT.may_load(storage)?.unwrap_or(true) {
+    IX_UPDATED_AT.save(storage, (env.block.time.nanos(), contract_id), &true)?;
+  } else if self_heal {
+    api.debug(
+      format!(
+        "contract {} not in updated_at index; reinserting",
+        contract_id
+      )
+      .as_str(),
+    );
+    IX_UPDATED_AT.save(storage, (env.block.time.nanos(), contract_id), &true)?;
+  } else {
+    // show never come here
+    return Err(ContractError::NotInIndex {
+      msg: "old value not in updated_at index".to_string(),
+    });
+  }
+
+  // update rev index
+  if IX_REV.has(storage, (meta.rev, contract_id)) {
+    IX_REV.remove(storage, (meta.rev, contract_id));
+    IX_REV.save(storage, (meta.rev + 1, contract_id), &true)?;
+  } else if self_heal {
+    api.debug(format!("contract {} not in rev index; reinserting", contract_id).as_str());
+    IX_REV.save(storage, (meta.rev + 1, contract_id), &true)?;
+  } else {
+    // show never come here
+    return Err(ContractError::NotInIndex {
+      msg: "old value not in rev index".to_string(),
+    });
+  }
+
+  // update managed contract metadata
+  meta.updated_at = env.block.time;
+  meta.rev += 1;
+
+  METADATA.save(storage, contract_addr.clone(), &meta)?;
+
+  Ok(())
 }
 
 fn update_index_metadata<'a>(
@@ -285,7 +382,7 @@ fn update_bool_index(
   if let Some(old_val) = some_old_val {
     map.remove(storage, (old_val, id));
   } else {
-    increment_index_size(storage, &IX_META_STRING, slot)?;
+    increment_index_size(storage, &IX_META_BOOL, slot)?;
   }
 
   let new_u8_bool = if *new_val { 1 } else { 0 };
@@ -303,36 +400,43 @@ fn update_bool_index(
   Ok(new_val.clone())
 }
 
+/// Apply tag updates, returning whether any tag was actually added or removed.
 fn update_contract_tags(
   storage: &mut dyn Storage,
   contract_id: ContractID,
   tag_updates: &TagUpdates,
-) -> Result<(), ContractError> {
+) -> Result<bool, ContractError> {
+  let mut is_changed = false;
   for tag in tag_updates.removed.as_ref().unwrap_or(&vec![]).iter() {
-    TAGGED_CONTRACT_IDS.remove(storage, (tag.clone(), contract_id));
+    if TAGGED_CONTRACT_IDS.has(storage, (tag.clone(), contract_id)) {
+      remove_tag(storage, tag, contract_id);
+      is_changed = true;
+    }
   }
   for tag in tag_updates.added.as_ref().unwrap_or(&vec![]).iter() {
-    TAGGED_CONTRACT_IDS.save(storage, (tag.clone(), contract_id), &true)?;
+    if !TAGGED_CONTRACT_IDS.has(storage, (tag.clone(), contract_id)) {
+      save_tag(storage, tag, contract_id)?;
+      is_changed = true;
+    }
   }
-  Ok(())
+  Ok(is_changed)
 }
 
+/// Apply relationship updates, returning whether any relationship was
+/// actually added or removed.
 fn update_relationships(
   storage: &mut dyn Storage,
   api: &dyn Api,
   contract_id: ContractID,
   rel_updates: &RelationshipUpdates,
-) -> Result<(), ContractError> {
+) -> Result<bool, ContractError> {
+  let mut is_changed = false;
   for rel in rel_updates.removed.as_ref().unwrap_or(&vec![]).iter() {
-    RELATIONSHIPS.remove(storage, (rel.address.clone(), rel.tag.clone(), contract_id))
+    is_changed |= remove_relationship(storage, &rel.address, &rel.tag, contract_id)?;
   }
   for rel in rel_updates.added.as_ref().unwrap_or(&vec![]).iter() {
     api.debug(format!("adding '{}' relationship for {:?}", rel.tag, rel.address).as_str());
-    RELATIONSHIPS.save(
-      storage,
-      (rel.address.clone(), rel.tag.clone(), contract_id),
-      &true,
-    )?;
+    is_changed |= save_relationship(storage, &rel.address, &rel.tag, contract_id)?;
   }
-  Ok(())
+  Ok(is_changed)
 }
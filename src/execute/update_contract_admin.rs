@@ -0,0 +1,58 @@
+use crate::{
+  error::ContractError,
+  state::{owns_contract, METADATA},
+};
+use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, WasmMsg};
+
+/// Hand admin rights over a managed contract to a new admin, or clear them
+/// if no admin is given. The repo must be the contract's current admin.
+pub fn update_contract_admin(
+  deps: DepsMut,
+  env: Env,
+  _info: MessageInfo,
+  contract_addr: &Addr,
+  maybe_admin: Option<Addr>,
+) -> Result<Response, ContractError> {
+  if !owns_contract(deps.storage, contract_addr) {
+    return Err(ContractError::ContractNotRegistered {});
+  }
+
+  let mut meta = METADATA.load(deps.storage, contract_addr.clone())?;
+  if meta.admin != Some(env.contract.address.clone()) {
+    return Err(ContractError::NotContractAdmin {});
+  }
+
+  let maybe_admin = match maybe_admin {
+    Some(admin) => Some(deps.api.addr_validate(admin.as_str())?),
+    None => None,
+  };
+
+  let msg = if let Some(admin) = &maybe_admin {
+    WasmMsg::UpdateAdmin {
+      contract_addr: contract_addr.to_string(),
+      admin: admin.to_string(),
+    }
+  } else {
+    WasmMsg::ClearAdmin {
+      contract_addr: contract_addr.to_string(),
+    }
+  };
+
+  // the msg executes atomically with this tx, so if it fails, so does this
+  // metadata update.
+  meta.admin = maybe_admin.clone();
+  METADATA.save(deps.storage, contract_addr.clone(), &meta)?;
+
+  Ok(
+    Response::new()
+      .add_attributes(vec![
+        attr("action", "update_contract_admin"),
+        attr("contract_addr", contract_addr),
+        attr(
+          "new_admin",
+          maybe_admin.map(|a| a.to_string()).unwrap_or_default(),
+        ),
+      ])
+      .add_message(msg),
+  )
+}
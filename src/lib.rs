@@ -12,3 +12,5 @@ pub mod msg;
 pub mod query;
 #[cfg(not(feature = "library"))]
 pub mod state;
+#[cfg(all(test, not(feature = "library")))]
+mod tests;
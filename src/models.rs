@@ -1,8 +1,20 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Binary, Timestamp};
+use cosmwasm_std::{Addr, Binary, Timestamp, Uint128, Uint64};
 
 pub const SLOT_COUNT: u8 = 5;
 
+/// Max length of a contract label accepted by the chain
+pub const MAX_LABEL_LEN: usize = 128;
+
+/// Max length of a tag or relationship name, as stored
+pub const MAX_TAG_LEN: usize = 128;
+
+/// Max size in bytes of the JSON document attached to a contract
+pub const MAX_DOC_SIZE: usize = 4096;
+
+/// Max number of contracts an admin batch operation can touch in one tx
+pub const MAX_BATCH_SIZE: usize = 50;
+
 pub type ContractID = u64;
 pub type IndexTypeCode = u8;
 pub type Slot = u8;
@@ -16,6 +28,56 @@ pub struct ContractMetadata {
   pub created_at: Timestamp,
   pub updated_at: Timestamp,
   pub rev: u64,
+  pub admin: Option<Addr>,
+  pub interface_unverified: Option<bool>,
+}
+
+/// Kind of submsg a reply is for, encoded in the upper bits of its reply ID
+#[cw_serde]
+#[derive(Copy)]
+pub enum ReplyKind {
+  Create,
+  Migrate,
+}
+
+impl ReplyKind {
+  const SHIFT: u32 = 56;
+
+  /// Build a reply ID for a submsg of this kind.
+  pub fn encode(
+    self,
+    id: u64,
+  ) -> u64 {
+    let tag: u64 = match self {
+      ReplyKind::Create => 0,
+      ReplyKind::Migrate => 1,
+    };
+    (tag << Self::SHIFT) | (id & ((1 << Self::SHIFT) - 1))
+  }
+
+  /// Split a reply ID into its kind and the ID it was built from.
+  pub fn decode(reply_id: u64) -> Option<(ReplyKind, u64)> {
+    let id = reply_id & ((1 << Self::SHIFT) - 1);
+    match reply_id >> Self::SHIFT {
+      0 => Some((ReplyKind::Create, id)),
+      1 => Some((ReplyKind::Migrate, id)),
+      _ => None,
+    }
+  }
+}
+
+/// State saved by create for use in the reply, once the address of the new
+/// contract is known. If the reply never happens, it's what lets PurgeOrphan
+/// find the entries create wrote.
+#[cw_serde]
+pub struct PendingCreate {
+  pub admin: Option<Addr>,
+  pub created_by: Addr,
+  pub tags: Vec<String>,
+  pub relationships: Vec<AddressTag>,
+  pub self_relationships: Vec<AddressTag>,
+  pub verify_interface: bool,
+  pub reply_payload: Option<Binary>,
 }
 
 #[cw_serde]
@@ -25,6 +87,8 @@ pub struct IndexMetadata {
   pub updated_at: Option<Timestamp>,
   pub updated_key: Option<(IndexPrefix, ContractID)>,
   pub size: u64,
+  /// ACL action required to write to the slot, if any
+  pub writable_by: Option<String>,
 }
 
 impl IndexMetadata {
@@ -38,6 +102,7 @@ impl IndexMetadata {
       updated_key: None,
       size: 0,
       slot,
+      writable_by: None,
     }
   }
 }
@@ -51,6 +116,7 @@ pub struct InstantiationPreset {
   pub indices: Option<Vec<IndexSlotValue>>,
   pub label: Option<String>,
   pub tags: Option<Vec<String>>,
+  pub relationships: Option<Vec<AddressTag>>,
 }
 
 #[cw_serde]
@@ -78,6 +144,40 @@ pub struct KeyValue {
   pub value: String,
 }
 
+#[cw_serde]
+#[derive(Copy)]
+pub enum IndexType {
+  Uint64,
+  Uint128,
+  Timestamp,
+  Text,
+  Boolean,
+}
+
+/// Progress of a SwapSlots operation spanning multiple txs
+#[cw_serde]
+pub struct SlotSwap {
+  pub kind: IndexType,
+  pub a: Slot,
+  pub b: Slot,
+  pub cursor: Option<ContractID>,
+}
+
+/// Progress of a BackfillReverseIndices operation spanning multiple txs. Each
+/// phase holds the last key of the source map it has backfilled.
+#[cw_serde]
+pub enum ReverseIndexBackfill {
+  Relationships(Option<(Addr, String, ContractID)>),
+  Tags(Option<(String, ContractID)>),
+}
+
+/// Progress of a RecountTotal operation spanning multiple txs
+#[cw_serde]
+pub struct Recount {
+  pub total: u64,
+  pub cursor: Option<ContractID>,
+}
+
 #[cw_serde]
 pub enum IndexPrefix {
   Uint64(u64),
@@ -89,13 +189,25 @@ pub enum IndexPrefix {
 
 #[cw_serde]
 pub enum IndexSlotValue {
-  Uint64 { slot: Slot, value: u64 },
-  Uint128 { slot: Slot, value: u128 },
+  Uint64 { slot: Slot, value: Uint64 },
+  Uint128 { slot: Slot, value: Uint128 },
   Timestamp { slot: Slot, value: Timestamp },
   Text { slot: Slot, value: String },
   Boolean { slot: Slot, value: bool },
 }
 
+impl IndexSlotValue {
+  pub fn kind_and_slot(&self) -> (IndexType, Slot) {
+    match self {
+      Self::Uint64 { slot, .. } => (IndexType::Uint64, *slot),
+      Self::Uint128 { slot, .. } => (IndexType::Uint128, *slot),
+      Self::Timestamp { slot, .. } => (IndexType::Timestamp, *slot),
+      Self::Text { slot, .. } => (IndexType::Text, *slot),
+      Self::Boolean { slot, .. } => (IndexType::Boolean, *slot),
+    }
+  }
+}
+
 #[cw_serde]
 pub enum IndexSlotName {
   Uint64 { slot: Slot, name: Option<String> },
@@ -105,6 +217,18 @@ pub enum IndexSlotName {
   Boolean { slot: Slot, name: Option<String> },
 }
 
+impl IndexSlotName {
+  pub fn kind_and_slot(&self) -> (IndexType, Slot) {
+    match self {
+      Self::Uint64 { slot, .. } => (IndexType::Uint64, *slot),
+      Self::Uint128 { slot, .. } => (IndexType::Uint128, *slot),
+      Self::Timestamp { slot, .. } => (IndexType::Timestamp, *slot),
+      Self::Text { slot, .. } => (IndexType::Text, *slot),
+      Self::Boolean { slot, .. } => (IndexType::Boolean, *slot),
+    }
+  }
+}
+
 #[cw_serde]
 pub struct IndexedValues {
   pub uint64: Vec<Option<u64>>,
@@ -115,6 +239,22 @@ pub struct IndexedValues {
 }
 
 impl IndexedValues {
+  /// Is there no value in the given slot for the given index type?
+  pub fn is_unset(
+    &self,
+    kind: IndexType,
+    slot: Slot,
+  ) -> bool {
+    let i = slot as usize;
+    match kind {
+      IndexType::Uint64 => self.uint64[i].is_none(),
+      IndexType::Uint128 => self.uint128[i].is_none(),
+      IndexType::Timestamp => self.timestamp[i].is_none(),
+      IndexType::Text => self.text[i].is_none(),
+      IndexType::Boolean => self.boolean[i].is_none(),
+    }
+  }
+
   pub fn new() -> Self {
     let mut uint64: Vec<Option<u64>> = Vec::with_capacity(SLOT_COUNT as usize);
     let mut uint128: Vec<Option<u128>> = Vec::with_capacity(SLOT_COUNT as usize);
@@ -136,17 +276,48 @@ impl IndexedValues {
   }
 }
 
+/// IndexedValues as returned by queries, with 64 and 128-bit values, and
+/// timestamps in nanoseconds, as strings that JS clients can parse exactly.
+#[cw_serde]
+pub struct IndexedValuesView {
+  pub uint64: Vec<Option<Uint64>>,
+  pub uint128: Vec<Option<Uint128>>,
+  pub text: Vec<Option<String>>,
+  pub timestamp: Vec<Option<Uint64>>,
+  pub boolean: Vec<Option<u8>>,
+}
+
+impl From<IndexedValues> for IndexedValuesView {
+  fn from(values: IndexedValues) -> Self {
+    Self {
+      uint64: values.uint64.iter().map(|x| x.map(Uint64::from)).collect(),
+      uint128: values
+        .uint128
+        .iter()
+        .map(|x| x.map(Uint128::from))
+        .collect(),
+      text: values.text,
+      timestamp: values
+        .timestamp
+        .iter()
+        .map(|x| x.map(Uint64::from))
+        .collect(),
+      boolean: values.boolean,
+    }
+  }
+}
+
 #[cw_serde]
 pub enum IndexSlotNameValue {
   Uint64 {
     slot: Slot,
     name: Option<String>,
-    value: Option<u64>,
+    value: Option<Uint64>,
   },
   Uint128 {
     slot: Slot,
     name: Option<String>,
-    value: Option<u128>,
+    value: Option<Uint128>,
   },
   Timestamp {
     slot: Slot,
@@ -165,15 +336,23 @@ pub enum IndexSlotNameValue {
   },
 }
 
+/// Bound resolved by the repo against the block time at query time
+#[cw_serde]
+pub enum RelativeBound {
+  BeforeNow,
+  AfterNow,
+}
+
 #[cw_serde]
 pub enum IndexBounds {
   CodeId {
-    between: Option<(Option<u64>, Option<u64>)>,
-    equals: Option<u64>,
+    between: Option<(Option<Uint64>, Option<Uint64>)>,
+    equals: Option<Uint64>,
+    equals_any: Option<Vec<Uint64>>,
   },
   Height {
-    between: Option<(Option<u64>, Option<u64>)>,
-    equals: Option<u64>,
+    between: Option<(Option<Uint64>, Option<Uint64>)>,
+    equals: Option<Uint64>,
   },
   Address {
     between: Option<(Option<Addr>, Option<Addr>)>,
@@ -182,6 +361,7 @@ pub enum IndexBounds {
   CreatedBy {
     between: Option<(Option<Addr>, Option<Addr>)>,
     equals: Option<Addr>,
+    equals_any: Option<Vec<Addr>>,
   },
   CreatedAt {
     between: Option<(Option<Timestamp>, Option<Timestamp>)>,
@@ -192,23 +372,25 @@ pub enum IndexBounds {
     equals: Option<Timestamp>,
   },
   Rev {
-    between: Option<(Option<u64>, Option<u64>)>,
-    equals: Option<u64>,
+    between: Option<(Option<Uint64>, Option<Uint64>)>,
+    equals: Option<Uint64>,
   },
   Uint64 {
     slot: u8,
-    between: Option<(Option<u64>, Option<u64>)>,
-    equals: Option<u64>,
+    between: Option<(Option<Uint64>, Option<Uint64>)>,
+    equals: Option<Uint64>,
   },
   Uint128 {
     slot: u8,
-    between: Option<(Option<u128>, Option<u128>)>,
-    equals: Option<u128>,
+    between: Option<(Option<Uint128>, Option<Uint128>)>,
+    equals: Option<Uint128>,
   },
   Timestamp {
     slot: u8,
     between: Option<(Option<Timestamp>, Option<Timestamp>)>,
+    /// Matches the exact nanosecond. Use between for a span of time.
     equals: Option<Timestamp>,
+    relative_to_now: Option<RelativeBound>,
   },
   Text {
     slot: u8,
@@ -1,9 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Binary, Timestamp};
+use cosmwasm_std::{Addr, Binary, Timestamp, Uint128};
 
 use crate::models::{
   AddressTag, ContractID, ContractMetadata, IndexBounds, IndexMetadataView, IndexSlotName,
-  IndexSlotValue, IndexedValues, InstantiationPreset, RelationshipUpdates, TagUpdates,
+  IndexSlotValue, IndexType, IndexedValuesView, InstantiationPreset, RelationshipUpdates, Slot,
+  TagUpdates,
 };
 
 #[cw_serde]
@@ -13,8 +14,17 @@ pub struct InstantiateMsg {
   pub default_code_id: Option<u64>,
   pub code_ids: Vec<u64>,
   pub indices: Option<Vec<IndexSlotName>>,
+  pub seed_updated_at: Option<bool>,
+  pub validate_json: Option<bool>,
+  pub reject_unverified: Option<bool>,
+  pub require_base64_tags: Option<bool>,
+  pub self_heal_indices: Option<bool>,
+  pub exclusive_slots: Option<bool>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
   Create {
@@ -27,14 +37,60 @@ pub enum ExecuteMsg {
     save_as: Option<String>,
     tags: Option<Vec<String>>,
     relationships: Option<Vec<AddressTag>>,
+    self_relationships: Option<Vec<AddressTag>>,
+    counts_toward_total: Option<bool>,
+    verify_interface: Option<bool>,
+    reply_payload: Option<Binary>,
+    /// Instantiate with this salt, giving the contract a predictable address
+    salt: Option<Binary>,
   },
   RemovePreset {
     preset: String,
   },
+  RemovePresets {
+    names: Vec<String>,
+  },
+  ClearPresets {},
+  Deregister {},
+  SetDoc {
+    contract_addr: Addr,
+    doc: Binary,
+  },
   Update {
     values: Option<Vec<IndexSlotValue>>,
     relationships: Option<RelationshipUpdates>,
     tags: Option<TagUpdates>,
+    code_id: Option<u64>,
+  },
+  Admin(AdminMsg),
+}
+
+/// Operator actions, each authorized through the ACL (or repo creator).
+#[cw_serde]
+pub enum AdminMsg {
+  BackfillReverseIndices {
+    limit: Option<u32>,
+  },
+  BatchSetIndices {
+    updates: Vec<(Addr, Vec<IndexSlotValue>)>,
+  },
+  ClearAcl {},
+  FinalizeCreate {
+    contract_id: ContractID,
+    contract_addr: Addr,
+    code_id: u64,
+  },
+  Migrate {
+    contract_addr: Addr,
+    new_code_id: u64,
+    msg: Binary,
+  },
+  PurgeOrphan {
+    contract_id: ContractID,
+  },
+  RecountTotal {
+    limit: Option<u32>,
+    cursor: Option<ContractID>,
   },
   Remove {
     contract_addr: Addr,
@@ -42,24 +98,86 @@ pub enum ExecuteMsg {
   RenameIndex {
     name: IndexSlotName,
   },
+  RetagContract {
+    contract_addr: Addr,
+    old_tag: String,
+    new_tag: String,
+  },
   SetAcl {
     acl_contract_addr: Addr,
   },
+  SetChildrenAdmin {
+    contract_addrs: Vec<Addr>,
+    new_admin: Addr,
+  },
+  SetRequiredDenoms {
+    denoms: Option<Vec<String>>,
+  },
+  SetSelfHealIndices {
+    enabled: bool,
+  },
+  SetSlotWriter {
+    kind: IndexType,
+    slot: Slot,
+    action: Option<String>,
+  },
+  SwapSlots {
+    kind: IndexType,
+    a: Slot,
+    b: Slot,
+    limit: Option<u32>,
+    cursor: Option<ContractID>,
+  },
   UpdateAllowedCodeIds {
     code_ids: Vec<u64>,
   },
+  /// Set or clear the admin of a contract the repo is currently admin of
+  UpdateContractAdmin {
+    contract_addr: Addr,
+    admin: Option<Addr>,
+  },
 }
 
 #[cw_serde]
 pub enum Target {
+  All,                                               // every contract, ordered by ID
+  CodeIdRecent { code_id: u64, desc: Option<bool> }, // by created_at, newest first by default
   Index(IndexBounds),
-  Relationship((Addr, String)), // subject addr, rel name
-  Tag(String),                  // tag associated with one or more contracts
+  RecentlyUpdated { since: Option<Since> }, // by updated_at, newest first unless desc is false
+  Relationship((Addr, String)),             // subject addr, rel name
+  RelationshipByName(String),               // rel name, across all subject addrs
+  Tag(String),                              // tag as stored, e.g. base64 if set by the client
 }
 
 #[cw_serde]
 pub enum QueryMsg {
   Count {},
+  NextId {},
+  Stats {},
+  AnyMatch {
+    target: Target,
+  },
+  Schema {},
+  BusiestIndices {
+    top_n: u32,
+  },
+  SlotSize {
+    kind: IndexType,
+    slot: Slot,
+  },
+  IsLabelTaken {
+    label: String,
+  },
+  PredictAddress {
+    code_id: u64,
+    salt: Binary,
+  },
+  Rank {
+    contract_addr: Addr,
+    kind: IndexType,
+    slot: Slot,
+    desc: Option<bool>,
+  },
   Read {
     target: Target,
     fields: Option<Vec<String>>,
@@ -69,6 +187,11 @@ pub enum QueryMsg {
     cursor: Option<(String, ContractID)>,
     meta: Option<bool>,
     wallet: Option<Addr>,
+    raw_keys: Option<bool>,
+    count_only: Option<bool>,
+    tiebreak_desc: Option<bool>,
+    with_tags: Option<bool>,
+    filter: Option<ChildFilter>,
   },
   Select {
     wallet: Option<Addr>,
@@ -77,6 +200,59 @@ pub enum QueryMsg {
   Values {
     contract_addr: Addr,
   },
+  Record {
+    contract_addr: Addr,
+  },
+  Doc {
+    contract_addr: Addr,
+  },
+  ValuesByIds {
+    ids: Vec<ContractID>,
+  },
+  WalletSummary {
+    wallet: Addr,
+  },
+  CodeIdHistory {
+    contract_addr: Addr,
+  },
+  BoolBreakdown {
+    slot: Slot,
+  },
+  Histogram {
+    kind: IndexType,
+    slot: Slot,
+    bucket_width: Uint128,
+    between: Option<(Option<Uint128>, Option<Uint128>)>,
+    max_scan: Option<u32>,
+    cursor: Option<(Uint128, ContractID)>,
+  },
+  CheckMappings {
+    limit: Option<u32>,
+    cursor: Option<ContractID>,
+  },
+  Unset {
+    kind: IndexType,
+    slot: Slot,
+    limit: Option<u32>,
+    cursor: Option<ContractID>,
+    max_scan: Option<u32>,
+  },
+  Feed {
+    desc: Option<bool>,
+    limit: Option<u32>,
+    cursor: Option<(Timestamp, ContractID)>,
+  },
+  AllPresets {
+    limit: Option<u32>,
+    cursor: Option<(Addr, String)>,
+    names_only: Option<bool>,
+  },
+  ReadUnion {
+    targets: Vec<UnionTarget>,
+    desc: Option<bool>,
+    limit: Option<u32>,
+    cursor: Option<ContractID>,
+  },
 }
 
 #[cw_serde]
@@ -88,11 +264,201 @@ pub enum Since {
 #[cw_serde]
 pub struct CountResponse {
   pub count: u64,
+  pub virtual_count: u64,
+}
+
+#[cw_serde]
+pub struct AnyMatchResponse {
+  pub exists: bool,
+}
+
+#[cw_serde]
+pub struct IsLabelTakenResponse {
+  pub is_taken: bool,
+}
+
+#[cw_serde]
+pub struct RankResponse {
+  pub rank: u64,
+  pub capped: bool,
+}
+
+/// The ID the next create will use, unless another create executes first
+#[cw_serde]
+pub struct NextIdResponse {
+  pub next_id: ContractID,
+}
+
+#[cw_serde]
+pub struct WalletSummaryResponse {
+  pub created_count: u64,
+  pub preset_count: u64,
+  pub can_create: bool,
+  pub can_remove: bool,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+  pub count: u64,
+  pub virtual_count: u64,
+  pub total_indexed: u64,
+  pub total_tags: u64,
+  pub total_relationships: u64,
+  pub total_presets: u64,
 }
 
 #[cw_serde]
 pub struct ValuesResponse {
-  pub values: IndexedValues,
+  pub values: IndexedValuesView,
+}
+
+#[cw_serde]
+pub struct SlotSchema {
+  pub slot: Slot,
+  pub name: Option<String>,
+  pub size: u64,
+}
+
+#[cw_serde]
+pub struct SchemaResponse {
+  pub uint64: Vec<SlotSchema>,
+  pub uint128: Vec<SlotSchema>,
+  pub text: Vec<SlotSchema>,
+  pub timestamp: Vec<SlotSchema>,
+  pub boolean: Vec<SlotSchema>,
+}
+
+#[cw_serde]
+pub struct IndexSize {
+  pub kind: IndexType,
+  pub slot: Slot,
+  pub name: Option<String>,
+  pub size: u64,
+}
+
+#[cw_serde]
+pub struct SlotSizeResponse {
+  pub kind: IndexType,
+  pub slot: Slot,
+  pub size: u64,
+}
+
+#[cw_serde]
+pub struct PredictAddressResponse {
+  pub address: Addr,
+}
+
+#[cw_serde]
+pub struct BusiestIndicesResponse {
+  pub indices: Vec<IndexSize>,
+}
+
+#[cw_serde]
+pub struct DocResponse {
+  pub doc: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct RecordResponse {
+  pub addr: Addr,
+  pub meta: Option<ContractMetadata>,
+  pub values: Option<IndexedValuesView>,
+  pub tags: Vec<String>,
+  pub relationships: Vec<AddressTag>,
+}
+
+#[cw_serde]
+pub struct ValuesByIdsResponse {
+  pub values: Vec<(ContractID, Option<IndexedValuesView>)>,
+}
+
+#[cw_serde]
+pub struct CodeIdHistoryResponse {
+  pub history: Vec<(u64, Timestamp)>,
+}
+
+#[cw_serde]
+pub struct HistogramResponse {
+  pub buckets: Vec<(Uint128, u64)>,
+  pub truncated: bool,
+  pub cursor: Option<(Uint128, ContractID)>,
+}
+
+/// Matches contracts whose Select response has the given value at a
+/// dot-separated path, e.g. "status.active" equals "true". The value is JSON.
+#[cw_serde]
+pub struct ChildFilter {
+  pub path: String,
+  pub equals: String,
+}
+
+/// A target whose first `limit` contracts, read in its own order, go into a
+/// ReadUnion, e.g. the most recent contracts under a tag.
+#[cw_serde]
+pub struct UnionTarget {
+  pub target: Target,
+  pub desc: Option<bool>,
+  pub limit: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ReadUnionResponse {
+  pub items: Vec<(ContractID, Addr)>,
+  pub cursor: Option<ContractID>,
+}
+
+/// Data set on the response to a create, once the new contract is registered
+#[cw_serde]
+pub struct CreateReplyData {
+  pub contract_addr: Addr,
+  pub payload: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct BoolBreakdownResponse {
+  pub true_count: u64,
+  pub false_count: u64,
+  pub truncated: bool,
+}
+
+#[cw_serde]
+pub struct FeedItem {
+  pub addr: Addr,
+  pub contract_id: ContractID,
+  pub code_id: u64,
+  pub created_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct FeedResponse {
+  pub items: Vec<FeedItem>,
+  pub cursor: Option<(Timestamp, ContractID)>,
+}
+
+#[cw_serde]
+pub struct PresetEntry {
+  pub owner: Addr,
+  pub name: String,
+  pub preset: Option<InstantiationPreset>,
+}
+
+#[cw_serde]
+pub struct AllPresetsResponse {
+  pub presets: Vec<PresetEntry>,
+  pub cursor: Option<(Addr, String)>,
+}
+
+#[cw_serde]
+pub struct CheckMappingsResponse {
+  pub mismatches: Vec<(ContractID, Addr)>,
+  pub cursor: Option<ContractID>,
+}
+
+#[cw_serde]
+pub struct UnsetResponse {
+  pub contracts: Vec<Addr>,
+  pub cursor: Option<ContractID>,
+  pub truncated: bool,
 }
 
 #[cw_serde]
@@ -107,10 +473,22 @@ pub struct SelectResponse {
   pub presets: Option<Vec<InstantiationPreset>>,
 }
 
+/// A page of contracts returned by read. Fields missing from the response
+/// decode to their defaults, so an empty result is just an empty page.
 #[cw_serde]
-pub struct Page {
-  pub page: Vec<EntityContractEnvelope>,
+#[derive(Default)]
+#[serde(default)]
+pub struct ReadResponse {
+  pub page: Vec<ContractStateEnvelope>,
+  /// Number of contracts the page covers, including any skipped by since
+  pub count: u64,
+  pub has_more: bool,
   pub cursor: Option<(String, ContractID)>,
+  pub raw_keys: Option<Vec<(String, ContractID)>>,
+  /// True if the read was made without a cursor
+  pub is_first_page: bool,
+  /// The limit the page was read with, after clamping
+  pub page_size: u32,
 }
 
 #[cw_serde]
@@ -122,8 +500,10 @@ pub enum ImplementorQueryMsg {
 }
 
 #[cw_serde]
-pub struct EntityContractEnvelope {
+pub struct ContractStateEnvelope {
   pub address: Addr,
   pub meta: Option<ContractMetadata>,
+  pub rev: Option<u64>,
   pub state: Option<Binary>,
+  pub tags: Option<Vec<String>>,
 }
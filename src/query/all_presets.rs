@@ -0,0 +1,59 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Addr, Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::InstantiationPreset,
+  msg::{AllPresetsResponse, PresetEntry},
+  state::PRESETS,
+};
+
+use super::read::{DEFAULT_LIMIT, MAX_LIMIT, MIN_LIMIT};
+
+/// Page through the presets of every wallet, ordered by owner and name, for
+/// auditing the templates saved in the repo. With names_only, each preset is
+/// omitted to keep pages small.
+pub fn all_presets(
+  deps: Deps,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<(Addr, String)>,
+  maybe_names_only: Option<bool>,
+) -> Result<AllPresetsResponse, ContractError> {
+  let limit = maybe_limit
+    .unwrap_or(DEFAULT_LIMIT)
+    .clamp(MIN_LIMIT, MAX_LIMIT) as usize;
+
+  let names_only = maybe_names_only.unwrap_or(false);
+  let start = maybe_cursor
+    .and_then(|key| Some(Bound::Exclusive((key, PhantomData))))
+    .or(None);
+
+  // fetch one more entry than the limit to tell whether there's another page
+  let entries = PRESETS
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit + 1)
+    .collect::<StdResult<Vec<((Addr, String), InstantiationPreset)>>>()?;
+
+  let has_more = entries.len() > limit;
+  let presets: Vec<PresetEntry> = entries
+    .into_iter()
+    .take(limit)
+    .map(|((owner, name), preset)| PresetEntry {
+      owner,
+      name,
+      preset: if names_only { None } else { Some(preset) },
+    })
+    .collect();
+
+  let cursor = if has_more {
+    presets
+      .last()
+      .and_then(|entry| Some((entry.owner.clone(), entry.name.clone())))
+  } else {
+    None
+  };
+
+  Ok(AllPresetsResponse { presets, cursor })
+}
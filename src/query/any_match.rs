@@ -0,0 +1,59 @@
+use cosmwasm_std::{Deps, Env, Order};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::ContractID,
+  msg::{AnyMatchResponse, Since, Target},
+  state::IX_REV,
+};
+
+use super::read::read;
+
+/// Does any contract match the target? This reads at most one index key,
+/// without resolving its contract's address.
+pub fn any_match(
+  deps: Deps,
+  env: &Env,
+  target: &Target,
+) -> Result<AnyMatchResponse, ContractError> {
+  // a rev floor filters contracts by their metadata, which a raw keys read
+  // can't do, so look for a contract past the floor in the rev index instead.
+  if let Target::RecentlyUpdated {
+    since: Some(Since::Rev(rev)),
+  } = target
+  {
+    let exists = IX_REV
+      .keys(
+        deps.storage,
+        Some(Bound::exclusive((*rev, ContractID::MAX))),
+        None,
+        Order::Ascending,
+      )
+      .next()
+      .transpose()?
+      .is_some();
+    return Ok(AnyMatchResponse { exists });
+  }
+
+  let page = read(
+    deps,
+    env,
+    target,
+    None,
+    Some(1),
+    None,
+    None,
+    None,
+    None,
+    None,
+    Some(true),
+    None,
+    None,
+    None,
+    None,
+  )?;
+  Ok(AnyMatchResponse {
+    exists: page.count > 0,
+  })
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::{Deps, Order};
+
+use crate::{
+  error::ContractError,
+  models::{Slot, SLOT_COUNT},
+  msg::BoolBreakdownResponse,
+  state::{get_bool_index, BoolIndexMap},
+};
+
+use super::read::scan_budget;
+
+/// Count the contracts with a true and a false value in a boolean slot. Each
+/// count stops at MAX_SCAN, in which case the response is truncated.
+pub fn bool_breakdown(
+  deps: Deps,
+  slot: Slot,
+) -> Result<BoolBreakdownResponse, ContractError> {
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+
+  let map = get_bool_index(slot)?;
+  let max_scan = scan_budget(None) as usize;
+  let (true_count, is_true_truncated) = count_prefix(deps, &map, 1, max_scan);
+  let (false_count, is_false_truncated) = count_prefix(deps, &map, 0, max_scan);
+
+  Ok(BoolBreakdownResponse {
+    true_count,
+    false_count,
+    truncated: is_true_truncated || is_false_truncated,
+  })
+}
+
+fn count_prefix(
+  deps: Deps,
+  map: &BoolIndexMap,
+  prefix: u8,
+  max_scan: usize,
+) -> (u64, bool) {
+  let n = map
+    .prefix(prefix)
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(max_scan + 1)
+    .count();
+  if n > max_scan {
+    (max_scan as u64, true)
+  } else {
+    (n as u64, false)
+  }
+}
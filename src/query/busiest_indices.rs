@@ -0,0 +1,41 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+
+use crate::{
+  error::ContractError,
+  models::{IndexMetadata, IndexType, Slot},
+  msg::{BusiestIndicesResponse, IndexSize},
+  state::get_index_meta_map,
+};
+
+/// Return the N largest index slots across all index types, by size.
+pub fn busiest_indices(
+  deps: Deps,
+  top_n: u32,
+) -> Result<BusiestIndicesResponse, ContractError> {
+  let mut indices: Vec<IndexSize> = vec![];
+
+  for kind in [
+    IndexType::Uint64,
+    IndexType::Uint128,
+    IndexType::Text,
+    IndexType::Timestamp,
+    IndexType::Boolean,
+  ] {
+    for (slot, meta) in get_index_meta_map(kind)
+      .range(deps.storage, None, None, Order::Ascending)
+      .collect::<StdResult<Vec<(Slot, IndexMetadata)>>>()?
+    {
+      indices.push(IndexSize {
+        kind,
+        slot,
+        name: meta.name,
+        size: meta.size,
+      });
+    }
+  }
+
+  indices.sort_by(|a, b| b.size.cmp(&a.size));
+  indices.truncate(top_n as usize);
+
+  Ok(BusiestIndicesResponse { indices })
+}
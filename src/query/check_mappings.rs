@@ -0,0 +1,51 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Addr, Deps, Order};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::ContractID,
+  msg::CheckMappingsResponse,
+  state::{ADDR_2_ID, ID_2_ADDR},
+};
+
+use super::read::scan_budget;
+
+/// Check that ADDR_2_ID maps each address in ID_2_ADDR back to its ID,
+/// reporting any entry that doesn't. Checks at most `limit` entries per call,
+/// ordered by ID, so keep following the cursor until it's None.
+pub fn check_mappings(
+  deps: Deps,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<ContractID>,
+) -> Result<CheckMappingsResponse, ContractError> {
+  let limit = scan_budget(maybe_limit) as usize;
+  let start = maybe_cursor
+    .and_then(|id| Some(Bound::Exclusive((id, PhantomData))))
+    .or(None);
+
+  let mut mismatches: Vec<(ContractID, Addr)> = vec![];
+  let mut last_id: Option<ContractID> = None;
+  let mut has_more = false;
+
+  for (i, entry) in ID_2_ADDR
+    .range(deps.storage, start, None, Order::Ascending)
+    .enumerate()
+  {
+    let (id, addr) = entry?;
+    if i == limit {
+      has_more = true;
+      break;
+    }
+    if ADDR_2_ID.may_load(deps.storage, addr.clone())? != Some(id) {
+      mismatches.push((id, addr));
+    }
+    last_id = Some(id);
+  }
+
+  Ok(CheckMappingsResponse {
+    mismatches,
+    cursor: if has_more { last_id } else { None },
+  })
+}
@@ -0,0 +1,21 @@
+use cosmwasm_std::{Addr, Deps, Order, StdResult, Timestamp};
+
+use crate::{
+  error::ContractError,
+  msg::CodeIdHistoryResponse,
+  state::{get_contract_id, CODE_ID_HISTORY},
+};
+
+/// Return every code ID a contract has had, in the order they were set.
+pub fn code_id_history(
+  deps: Deps,
+  contract_addr: &Addr,
+) -> Result<CodeIdHistoryResponse, ContractError> {
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+  let history: Vec<(u64, Timestamp)> = CODE_ID_HISTORY
+    .prefix(contract_id)
+    .range(deps.storage, None, None, Order::Ascending)
+    .map(|entry| entry.map(|(_, change)| change))
+    .collect::<StdResult<Vec<(u64, Timestamp)>>>()?;
+  Ok(CodeIdHistoryResponse { history })
+}
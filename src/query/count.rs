@@ -1,10 +1,16 @@
 use cosmwasm_std::Deps;
 
-use crate::{error::ContractError, msg::CountResponse, state::COUNT};
+use crate::{
+  error::ContractError,
+  msg::CountResponse,
+  state::{COUNT, VIRTUAL_COUNT},
+};
 
-/// Return total number of contracts in the repo.
+/// Return total number of contracts in the repo, not including virtual ones,
+/// which are counted separately.
 pub fn count(deps: Deps) -> Result<CountResponse, ContractError> {
   Ok(CountResponse {
-    count: COUNT.load(deps.storage)?,
+    count: COUNT.may_load(deps.storage)?.unwrap_or(0),
+    virtual_count: VIRTUAL_COUNT.may_load(deps.storage)?.unwrap_or(0),
   })
 }
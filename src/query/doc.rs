@@ -0,0 +1,18 @@
+use cosmwasm_std::{Addr, Deps};
+
+use crate::{
+  error::ContractError,
+  msg::DocResponse,
+  state::{get_contract_id, DOCS},
+};
+
+/// Get the JSON document attached to a contract, if any.
+pub fn doc(
+  deps: Deps,
+  contract_addr: &Addr,
+) -> Result<DocResponse, ContractError> {
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+  Ok(DocResponse {
+    doc: DOCS.may_load(deps.storage, contract_id)?,
+  })
+}
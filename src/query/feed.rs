@@ -0,0 +1,72 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Deps, Order, StdResult, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::ContractID,
+  msg::{FeedItem, FeedResponse},
+  state::{ID_2_ADDR, IX_CREATED_AT, METADATA},
+};
+
+use super::read::{DEFAULT_LIMIT, MAX_LIMIT, MIN_LIMIT};
+
+/// Page through contracts by creation time for activity feeds, returning only
+/// the fields a feed needs rather than each contract's full metadata. Newest
+/// contracts come first unless desc is false.
+pub fn feed(
+  deps: Deps,
+  maybe_desc: Option<bool>,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<(Timestamp, ContractID)>,
+) -> Result<FeedResponse, ContractError> {
+  let order = if maybe_desc.unwrap_or(true) {
+    Order::Descending
+  } else {
+    Order::Ascending
+  };
+
+  let limit = maybe_limit
+    .unwrap_or(DEFAULT_LIMIT)
+    .clamp(MIN_LIMIT, MAX_LIMIT) as usize;
+
+  let bound = maybe_cursor
+    .and_then(|(time, id)| Some(Bound::Exclusive(((time.nanos(), id), PhantomData))))
+    .or(None);
+
+  let (start, stop) = match order {
+    Order::Ascending => (bound, None),
+    Order::Descending => (None, bound),
+  };
+
+  // fetch one more key than the limit to tell whether there's another page
+  let keys = IX_CREATED_AT
+    .keys(deps.storage, start, stop, order)
+    .take(limit + 1)
+    .collect::<StdResult<Vec<(u64, ContractID)>>>()?;
+
+  let has_more = keys.len() > limit;
+  let mut items: Vec<FeedItem> = Vec::with_capacity(limit);
+
+  for (nanos, id) in keys.into_iter().take(limit) {
+    let addr = ID_2_ADDR.load(deps.storage, id)?;
+    let meta = METADATA.load(deps.storage, addr.clone())?;
+    items.push(FeedItem {
+      addr,
+      contract_id: id,
+      code_id: meta.code_id,
+      created_at: Timestamp::from_nanos(nanos),
+    });
+  }
+
+  let cursor = if has_more {
+    items
+      .last()
+      .and_then(|item| Some((item.created_at, item.contract_id)))
+  } else {
+    None
+  };
+
+  Ok(FeedResponse { items, cursor })
+}
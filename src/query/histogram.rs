@@ -0,0 +1,133 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Deps, Order, StdResult, Uint128};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, IndexType, Slot, SLOT_COUNT},
+  msg::HistogramResponse,
+  state::{get_timestamp_index, get_u128_index, get_u64_index},
+};
+
+use super::read::scan_budget;
+
+/// Tally the number of contracts per bucket of values in a numeric index slot,
+/// where each bucket spans `bucket_width` values. Since index keys are sorted,
+/// this is a single pass over at most `max_scan` keys. If there are more keys
+/// than that, the histogram is truncated, and the returned cursor can be used
+/// to continue where it left off. Note that the last bucket of one call and
+/// the first bucket of the next can have the same start, in which case their
+/// counts should be summed.
+pub fn histogram(
+  deps: Deps,
+  kind: IndexType,
+  slot: Slot,
+  bucket_width: Uint128,
+  between: Option<(Option<Uint128>, Option<Uint128>)>,
+  maybe_max_scan: Option<u32>,
+  maybe_cursor: Option<(Uint128, ContractID)>,
+) -> Result<HistogramResponse, ContractError> {
+  let bucket_width = bucket_width.u128();
+  let maybe_cursor = maybe_cursor.map(|(x, id)| (x.u128(), id));
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+  if bucket_width == 0 {
+    return Err(ContractError::InvalidBucketWidth {});
+  }
+
+  let max_scan = scan_budget(maybe_max_scan);
+  let (lower, upper) = between
+    .map(|(lower, upper)| (lower.map(|x| x.u128()), upper.map(|x| x.u128())))
+    .unwrap_or((None, None));
+
+  let keys: Box<dyn Iterator<Item = StdResult<(u128, ContractID)>> + '_> = match kind {
+    IndexType::Uint64 | IndexType::Timestamp => {
+      let map = if let IndexType::Uint64 = kind {
+        get_u64_index(slot)?
+      } else {
+        get_timestamp_index(slot)?
+      };
+      let to_u64 = |x: u128| -> u64 { x.min(u64::MAX as u128) as u64 };
+      let min = if let Some((x, id)) = maybe_cursor {
+        Some(Bound::Exclusive(((to_u64(x), id), PhantomData)))
+      } else {
+        lower
+          .and_then(|x| {
+            Some(Bound::Inclusive((
+              (to_u64(x), ContractID::MIN),
+              PhantomData,
+            )))
+          })
+          .or(None)
+      };
+      let max = upper
+        .and_then(|x| {
+          Some(Bound::Exclusive((
+            (to_u64(x), ContractID::MIN),
+            PhantomData,
+          )))
+        })
+        .or(None);
+      Box::new(
+        map
+          .keys(deps.storage, min, max, Order::Ascending)
+          .map(|r| r.map(|(x, id)| (x as u128, id))),
+      )
+    },
+    IndexType::Uint128 => {
+      let map = get_u128_index(slot)?;
+      let min = if let Some((x, id)) = maybe_cursor {
+        Some(Bound::Exclusive(((x, id), PhantomData)))
+      } else {
+        lower
+          .and_then(|x| Some(Bound::Inclusive(((x, ContractID::MIN), PhantomData))))
+          .or(None)
+      };
+      let max = upper
+        .and_then(|x| Some(Bound::Exclusive(((x, ContractID::MIN), PhantomData))))
+        .or(None);
+      map.keys(deps.storage, min, max, Order::Ascending)
+    },
+    IndexType::Text | IndexType::Boolean => {
+      return Err(ContractError::NumericIndexRequired {});
+    },
+  };
+
+  let mut buckets: Vec<(u128, u64)> = vec![];
+  let mut n_scanned: u32 = 0;
+  let mut truncated = false;
+  let mut last_key: Option<(u128, ContractID)> = None;
+
+  for result in keys {
+    if n_scanned == max_scan {
+      truncated = true;
+      break;
+    }
+    n_scanned += 1;
+    let (value, id) = result?;
+    last_key = Some((value, id));
+    let bucket_start = (value / bucket_width) * bucket_width;
+    if let Some((start, count)) = buckets.last_mut() {
+      if *start == bucket_start {
+        *count += 1;
+        continue;
+      }
+    }
+    buckets.push((bucket_start, 1));
+  }
+
+  Ok(HistogramResponse {
+    buckets: buckets
+      .into_iter()
+      .map(|(start, count)| (start.into(), count))
+      .collect(),
+    truncated,
+    cursor: if truncated {
+      last_key.map(|(x, id)| (x.into(), id))
+    } else {
+      None
+    },
+  })
+}
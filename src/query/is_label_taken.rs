@@ -0,0 +1,13 @@
+use cosmwasm_std::Deps;
+
+use crate::{error::ContractError, msg::IsLabelTakenResponse, state::LABELS};
+
+/// Is the given label already in use by a contract in the repo?
+pub fn is_label_taken(
+  deps: Deps,
+  label: &String,
+) -> Result<IsLabelTakenResponse, ContractError> {
+  Ok(IsLabelTakenResponse {
+    is_taken: LABELS.has(deps.storage, label.clone()),
+  })
+}
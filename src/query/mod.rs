@@ -1,8 +1,55 @@
+mod all_presets;
+mod any_match;
+mod bool_breakdown;
+mod busiest_indices;
+mod check_mappings;
+mod code_id_history;
 mod count;
+mod doc;
+mod feed;
+mod histogram;
+mod is_label_taken;
+mod next_id;
+mod predict_address;
+mod rank;
 mod read;
+mod read_union;
+mod record;
+mod schema;
 mod select;
+mod slot_size;
+mod stats;
+mod unset;
 mod values;
+mod values_by_ids;
+mod wallet_summary;
+pub use all_presets::all_presets;
+pub use any_match::any_match;
+pub use bool_breakdown::bool_breakdown;
+pub use busiest_indices::busiest_indices;
+pub use check_mappings::check_mappings;
+pub use code_id_history::code_id_history;
 pub use count::count;
+pub use doc::doc;
+pub use feed::feed;
+pub use histogram::histogram;
+pub use is_label_taken::is_label_taken;
+pub use next_id::next_id;
+pub use predict_address::predict_address;
+pub use rank::rank;
+pub use read::query_smart_no_deserialize;
 pub use read::read;
+pub use read::DEFAULT_LIMIT;
+pub use read::MAX_FILTER_SCAN;
+pub use read::MAX_LIMIT;
+pub use read::MIN_LIMIT;
+pub use read_union::read_union;
+pub use record::record;
+pub use schema::schema;
 pub use select::select;
+pub use slot_size::slot_size;
+pub use stats::stats;
+pub use unset::unset;
 pub use values::values;
+pub use values_by_ids::values_by_ids;
+pub use wallet_summary::wallet_summary;
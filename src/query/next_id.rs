@@ -0,0 +1,11 @@
+use cosmwasm_std::Deps;
+
+use crate::{error::ContractError, msg::NextIdResponse, state::peek_next_contract_id};
+
+/// Return the contract ID that the next create will use. Any other create
+/// executed first takes this ID, so it's only a best guess.
+pub fn next_id(deps: Deps) -> Result<NextIdResponse, ContractError> {
+  Ok(NextIdResponse {
+    next_id: peek_next_contract_id(deps.storage)?,
+  })
+}
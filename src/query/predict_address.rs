@@ -0,0 +1,39 @@
+#[cfg(feature = "cosmwasm_1_2")]
+use cosmwasm_std::instantiate2_address;
+use cosmwasm_std::{Binary, Deps, Env};
+
+use crate::{error::ContractError, msg::PredictAddressResponse};
+
+/// Compute the address create would give a contract instantiated from the
+/// given code ID with the given salt.
+#[cfg(feature = "cosmwasm_1_2")]
+pub fn predict_address(
+  deps: Deps,
+  env: &Env,
+  code_id: u64,
+  salt: &Binary,
+) -> Result<PredictAddressResponse, ContractError> {
+  let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+  let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+  let canonical_addr = instantiate2_address(checksum.as_slice(), &creator, salt.as_slice())
+    .map_err(|err| ContractError::InvalidSalt {
+      msg: err.to_string(),
+    })?;
+
+  Ok(PredictAddressResponse {
+    address: deps.api.addr_humanize(&canonical_addr)?,
+  })
+}
+
+/// Without instantiate2 there's no salted create whose address to predict.
+#[cfg(not(feature = "cosmwasm_1_2"))]
+pub fn predict_address(
+  _deps: Deps,
+  _env: &Env,
+  _code_id: u64,
+  _salt: &Binary,
+) -> Result<PredictAddressResponse, ContractError> {
+  Err(ContractError::InvalidSalt {
+    msg: "predicting addresses requires the cosmwasm_1_2 feature".to_string(),
+  })
+}
@@ -0,0 +1,117 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Addr, Deps, Order, Storage};
+use cw_storage_plus::{Bound, Map, PrimaryKey};
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, IndexType, Slot, SLOT_COUNT},
+  msg::RankResponse,
+  state::{
+    get_bool_index, get_contract_id, get_text_index, get_timestamp_index, get_u128_index,
+    get_u64_index, ID_2_INDEXED_VALUES,
+  },
+};
+
+use super::read::scan_budget;
+
+/// Return a contract's 1-based position within an index slot, in ascending
+/// order or descending if `desc`. Since index keys are sorted, this counts the
+/// keys that precede the contract's own, up to the max scan budget. If there
+/// are more keys than that, the scan is capped, and the rank is a lower bound.
+pub fn rank(
+  deps: Deps,
+  contract_addr: &Addr,
+  kind: IndexType,
+  slot: Slot,
+  maybe_desc: Option<bool>,
+) -> Result<RankResponse, ContractError> {
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+  let values = ID_2_INDEXED_VALUES.load(deps.storage, contract_id)?;
+  let desc = maybe_desc.unwrap_or(false);
+  let i = slot as usize;
+
+  let (n_preceding, capped) = match kind {
+    IndexType::Uint64 => {
+      let value = values.uint64[i].ok_or(ContractError::NotFound {})?;
+      count_preceding(
+        deps.storage,
+        &get_u64_index(slot)?,
+        (value, contract_id),
+        desc,
+      )
+    },
+    IndexType::Uint128 => {
+      let value = values.uint128[i].ok_or(ContractError::NotFound {})?;
+      count_preceding(
+        deps.storage,
+        &get_u128_index(slot)?,
+        (value, contract_id),
+        desc,
+      )
+    },
+    IndexType::Timestamp => {
+      let value = values.timestamp[i].ok_or(ContractError::NotFound {})?;
+      count_preceding(
+        deps.storage,
+        &get_timestamp_index(slot)?,
+        (value, contract_id),
+        desc,
+      )
+    },
+    IndexType::Text => {
+      let value = values.text[i].clone().ok_or(ContractError::NotFound {})?;
+      count_preceding(
+        deps.storage,
+        &get_text_index(slot)?,
+        (value, contract_id),
+        desc,
+      )
+    },
+    IndexType::Boolean => {
+      let value = values.boolean[i].ok_or(ContractError::NotFound {})?;
+      count_preceding(
+        deps.storage,
+        &get_bool_index(slot)?,
+        (value, contract_id),
+        desc,
+      )
+    },
+  };
+
+  Ok(RankResponse {
+    rank: n_preceding + 1,
+    capped,
+  })
+}
+
+fn count_preceding<'a, K>(
+  storage: &dyn Storage,
+  map: &Map<'a, (K, ContractID), bool>,
+  key: (K, ContractID),
+  desc: bool,
+) -> (u64, bool)
+where
+  (K, ContractID): PrimaryKey<'a>,
+{
+  let max_scan = scan_budget(None) as usize;
+  let bound = Some(Bound::Exclusive((key, PhantomData)));
+  let (min, max, order) = if desc {
+    (bound, None, Order::Descending)
+  } else {
+    (None, bound, Order::Ascending)
+  };
+  let n = map
+    .keys_raw(storage, min, max, order)
+    .take(max_scan + 1)
+    .count();
+  if n > max_scan {
+    (max_scan as u64, true)
+  } else {
+    (n as u64, false)
+  }
+}
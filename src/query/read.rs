@@ -1,19 +1,20 @@
 use std::marker::PhantomData;
 
 use cosmwasm_std::{
-  to_binary, to_vec, Addr, Api, Binary, ContractResult, Deps, Empty, Order, QuerierWrapper,
-  QueryRequest, StdError, StdResult, Storage, SystemResult, Timestamp, WasmQuery,
+  to_binary, to_vec, Addr, Api, Binary, ContractResult, Deps, Empty, Env, Order, QuerierWrapper,
+  QueryRequest, StdError, StdResult, Storage, SystemResult, Timestamp, Uint128, Uint64, WasmQuery,
 };
-use cw_storage_plus::{Bound, Map, PrefixBound};
+use cw_storage_plus::{Bound, KeyDeserialize, Map, PrefixBound, PrimaryKey};
+use sha2::{Digest, Sha256};
 
 use crate::{
   error::ContractError,
-  models::{ContractID, IndexBounds},
-  msg::{EntityContractEnvelope, ImplementorQueryMsg, Page, Since, Target},
+  models::{ContractID, IndexBounds, RelativeBound},
+  msg::{ChildFilter, ContractStateEnvelope, ImplementorQueryMsg, ReadResponse, Since, Target},
   state::{
-    get_bool_index, get_text_index, get_timestamp_index, get_u128_index, get_u64_index, ID_2_ADDR,
-    IX_CODE_ID, IX_CREATED_AT, IX_CREATED_BY, IX_HEIGHT, IX_REV, IX_UPDATED_AT, METADATA,
-    RELATIONSHIPS, TAGGED_CONTRACT_IDS,
+    get_bool_index, get_contract_tags, get_text_index, get_timestamp_index, get_u128_index,
+    get_u64_index, ID_2_ADDR, IX_CODE_ID, IX_CODE_ID_CREATED, IX_CREATED_AT, IX_CREATED_BY,
+    IX_HEIGHT, IX_REV, IX_UPDATED_AT, METADATA, RELATIONSHIPS, REL_BY_NAME, TAGGED_CONTRACT_IDS,
   },
 };
 
@@ -21,8 +22,32 @@ pub const MIN_LIMIT: u32 = 1;
 pub const MAX_LIMIT: u32 = 50;
 pub const DEFAULT_LIMIT: u32 = 25;
 
+// limits aren't configurable, so a bad default fails the build rather than
+// being silently clamped at runtime.
+const _: () = assert!(MIN_LIMIT <= DEFAULT_LIMIT && DEFAULT_LIMIT <= MAX_LIMIT);
+
+/// Max number of entries a scanning query reads per call.
+pub const MAX_SCAN: u32 = 500;
+
+/// Timestamps below this many nanos (early 1973) are assumed to have been
+/// given in seconds, millis or micros by mistake.
+pub const MIN_PLAUSIBLE_NANOS: u64 = 100_000_000_000_000_000;
+
+/// Max number of contracts whose state a filtered read queries per call
+pub const MAX_FILTER_SCAN: u32 = 100;
+
+/// Max number of values in an equals_any bound
+pub const MAX_EQUALS_ANY: usize = 10;
+
+/// Resolve the number of entries a scanning query may read, clamped to the
+/// hard ceiling of MAX_SCAN.
+pub fn scan_budget(maybe_max_scan: Option<u32>) -> u32 {
+  maybe_max_scan.unwrap_or(MAX_SCAN).clamp(1, MAX_SCAN)
+}
+
 pub fn read(
   deps: Deps,
+  env: &Env,
   target: &Target,
   maybe_desc: Option<bool>,
   maybe_limit: Option<u32>,
@@ -31,128 +56,462 @@ pub fn read(
   maybe_meta: Option<bool>,
   maybe_wallet: Option<Addr>,
   maybe_cursor: Option<(String, ContractID)>,
-) -> Result<Page, ContractError> {
+  maybe_raw_keys: Option<bool>,
+  maybe_count_only: Option<bool>,
+  maybe_tiebreak_desc: Option<bool>,
+  maybe_with_tags: Option<bool>,
+  maybe_filter: Option<ChildFilter>,
+) -> Result<ReadResponse, ContractError> {
   // clamp limit to min and max bounds
   let limit = maybe_limit
     .unwrap_or(DEFAULT_LIMIT)
     .clamp(MIN_LIMIT, MAX_LIMIT);
 
-  // resolve Order enum from desc flag
-  let order = if maybe_desc.unwrap_or(false) {
+  // resolve Order enum from desc flag. Recently updated contracts come
+  // newest first unless desc is false.
+  let is_desc = match target {
+    Target::CodeIdRecent { desc, .. } => desc.unwrap_or(true),
+    Target::RecentlyUpdated { .. } => maybe_desc.unwrap_or(true),
+    _ => maybe_desc.unwrap_or(false),
+  };
+  let order = if is_desc {
     Order::Descending
   } else {
     Order::Ascending
   };
 
-  // build vec of returned contract addresses from contract ID's, along with
-  // any queried state from each contract, provided params is not None.
-  let page: Page = match &target {
+  // order of contract ID's among those with the same value, which follows
+  // the value order unless set otherwise.
+  let id_order = match maybe_tiebreak_desc {
+    Some(true) => Order::Descending,
+    Some(false) => Order::Ascending,
+    None => order,
+  };
+
+  // a rev floor on recently updated contracts can't be expressed in terms of
+  // the updated_at index, so it filters rows like the since param does.
+  let maybe_since = match target {
+    Target::RecentlyUpdated {
+      since: Some(Since::Rev(rev)),
+    } => {
+      if maybe_since.is_some() {
+        return Err(ContractError::ConflictingParams {
+          msg: "since can't be given both in the target and as a param".to_string(),
+        });
+      }
+      Some(Since::Rev(*rev))
+    },
+    _ => maybe_since,
+  };
+
+  let is_raw = maybe_raw_keys.unwrap_or(false);
+  let with_tags = maybe_with_tags.unwrap_or(false);
+  if is_raw
+    && (maybe_fields.is_some()
+      || maybe_meta.is_some()
+      || maybe_since.is_some()
+      || with_tags
+      || maybe_filter.is_some())
+  {
+    return Err(ContractError::ConflictingParams {
+      msg: "raw_keys can't be combined with fields, meta, since, with_tags, or filter".to_string(),
+    });
+  }
+
+  let is_count_only = maybe_count_only.unwrap_or(false);
+  if is_count_only
+    && (is_raw
+      || maybe_fields.is_some()
+      || maybe_meta.is_some()
+      || with_tags
+      || maybe_filter.is_some())
+  {
+    return Err(ContractError::ConflictingParams {
+      msg: "count_only can't be combined with raw_keys, fields, meta, with_tags, or filter"
+        .to_string(),
+    });
+  }
+
+  let is_first_page = maybe_cursor.is_none();
+
+  // a cursor is only valid for the kind of read that produced it
+  let scope = cursor_scope(target, order, id_order)?;
+  let maybe_cursor = match maybe_cursor {
+    Some((x, id)) => Some((unwrap_cursor(&scope, &x)?, id)),
+    None => None,
+  };
+
+  // fetch one more key than the limit to tell whether there's another page.
+  // A filtered read fetches as many keys as it may scan instead.
+  let n_keys = if maybe_filter.is_some() {
+    MAX_FILTER_SCAN + 1
+  } else {
+    limit + 1
+  };
+
+  let mut keys = match &target {
     Target::Index(bounds) => {
-      let keys = read_index(deps, bounds, order, limit, maybe_cursor)?;
-      build_contracts_page(
-        deps,
-        &keys,
-        maybe_fields,
-        maybe_since,
-        maybe_meta,
-        maybe_wallet,
-      )
+      validate_bounds(bounds)?;
+      read_index(deps, env, bounds, order, id_order, n_keys, maybe_cursor)?
     },
-    Target::Tag(tag) => {
-      let keys = read_tags(deps, tag, order, limit, maybe_cursor)?;
-      build_contracts_page(
-        deps,
-        &keys,
-        maybe_fields,
-        maybe_since,
-        maybe_meta,
-        maybe_wallet,
-      )
+    Target::All => read_all(deps, id_order, n_keys, maybe_cursor)?,
+    Target::CodeIdRecent { code_id, .. } => {
+      read_code_id_recent(deps, *code_id, order, n_keys, maybe_cursor)?
     },
-    Target::Relationship((rel_subject_addr, rel_name)) => {
-      let keys = read_relationship(deps, rel_subject_addr, rel_name, order, limit, maybe_cursor)?;
-      build_contracts_page(
-        deps,
-        &keys,
-        maybe_fields,
-        maybe_since,
-        maybe_meta,
-        maybe_wallet,
-      )
+    Target::RecentlyUpdated { since } => {
+      let floor = match since {
+        Some(Since::Timestamp(time)) => Some(time.plus_nanos(1)),
+        _ => None,
+      };
+      let bounds = IndexBounds::UpdatedAt {
+        between: floor.and_then(|t| Some((Some(t), None))),
+        equals: None,
+      };
+      read_index(deps, env, &bounds, order, id_order, n_keys, maybe_cursor)?
+    },
+    // every contract under a tag or relationship shares the same "value", so
+    // only the ID order matters.
+    Target::Tag(tag) => read_tags(deps, tag, id_order, n_keys, maybe_cursor)?,
+    Target::Relationship((rel_subject_addr, rel_name)) => read_relationship(
+      deps,
+      rel_subject_addr,
+      rel_name,
+      id_order,
+      n_keys,
+      maybe_cursor,
+    )?,
+    Target::RelationshipByName(rel_name) => {
+      read_relationship_by_name(deps, rel_name, id_order, n_keys, maybe_cursor)?
+    },
+  };
+
+  // the next page starts after the last key returned or, when filtering,
+  // the last key scanned.
+  let (has_more, maybe_last_key) = if let Some(filter) = &maybe_filter {
+    let filtered = filter_keys(deps, keys, filter, limit, &maybe_wallet)?;
+    keys = filtered.matches;
+    (filtered.has_more, filtered.maybe_last_key)
+  } else {
+    let has_more = keys.len() > limit as usize;
+    keys.truncate(limit as usize);
+    (has_more, if has_more { keys.last().cloned() } else { None })
+  };
+  let cursor = next_cursor(&scope, maybe_last_key);
+
+  // return the index keys as-is, skipping address resolution
+  if is_raw || is_count_only {
+    return Ok(ReadResponse {
+      page: vec![],
+      count: keys.len() as u64,
+      has_more,
+      cursor,
+      raw_keys: if is_raw { Some(keys) } else { None },
+      is_first_page,
+      page_size: limit,
+    });
+  }
+
+  // build vec of returned contract addresses from contract ID's, along with
+  // any queried state from each contract, provided params is not None.
+  build_contracts_page(
+    deps,
+    &keys,
+    has_more,
+    cursor,
+    is_first_page,
+    limit,
+    maybe_fields,
+    maybe_since,
+    maybe_meta,
+    maybe_wallet,
+    with_tags,
+  )
+}
+
+/// The cursor for the next page, resuming after the given key, or None if
+/// this is the last page.
+fn next_cursor(
+  scope: &String,
+  maybe_last_key: Option<(String, ContractID)>,
+) -> Option<(String, ContractID)> {
+  maybe_last_key.and_then(|(x, id)| Some((format!("{}:{}", scope, x), id)))
+}
+
+/// The keys a filtered read kept, whether there may be more, and the key to
+/// resume after.
+struct FilteredKeys {
+  matches: Vec<(String, ContractID)>,
+  has_more: bool,
+  maybe_last_key: Option<(String, ContractID)>,
+}
+
+/// Keep the keys of contracts whose Select response matches the filter,
+/// querying at most MAX_FILTER_SCAN contracts. A contract whose state can't
+/// be queried doesn't match.
+fn filter_keys(
+  deps: Deps,
+  keys: Vec<(String, ContractID)>,
+  filter: &ChildFilter,
+  limit: u32,
+  maybe_wallet: &Option<Addr>,
+) -> Result<FilteredKeys, ContractError> {
+  let expected: serde_json::Value =
+    serde_json::from_str(&filter.equals).map_err(|err| ContractError::InvalidJson {
+      msg: err.to_string(),
+    })?;
+  let path: Vec<&str> = filter.path.split('.').collect();
+  let msg = build_select_msg(&Some(vec![path[0].to_owned()]), maybe_wallet)?;
+
+  let is_exhausted = keys.len() <= MAX_FILTER_SCAN as usize;
+  let mut matches: Vec<(String, ContractID)> = Vec::with_capacity(limit as usize);
+  let mut maybe_last_scanned: Option<(String, ContractID)> = None;
+
+  for key in keys.into_iter().take(MAX_FILTER_SCAN as usize) {
+    let contract_addr = ID_2_ADDR.load(deps.storage, key.1)?;
+    let maybe_value = query_child_state(deps.api, deps.querier, &contract_addr, &msg)
+      .ok()
+      .and_then(|state| serde_json::from_slice::<serde_json::Value>(state.as_slice()).ok())
+      .and_then(|state| path.iter().try_fold(state, |v, k| v.get(*k).cloned()));
+
+    if maybe_value.as_ref() == Some(&expected) {
+      // a match beyond the limit means there's another page, which starts
+      // after the last match returned.
+      if matches.len() == limit as usize {
+        let maybe_last_key = matches.last().cloned();
+        return Ok(FilteredKeys {
+          matches,
+          has_more: true,
+          maybe_last_key,
+        });
+      }
+      matches.push(key.clone());
+    }
+    maybe_last_scanned = Some(key);
+  }
+
+  Ok(FilteredKeys {
+    matches,
+    has_more: !is_exhausted,
+    maybe_last_key: if is_exhausted {
+      None
+    } else {
+      maybe_last_scanned
+    },
+  })
+}
+
+/// Identify the read a cursor belongs to, so that a cursor from one target or
+/// order can't be used to page through another. Besides the kind of target,
+/// the scope holds a hash of the whole target, which tells apart tags,
+/// relationships, code ID's and index bounds without the scope growing with
+/// them.
+fn cursor_scope(
+  target: &Target,
+  order: Order,
+  id_order: Order,
+) -> Result<String, ContractError> {
+  let kind = match target {
+    Target::Index(bounds) => match bounds {
+      IndexBounds::Address { .. } => "address".to_string(),
+      IndexBounds::CreatedBy { equals_any, .. } => {
+        if equals_any.is_some() {
+          "created_by_any".to_string()
+        } else {
+          "created_by".to_string()
+        }
+      },
+      IndexBounds::CreatedAt { .. } => "created_at".to_string(),
+      IndexBounds::UpdatedAt { .. } => "updated_at".to_string(),
+      IndexBounds::Rev { .. } => "rev".to_string(),
+      IndexBounds::CodeId { equals_any, .. } => {
+        if equals_any.is_some() {
+          "code_id_any".to_string()
+        } else {
+          "code_id".to_string()
+        }
+      },
+      IndexBounds::Height { .. } => "height".to_string(),
+      IndexBounds::Uint64 { slot, .. } => format!("u64_{}", slot),
+      IndexBounds::Uint128 { slot, .. } => format!("u128_{}", slot),
+      IndexBounds::Timestamp { slot, .. } => format!("ts_{}", slot),
+      IndexBounds::Text { slot, .. } => format!("text_{}", slot),
+      IndexBounds::Boolean { slot, .. } => format!("bool_{}", slot),
     },
-  }?;
+    Target::All => "all".to_string(),
+    Target::CodeIdRecent { .. } => "code_id_recent".to_string(),
+    Target::RecentlyUpdated { .. } => "recently_updated".to_string(),
+    Target::Tag(_) => "tag".to_string(),
+    Target::Relationship(_) => "rel".to_string(),
+    Target::RelationshipByName(_) => "rel_by_name".to_string(),
+  };
+  let dir = |order: Order| if order == Order::Ascending { "a" } else { "d" };
+  let hash: String = Sha256::digest(&to_vec(target)?)[..8]
+    .iter()
+    .map(|b| format!("{:02x}", b))
+    .collect();
+  Ok(format!("{}.{}{}.{}", kind, dir(order), dir(id_order), hash))
+}
 
-  Ok(page)
+/// Strip the scope from a cursor's value, failing if it's from another scope.
+fn unwrap_cursor(
+  scope: &String,
+  raw_value: &String,
+) -> Result<String, ContractError> {
+  match raw_value.split_once(':') {
+    Some((cursor_scope, value)) if cursor_scope == scope => Ok(value.to_owned()),
+    _ => Err(ContractError::InvalidCursor {}),
+  }
 }
 
 fn build_contracts_page(
   deps: Deps,
   keys: &Vec<(String, ContractID)>,
+  has_more: bool,
+  cursor: Option<(String, ContractID)>,
+  is_first_page: bool,
+  page_size: u32,
   maybe_fields: Option<Vec<String>>,
   maybe_since: Option<Since>,
   maybe_meta: Option<bool>,
   maybe_wallet: Option<Addr>,
-) -> Result<Page, ContractError> {
-  let mut page_data: Vec<EntityContractEnvelope> = Vec::with_capacity(keys.len());
-  let next_cursor: Option<(String, ContractID)> =
-    keys.last().and_then(|x| Some(x.clone())).or(None);
+  with_tags: bool,
+) -> Result<ReadResponse, ContractError> {
+  let mut page_data: Vec<ContractStateEnvelope> = Vec::with_capacity(keys.len());
+
+  // every contract is sent the same Select msg, so serialize it only once
+  let maybe_select_msg = if maybe_fields.is_some() {
+    Some(build_select_msg(&maybe_fields, &maybe_wallet)?)
+  } else {
+    None
+  };
 
   for (_, id) in keys.iter() {
     let contract_addr = ID_2_ADDR.load(deps.storage, *id)?;
-    let some_meta = if maybe_meta.unwrap_or(false) {
+    // load the metadata at most once per contract, whether it's returned,
+    // used to evaluate since, or both.
+    let with_meta = maybe_meta.unwrap_or(false);
+    let some_loaded_meta = if with_meta || maybe_since.is_some() {
       METADATA.may_load(deps.storage, contract_addr.clone())?
     } else {
       None
     };
 
-    //skip if not modified since modified_since revision or timestamp
-    if let Some(since) = maybe_since.clone() {
-      let meta = if let Some(meta) = &some_meta {
-        meta.clone()
-      } else {
-        METADATA.load(deps.storage, contract_addr.clone())?
-      };
+    //skip if not modified since modified_since revision or timestamp. Since
+    // we load the metadata anyway, we return its rev for incremental syncing.
+    let mut some_rev: Option<u64> = None;
+    if let Some(since) = &maybe_since {
+      let meta = some_loaded_meta
+        .as_ref()
+        .ok_or(ContractError::NotFound {})?;
+      some_rev = Some(meta.rev);
       match since {
         Since::Rev(rev) => {
-          if meta.rev <= rev {
+          if meta.rev <= *rev {
             continue;
           }
         },
         Since::Timestamp(time) => {
-          if meta.updated_at <= time {
+          if meta.updated_at <= *time {
             continue;
           }
         },
       }
     }
 
-    // query state from contract if fields vec is not None
-    let state = if maybe_fields.is_some() {
-      // an empty fields vec should be interpreted as "select *"
-      Some(query_smart_no_deserialize(
+    let some_meta = if with_meta { some_loaded_meta } else { None };
+
+    // query state from contract if fields vec is not None. An empty fields
+    // vec should be interpreted as "select *".
+    let state = if let Some(msg) = &maybe_select_msg {
+      Some(query_child_state(
         deps.api,
         deps.querier,
         &contract_addr,
-        &maybe_fields,
-        &maybe_wallet,
+        msg,
       )?)
     } else {
       None
     };
 
-    page_data.push(EntityContractEnvelope {
+    let tags = if with_tags {
+      Some(get_contract_tags(deps.storage, *id)?)
+    } else {
+      None
+    };
+
+    page_data.push(ContractStateEnvelope {
       address: contract_addr.clone(),
       meta: some_meta,
+      rev: some_rev,
       state,
+      tags,
     })
   }
 
-  Ok(Page {
+  Ok(ReadResponse {
     page: page_data,
-    cursor: next_cursor,
+    count: keys.len() as u64,
+    has_more,
+    cursor,
+    raw_keys: None,
+    is_first_page,
+    page_size,
   })
 }
 
+fn read_all(
+  deps: Deps,
+  order: Order,
+  limit: u32,
+  maybe_cursor: Option<(String, ContractID)>,
+) -> Result<Vec<(String, ContractID)>, ContractError> {
+  let bound = maybe_cursor
+    .and_then(|(_, id)| Some(Bound::Exclusive((id, PhantomData))))
+    .or(None);
+
+  let (start, stop) = match order {
+    Order::Ascending => (bound, None),
+    Order::Descending => (None, bound),
+  };
+
+  let iter = ID_2_ADDR.range(deps.storage, start, stop, order);
+
+  collect(iter, limit, |id, _| Ok((String::new(), id)))
+}
+
+/// Page through the contracts with the given code ID by creation time. Those
+/// created in the same block follow the same order by ID.
+fn read_code_id_recent(
+  deps: Deps,
+  code_id: u64,
+  order: Order,
+  limit: u32,
+  maybe_cursor: Option<(String, ContractID)>,
+) -> Result<Vec<(String, ContractID)>, ContractError> {
+  let map = IX_CODE_ID_CREATED.sub_prefix(code_id);
+
+  let bound = if let Some((x, id)) = maybe_cursor {
+    let nanos = x
+      .parse::<u64>()
+      .map_err(|_| ContractError::InvalidCursor {})?;
+    Some(Bound::Exclusive(((nanos, id), PhantomData)))
+  } else {
+    None
+  };
+
+  let (start, stop) = match order {
+    Order::Ascending => (bound, None),
+    Order::Descending => (None, bound),
+  };
+
+  let iter = map.range(deps.storage, start, stop, order);
+
+  collect(iter, limit, |(nanos, id), _| Ok((nanos.to_string(), id)))
+}
+
+/// Page through the contracts under a tag by ID. Tags are matched as stored,
+/// so a tag added through the client's UpdateBuilder, which encodes tags as
+/// base64, must be given here in that encoded form, as it is to create.
 fn read_tags(
   deps: Deps,
   tag: &String,
@@ -162,7 +521,9 @@ fn read_tags(
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
   let map = TAGGED_CONTRACT_IDS;
 
-  let iter = if let Some((tag, min_contract_id)) = maybe_cursor {
+  // the cursor only carries the last contract ID. Its value is ignored so
+  // that it can't point into another tag.
+  let iter = if let Some((_, min_contract_id)) = maybe_cursor {
     let bound = Some(Bound::Exclusive((
       (tag.clone(), min_contract_id),
       PhantomData,
@@ -195,6 +556,9 @@ fn read_tags(
   collect(iter, limit, |k, _| Ok(k.clone()))
 }
 
+/// Page through the contracts related to the subject address by the named
+/// relationship, ordered by ID. Rows go through the same envelope building
+/// as index reads, so desc, since, meta and fields apply alike.
 fn read_relationship(
   deps: Deps,
   rel_subject_addr: &Addr,
@@ -205,22 +569,24 @@ fn read_relationship(
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
   let map = RELATIONSHIPS;
 
-  let iter = if let Some((name, min_contract_id)) = maybe_cursor {
+  // the cursor only carries the last contract ID. Its value is ignored so
+  // that it can't point into another relationship.
+  let iter = if let Some((_, min_contract_id)) = maybe_cursor {
     let bound = Some(Bound::Exclusive((
-      (rel_subject_addr.clone(), name.clone(), min_contract_id),
+      (rel_subject_addr.clone(), rel_name.clone(), min_contract_id),
       PhantomData,
     )));
     match order {
       Order::Ascending => {
         let upper = Some(Bound::Inclusive((
-          (rel_subject_addr.clone(), name.clone(), ContractID::MAX),
+          (rel_subject_addr.clone(), rel_name.clone(), ContractID::MAX),
           PhantomData,
         )));
         map.range(deps.storage, bound, upper, order)
       },
       Order::Descending => {
         let lower = Some(Bound::Inclusive((
-          (rel_subject_addr.clone(), name.clone(), ContractID::MIN),
+          (rel_subject_addr.clone(), rel_name.clone(), ContractID::MIN),
           PhantomData,
         )));
         map.range(deps.storage, lower, bound, order)
@@ -244,32 +610,175 @@ fn read_relationship(
   collect(iter, limit, |(_, name, id), _| Ok((name.clone(), id)))
 }
 
+fn read_relationship_by_name(
+  deps: Deps,
+  rel_name: &String,
+  order: Order,
+  limit: u32,
+  maybe_cursor: Option<(String, ContractID)>,
+) -> Result<Vec<(String, ContractID)>, ContractError> {
+  let map = REL_BY_NAME;
+
+  // the cursor only carries the last contract ID. Its value is ignored so
+  // that it can't point into another relationship name.
+  let iter = if let Some((_, min_contract_id)) = maybe_cursor {
+    let bound = Some(Bound::Exclusive((
+      (rel_name.clone(), min_contract_id),
+      PhantomData,
+    )));
+    match order {
+      Order::Ascending => {
+        let upper = Some(Bound::Inclusive((
+          (rel_name.clone(), ContractID::MAX),
+          PhantomData,
+        )));
+        map.range(deps.storage, bound, upper, order)
+      },
+      Order::Descending => {
+        let lower = Some(Bound::Inclusive((
+          (rel_name.clone(), ContractID::MIN),
+          PhantomData,
+        )));
+        map.range(deps.storage, lower, bound, order)
+      },
+    }
+  } else {
+    map.prefix_range(
+      deps.storage,
+      Some(PrefixBound::Inclusive((rel_name.clone(), PhantomData))),
+      Some(PrefixBound::Inclusive((rel_name.clone(), PhantomData))),
+      order,
+    )
+  };
+
+  collect(iter, limit, |k, _| Ok(k.clone()))
+}
+
+/// Reject bounds that set both equals and between, since only one of them can
+/// apply.
+fn validate_bounds(bounds: &IndexBounds) -> Result<(), ContractError> {
+  let is_ambiguous = match bounds {
+    IndexBounds::CodeId {
+      equals,
+      between,
+      equals_any,
+    } => {
+      [equals.is_some(), between.is_some(), equals_any.is_some()]
+        .iter()
+        .filter(|x| **x)
+        .count()
+        > 1
+    },
+    IndexBounds::Height { equals, between } => equals.is_some() && between.is_some(),
+    IndexBounds::Address { equals, between } => equals.is_some() && between.is_some(),
+    IndexBounds::CreatedBy {
+      equals,
+      between,
+      equals_any,
+    } => {
+      [equals.is_some(), between.is_some(), equals_any.is_some()]
+        .iter()
+        .filter(|x| **x)
+        .count()
+        > 1
+    },
+    IndexBounds::CreatedAt { equals, between } => equals.is_some() && between.is_some(),
+    IndexBounds::UpdatedAt { equals, between } => equals.is_some() && between.is_some(),
+    IndexBounds::Rev { equals, between } => equals.is_some() && between.is_some(),
+    IndexBounds::Uint64 {
+      equals, between, ..
+    } => equals.is_some() && between.is_some(),
+    IndexBounds::Uint128 {
+      equals, between, ..
+    } => equals.is_some() && between.is_some(),
+    IndexBounds::Timestamp {
+      equals,
+      between,
+      relative_to_now,
+      ..
+    } => equals.is_some() && (between.is_some() || relative_to_now.is_some()),
+    IndexBounds::Text {
+      equals, between, ..
+    } => equals.is_some() && between.is_some(),
+    IndexBounds::Boolean { .. } => false,
+  };
+  if is_ambiguous {
+    return Err(ContractError::ConflictingBounds {});
+  }
+  Ok(())
+}
+
 fn read_index(
   deps: Deps,
+  env: &Env,
   bounds: &IndexBounds,
   order: Order,
+  id_order: Order,
   limit: u32,
   maybe_cursor: Option<(String, ContractID)>,
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
   let store = deps.storage;
   let api = deps.api;
 
+  if id_order != order {
+    let is_supported = match bounds {
+      IndexBounds::Address { .. } | IndexBounds::CreatedBy { .. } | IndexBounds::Boolean { .. } => {
+        false
+      },
+      IndexBounds::CodeId { equals_any, .. } => equals_any.is_none(),
+      _ => true,
+    };
+    if !is_supported {
+      return Err(ContractError::ConflictingParams {
+        msg: "tiebreak_desc isn't supported for this index".to_string(),
+      });
+    }
+  }
+
   // compute vec of contract ID's from an index
   Ok(match bounds.clone() {
     IndexBounds::Address { equals, between } => {
       paginate_metadata(store, api, maybe_cursor, equals, between, order, limit)?
     },
-    IndexBounds::CreatedBy { equals, between } => {
+    IndexBounds::CreatedBy {
+      equals,
+      between,
+      equals_any,
+    } => {
       let ix = &IX_CREATED_BY;
-      paginate_addr_index(store, api, ix, equals, between, order, limit, maybe_cursor)?
+      if let Some(addrs) = equals_any {
+        paginate_in_set(store, ix, addrs, order, limit, maybe_cursor, |x| {
+          x.to_string()
+        })?
+      } else {
+        paginate_addr_index(store, api, ix, equals, between, order, limit, maybe_cursor)?
+      }
     },
     IndexBounds::CreatedAt { equals, between } => {
       let ix = &IX_CREATED_AT;
-      paginate_ts_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      paginate_ts_index(
+        store,
+        ix,
+        equals,
+        between,
+        order,
+        id_order,
+        limit,
+        maybe_cursor,
+      )?
     },
     IndexBounds::UpdatedAt { equals, between } => {
       let ix = &IX_UPDATED_AT;
-      paginate_ts_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      paginate_ts_index(
+        store,
+        ix,
+        equals,
+        between,
+        order,
+        id_order,
+        limit,
+        maybe_cursor,
+      )?
     },
     IndexBounds::Uint64 {
       slot,
@@ -283,6 +792,7 @@ fn read_index(
         equals,
         between,
         order,
+        id_order,
         limit,
         maybe_cursor,
       )?
@@ -299,6 +809,7 @@ fn read_index(
         equals,
         between,
         order,
+        id_order,
         limit,
         maybe_cursor,
       )?
@@ -307,21 +818,70 @@ fn read_index(
       slot,
       equals,
       between,
+      relative_to_now,
     } => {
       let ix = &get_timestamp_index(slot)?;
-      paginate_ts_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      let between = apply_relative_bound(env, between, relative_to_now);
+      paginate_ts_index(
+        store,
+        ix,
+        equals,
+        between,
+        order,
+        id_order,
+        limit,
+        maybe_cursor,
+      )?
     },
     IndexBounds::Rev { equals, between } => {
       let ix = &IX_REV;
-      paginate_u64_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      paginate_u64_index(
+        store,
+        ix,
+        equals,
+        between,
+        order,
+        id_order,
+        limit,
+        maybe_cursor,
+      )?
     },
-    IndexBounds::CodeId { equals, between } => {
+    IndexBounds::CodeId {
+      equals,
+      between,
+      equals_any,
+    } => {
       let ix = &IX_CODE_ID;
-      paginate_u64_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      if let Some(values) = equals_any {
+        let values: Vec<u64> = values.iter().map(|x| x.u64()).collect();
+        paginate_in_set(store, ix, values, order, limit, maybe_cursor, |x| {
+          x.to_string()
+        })?
+      } else {
+        paginate_u64_index(
+          store,
+          ix,
+          equals,
+          between,
+          order,
+          id_order,
+          limit,
+          maybe_cursor,
+        )?
+      }
     },
     IndexBounds::Height { equals, between } => {
       let ix = &IX_HEIGHT;
-      paginate_u64_index(store, ix, equals, between, order, limit, maybe_cursor)?
+      paginate_u64_index(
+        store,
+        ix,
+        equals,
+        between,
+        order,
+        id_order,
+        limit,
+        maybe_cursor,
+      )?
     },
     IndexBounds::Boolean { slot, start, stop } => {
       let map = get_bool_index(slot)?;
@@ -339,6 +899,7 @@ fn read_index(
         equals,
         between,
         order,
+        id_order,
         limit,
         maybe_cursor,
       )?
@@ -346,19 +907,40 @@ fn read_index(
   })
 }
 
-fn query_smart_no_deserialize(
+pub fn query_smart_no_deserialize(
   api: &dyn Api,
   querier: QuerierWrapper<Empty>,
   contract_addr: &Addr,
   fields: &Option<Vec<String>>,
   wallet: &Option<Addr>,
+) -> Result<Binary, ContractError> {
+  let msg = build_select_msg(fields, wallet)?;
+  query_child_state(api, querier, contract_addr, &msg)
+}
+
+/// Serialize the Select query sent to managed contracts. Queries made to
+/// many contracts with the same params should build it once and reuse it.
+pub fn build_select_msg(
+  fields: &Option<Vec<String>>,
+  wallet: &Option<Addr>,
+) -> Result<Binary, ContractError> {
+  Ok(to_binary(&ImplementorQueryMsg::Select {
+    wallet: wallet.clone(),
+    fields: fields.clone(),
+  })?)
+}
+
+/// Query a managed contract's state with a Select msg built by
+/// build_select_msg, returning the response without deserializing it.
+pub fn query_child_state(
+  api: &dyn Api,
+  querier: QuerierWrapper<Empty>,
+  contract_addr: &Addr,
+  msg: &Binary,
 ) -> Result<Binary, ContractError> {
   let request: QueryRequest<Empty> = WasmQuery::Smart {
     contract_addr: contract_addr.clone().into(),
-    msg: to_binary(&ImplementorQueryMsg::Select {
-      wallet: wallet.clone(),
-      fields: fields.clone(),
-    })?,
+    msg: msg.clone(),
   }
   .into();
 
@@ -439,12 +1021,15 @@ fn paginate_metadata(
 fn paginate_u128_index<'a>(
   store: &dyn Storage,
   map: &Map<'a, (u128, ContractID), bool>,
-  equals: Option<u128>,
-  between: Option<(Option<u128>, Option<u128>)>,
+  equals: Option<Uint128>,
+  between: Option<(Option<Uint128>, Option<Uint128>)>,
   order: Order,
+  id_order: Order,
   limit: u32,
   cursor: Option<(String, ContractID)>,
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
+  let equals = equals.map(|x| x.u128());
+  let between = between.map(|(lower, upper)| (lower.map(|x| x.u128()), upper.map(|x| x.u128())));
   let (start, stop, is_exclusive) = if let Some(value) = equals {
     (Some(value), Some(value), false)
   } else if let Some((lower, upper)) = between {
@@ -453,23 +1038,52 @@ fn paginate_u128_index<'a>(
     (None, None, true)
   };
 
+  if id_order != order {
+    let cursor = cursor.and_then(|(x, id)| {
+      equals
+        .or_else(|| x.parse::<u128>().ok())
+        .and_then(|x| Some((x, id)))
+    });
+    let upper = stop.and_then(|x| Some((x, is_exclusive)));
+    return paginate_tiebroken(
+      store,
+      map,
+      start,
+      upper,
+      cursor,
+      order,
+      id_order,
+      limit,
+      |x| x.to_string(),
+    );
+  }
+
   let iter = if let Some((x, id)) = cursor {
-    let start = match x.parse::<u128>() {
-      Ok(x) => Some(Bound::Exclusive(((x, id), PhantomData))),
-      Err(_) => None,
+    // when paging through an equals block, the cursor can only ever point
+    // inside of it, regardless of the value encoded in the cursor string.
+    let cursor_value = if equals.is_some() {
+      equals
+    } else {
+      x.parse::<u128>().ok()
     };
-    let stop = stop
+    let cursor_bound = cursor_value
+      .and_then(|x| Some(Bound::Exclusive(((x, id), PhantomData))))
+      .or(None);
+    let lower = start
+      .and_then(|x| Some(Bound::Inclusive(((x, ContractID::MIN), PhantomData))))
+      .or(None);
+    let upper = stop
       .and_then(|x| {
         Some(if is_exclusive {
-          Bound::Exclusive(((x, 0), PhantomData))
+          Bound::Exclusive(((x, ContractID::MIN), PhantomData))
         } else {
           Bound::Inclusive(((x, ContractID::MAX), PhantomData))
         })
       })
       .or(None);
     match order {
-      Order::Ascending => map.range(store, start, stop, order),
-      Order::Descending => map.range(store, stop, start, order),
+      Order::Ascending => map.range(store, cursor_bound, upper, order),
+      Order::Descending => map.range(store, lower, cursor_bound, order),
     }
   } else {
     map.prefix_range(
@@ -497,15 +1111,110 @@ fn paginate_u128_index<'a>(
   );
 }
 
+/// Page through contracts whose value is any of the given values, ordered by
+/// contract ID. Each value is its own prefix in the index, so we merge the
+/// streams of contract ID's under each prefix. The cursor's ID is all we need
+/// to resume, since every stream is ordered by ID.
+fn paginate_in_set<'a, K, F>(
+  store: &dyn Storage,
+  map: &Map<'a, (K, ContractID), bool>,
+  values: Vec<K>,
+  order: Order,
+  limit: u32,
+  cursor: Option<(String, ContractID)>,
+  to_string: F,
+) -> Result<Vec<(String, ContractID)>, ContractError>
+where
+  K: Clone + PartialEq + 'static,
+  (K, ContractID): PrimaryKey<'a> + KeyDeserialize<Output = (K, ContractID)>,
+  F: Fn(&K) -> String,
+{
+  if values.is_empty() || values.len() > MAX_EQUALS_ANY {
+    return Err(ContractError::InvalidSetSize {
+      max: MAX_EQUALS_ANY as u32,
+    });
+  }
+
+  let mut unique_values: Vec<K> = Vec::with_capacity(values.len());
+  for value in values.into_iter() {
+    if !unique_values.contains(&value) {
+      unique_values.push(value);
+    }
+  }
+
+  let cursor_id = cursor.and_then(|(_, id)| Some(id));
+
+  let mut streams: Vec<_> = unique_values
+    .iter()
+    .map(|value| {
+      let after_cursor =
+        cursor_id.and_then(|id| Some(Bound::Exclusive(((value.clone(), id), PhantomData))));
+      let first = Some(Bound::Inclusive((
+        (value.clone(), ContractID::MIN),
+        PhantomData,
+      )));
+      let last = Some(Bound::Inclusive((
+        (value.clone(), ContractID::MAX),
+        PhantomData,
+      )));
+      let (min, max) = match order {
+        Order::Ascending => (after_cursor.or(first), last),
+        Order::Descending => (first, after_cursor.or(last)),
+      };
+      (
+        to_string(value),
+        map.keys(store, min, max, order).peekable(),
+      )
+    })
+    .collect();
+
+  let mut keys: Vec<(String, ContractID)> = Vec::with_capacity(limit as usize);
+
+  while keys.len() < limit as usize {
+    // find the stream whose next ID comes first in the given order
+    let mut next: Option<(usize, ContractID)> = None;
+    for (i, (_, stream)) in streams.iter_mut().enumerate() {
+      if let Some(Err(_)) = stream.peek() {
+        if let Some(Err(e)) = stream.next() {
+          return Err(ContractError::Std(e));
+        }
+      }
+      if let Some(Ok((_, id))) = stream.peek() {
+        let id = *id;
+        let is_first = match (next, order) {
+          (None, _) => true,
+          (Some((_, other)), Order::Ascending) => id < other,
+          (Some((_, other)), Order::Descending) => id > other,
+        };
+        if is_first {
+          next = Some((i, id));
+        }
+      }
+    }
+    if let Some((i, id)) = next {
+      let (value, stream) = &mut streams[i];
+      stream.next();
+      keys.push((value.clone(), id));
+    } else {
+      break;
+    }
+  }
+
+  Ok(keys)
+}
+
 fn paginate_u64_index<'a>(
   store: &dyn Storage,
   map: &Map<'a, (u64, ContractID), bool>,
-  equals: Option<u64>,
-  between: Option<(Option<u64>, Option<u64>)>,
+  equals: Option<Uint64>,
+  between: Option<(Option<Uint64>, Option<Uint64>)>,
   order: Order,
+  id_order: Order,
   limit: u32,
   cursor: Option<(String, ContractID)>,
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
+  let equals = equals.map(|x| x.u64());
+  let between = between.map(|(lower, upper)| (lower.map(|x| x.u64()), upper.map(|x| x.u64())));
   let (start, stop, is_exclusive) = if let Some(value) = equals {
     (Some(value), Some(value), false)
   } else if let Some((lower, upper)) = between {
@@ -514,23 +1223,52 @@ fn paginate_u64_index<'a>(
     (None, None, true)
   };
 
+  if id_order != order {
+    let cursor = cursor.and_then(|(x, id)| {
+      equals
+        .or_else(|| x.parse::<u64>().ok())
+        .and_then(|x| Some((x, id)))
+    });
+    let upper = stop.and_then(|x| Some((x, is_exclusive)));
+    return paginate_tiebroken(
+      store,
+      map,
+      start,
+      upper,
+      cursor,
+      order,
+      id_order,
+      limit,
+      |x| x.to_string(),
+    );
+  }
+
   let iter = if let Some((x, id)) = cursor {
-    let start = match x.parse::<u64>() {
-      Ok(x) => Some(Bound::Exclusive(((x, id), PhantomData))),
-      Err(_) => None,
+    // when paging through an equals block, the cursor can only ever point
+    // inside of it, regardless of the value encoded in the cursor string.
+    let cursor_value = if equals.is_some() {
+      equals
+    } else {
+      x.parse::<u64>().ok()
     };
-    let stop = stop
+    let cursor_bound = cursor_value
+      .and_then(|x| Some(Bound::Exclusive(((x, id), PhantomData))))
+      .or(None);
+    let lower = start
+      .and_then(|x| Some(Bound::Inclusive(((x, ContractID::MIN), PhantomData))))
+      .or(None);
+    let upper = stop
       .and_then(|x| {
         Some(if is_exclusive {
-          Bound::Exclusive(((x, 0), PhantomData))
+          Bound::Exclusive(((x, ContractID::MIN), PhantomData))
         } else {
           Bound::Inclusive(((x, ContractID::MAX), PhantomData))
         })
       })
       .or(None);
     match order {
-      Order::Ascending => map.range(store, start, stop, order),
-      Order::Descending => map.range(store, stop, start, order),
+      Order::Ascending => map.range(store, cursor_bound, upper, order),
+      Order::Descending => map.range(store, lower, cursor_bound, order),
     }
   } else {
     map.prefix_range(
@@ -607,10 +1345,11 @@ fn paginate_str_index<'a>(
   equals: Option<String>,
   between: Option<(Option<String>, Option<String>)>,
   order: Order,
+  id_order: Order,
   limit: u32,
   cursor: Option<(String, ContractID)>,
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
-  let (start, stop, is_exclusive) = if let Some(value) = equals {
+  let (start, stop, is_exclusive) = if let Some(value) = &equals {
     (Some(value.clone()), Some(value.clone()), false)
   } else if let Some((lower, upper)) = between {
     (lower, upper, true)
@@ -618,20 +1357,44 @@ fn paginate_str_index<'a>(
     (None, None, true)
   };
 
+  // when paging through an equals block, the cursor can only ever point
+  // inside of it, regardless of the value encoded in the cursor string.
+  let cursor = cursor.and_then(|(x, id)| Some((equals.clone().unwrap_or(x), id)));
+
+  if id_order != order {
+    let upper = stop.and_then(|x| Some((x, is_exclusive)));
+    return paginate_tiebroken(
+      store,
+      map,
+      start,
+      upper,
+      cursor,
+      order,
+      id_order,
+      limit,
+      |x| x.clone(),
+    );
+  }
+
   let iter = if let Some(cur) = cursor {
-    let start = Some(Bound::Exclusive((cur, PhantomData)));
-    let stop = stop
+    // bound the value range by its first and last keys, so that the cursor
+    // and the bounds are all (String, ContractID) keys in the same map.
+    let cursor_bound = Some(Bound::Exclusive((cur, PhantomData)));
+    let lower = start
+      .and_then(|x| Some(Bound::Inclusive(((x, ContractID::MIN), PhantomData))))
+      .or(None);
+    let upper = stop
       .and_then(|x| {
         Some(if is_exclusive {
-          Bound::Exclusive(((x, 0), PhantomData))
+          Bound::Exclusive(((x, ContractID::MIN), PhantomData))
         } else {
-          Bound::Inclusive(((x, 0), PhantomData))
+          Bound::Inclusive(((x, ContractID::MAX), PhantomData))
         })
       })
       .or(None);
     match order {
-      Order::Ascending => map.range(store, start, stop, order),
-      Order::Descending => map.range(store, stop, start, order),
+      Order::Ascending => map.range(store, cursor_bound, upper, order),
+      Order::Descending => map.range(store, lower, cursor_bound, order),
     }
   } else {
     map.prefix_range(
@@ -722,28 +1485,165 @@ fn paginate_addr_index<'a>(
   );
 }
 
+/// Narrow timestamp bounds to before or after the current block time. Since
+/// the upper bound is exclusive, BeforeNow excludes the current block time.
+fn apply_relative_bound(
+  env: &Env,
+  between: Option<(Option<Timestamp>, Option<Timestamp>)>,
+  relative_to_now: Option<RelativeBound>,
+) -> Option<(Option<Timestamp>, Option<Timestamp>)> {
+  let now = env.block.time;
+  let (lower, upper) = between.unwrap_or((None, None));
+  match relative_to_now {
+    Some(RelativeBound::BeforeNow) => Some((
+      lower,
+      Some(upper.and_then(|t| Some(t.min(now))).unwrap_or(now)),
+    )),
+    Some(RelativeBound::AfterNow) => Some((
+      Some(lower.and_then(|t| Some(t.max(now))).unwrap_or(now)),
+      upper,
+    )),
+    None => between,
+  }
+}
+
+/// Page through an index in value order, but with the ID's of contracts that
+/// share a value in the opposite order. The index itself orders ID's the same
+/// way as values, so this takes one extra range per distinct value on the
+/// page: one to find the next value and another to read its ID's.
+fn paginate_tiebroken<'a, K, F>(
+  store: &dyn Storage,
+  map: &Map<'a, (K, ContractID), bool>,
+  lower: Option<K>,
+  upper: Option<(K, bool)>,
+  cursor: Option<(K, ContractID)>,
+  order: Order,
+  id_order: Order,
+  limit: u32,
+  to_string: F,
+) -> Result<Vec<(String, ContractID)>, ContractError>
+where
+  K: Clone + 'static,
+  (K, ContractID): PrimaryKey<'a> + KeyDeserialize<Output = (K, ContractID)>,
+  F: Fn(&K) -> String,
+{
+  let limit = limit as usize;
+  let make_lower = || {
+    lower
+      .clone()
+      .and_then(|x| Some(Bound::Inclusive(((x, ContractID::MIN), PhantomData))))
+  };
+  let make_upper = || {
+    upper.clone().and_then(|(x, is_exclusive)| {
+      Some(if is_exclusive {
+        Bound::Exclusive(((x, ContractID::MIN), PhantomData))
+      } else {
+        Bound::Inclusive(((x, ContractID::MAX), PhantomData))
+      })
+    })
+  };
+
+  let mut keys: Vec<(String, ContractID)> = Vec::with_capacity(limit);
+  let mut last_value: Option<K> = None;
+
+  // finish reading the ID's of the value the cursor points into
+  if let Some((value, id)) = cursor {
+    let (min, max) = match id_order {
+      Order::Ascending => (
+        Bound::Exclusive(((value.clone(), id), PhantomData)),
+        Bound::Inclusive(((value.clone(), ContractID::MAX), PhantomData)),
+      ),
+      Order::Descending => (
+        Bound::Inclusive(((value.clone(), ContractID::MIN), PhantomData)),
+        Bound::Exclusive(((value.clone(), id), PhantomData)),
+      ),
+    };
+    for result in map.keys(store, Some(min), Some(max), id_order).take(limit) {
+      let (x, id) = result?;
+      keys.push((to_string(&x), id));
+    }
+    last_value = Some(value);
+  }
+
+  while keys.len() < limit {
+    // find the next distinct value
+    let (min, max) = match (&last_value, order) {
+      (Some(v), Order::Ascending) => (
+        Some(Bound::Exclusive((
+          (v.clone(), ContractID::MAX),
+          PhantomData,
+        ))),
+        make_upper(),
+      ),
+      (Some(v), Order::Descending) => (
+        make_lower(),
+        Some(Bound::Exclusive((
+          (v.clone(), ContractID::MIN),
+          PhantomData,
+        ))),
+      ),
+      (None, _) => (make_lower(), make_upper()),
+    };
+    let value = match map.keys(store, min, max, order).next() {
+      Some(result) => result?.0,
+      None => break,
+    };
+
+    // read its ID's in the tiebreak order
+    let min = Bound::Inclusive(((value.clone(), ContractID::MIN), PhantomData));
+    let max = Bound::Inclusive(((value.clone(), ContractID::MAX), PhantomData));
+    for result in map
+      .keys(store, Some(min), Some(max), id_order)
+      .take(limit - keys.len())
+    {
+      let (x, id) = result?;
+      keys.push((to_string(&x), id));
+    }
+    last_value = Some(value);
+  }
+
+  Ok(keys)
+}
+
 fn paginate_ts_index<'a>(
   store: &dyn Storage,
   map: &Map<'a, (u64, ContractID), bool>,
   equals: Option<Timestamp>,
   between: Option<(Option<Timestamp>, Option<Timestamp>)>,
   order: Order,
+  id_order: Order,
   limit: u32,
   raw_cursor: Option<(String, ContractID)>,
 ) -> Result<Vec<(String, ContractID)>, ContractError> {
+  // timestamps are indexed in nanos, so bounds must be too. Cursors aren't
+  // checked, since they come from the index, which holds whatever the
+  // contracts wrote to it.
+  let mut values: Vec<u64> = vec![];
+  values.extend(equals.and_then(|t| Some(t.nanos())));
+  if let Some((l, u)) = between {
+    values.extend(l.and_then(|t| Some(t.nanos())));
+    values.extend(u.and_then(|t| Some(t.nanos())));
+  }
+  for nanos in values.iter() {
+    if *nanos != 0 && *nanos < MIN_PLAUSIBLE_NANOS {
+      return Err(ContractError::ImplausibleTimestamp { nanos: *nanos });
+    }
+  }
+
   paginate_u64_index(
     store,
     map,
-    equals.and_then(|x| Some(x.nanos())).or(None),
+    equals.and_then(|x| Some(x.nanos().into())).or(None),
     between
       .and_then(|(l, u)| {
         Some((
-          l.and_then(|t| Some(t.nanos())).or(None),
-          u.and_then(|t| Some(t.nanos())).or(None),
+          l.and_then(|t| Some(t.nanos().into())).or(None),
+          u.and_then(|t| Some(t.nanos().into())).or(None),
         ))
       })
       .or(None),
     order,
+    id_order,
     limit,
     raw_cursor,
   )
@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_std::{Addr, Deps, Env};
+
+use crate::{
+  error::ContractError,
+  models::ContractID,
+  msg::{ReadUnionResponse, UnionTarget},
+  state::ID_2_ADDR,
+};
+
+use super::read::{read, DEFAULT_LIMIT, MAX_LIMIT, MIN_LIMIT};
+
+/// Max number of targets in a ReadUnion
+pub const MAX_UNION_TARGETS: usize = 10;
+
+/// Merge the contracts matched by several targets into one stream, paged by
+/// contract ID. Each target contributes only its first `limit` contracts,
+/// read in its own order, so mixed orders don't conflict: they decide which
+/// contracts make it into the union, while the union itself is always
+/// ordered by ID, ascending unless desc is true. A contract matched by more
+/// than one target appears once.
+pub fn read_union(
+  deps: Deps,
+  env: &Env,
+  targets: &[UnionTarget],
+  maybe_desc: Option<bool>,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<ContractID>,
+) -> Result<ReadUnionResponse, ContractError> {
+  if targets.is_empty() || targets.len() > MAX_UNION_TARGETS {
+    return Err(ContractError::InvalidSetSize {
+      max: MAX_UNION_TARGETS as u32,
+    });
+  }
+
+  let limit = maybe_limit
+    .unwrap_or(DEFAULT_LIMIT)
+    .clamp(MIN_LIMIT, MAX_LIMIT) as usize;
+
+  let mut ids: BTreeSet<ContractID> = BTreeSet::new();
+  for union_target in targets.iter() {
+    let page = read(
+      deps,
+      env,
+      &union_target.target,
+      union_target.desc,
+      union_target.limit,
+      None,
+      None,
+      None,
+      None,
+      None,
+      Some(true),
+      None,
+      None,
+      None,
+      None,
+    )?;
+    ids.extend(
+      page
+        .raw_keys
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(_, id)| id),
+    );
+  }
+
+  // fetch one more ID than the limit to tell whether there's another page
+  let page_ids: Vec<ContractID> = if maybe_desc.unwrap_or(false) {
+    ids
+      .into_iter()
+      .rev()
+      .filter(|id| maybe_cursor.map_or(true, |cursor| *id < cursor))
+      .take(limit + 1)
+      .collect()
+  } else {
+    ids
+      .into_iter()
+      .filter(|id| maybe_cursor.map_or(true, |cursor| *id > cursor))
+      .take(limit + 1)
+      .collect()
+  };
+
+  let has_more = page_ids.len() > limit;
+  let mut items: Vec<(ContractID, Addr)> = Vec::with_capacity(limit);
+  for id in page_ids.into_iter().take(limit) {
+    items.push((id, ID_2_ADDR.load(deps.storage, id)?));
+  }
+
+  let cursor = if has_more {
+    items.last().and_then(|(id, _)| Some(*id))
+  } else {
+    None
+  };
+
+  Ok(ReadUnionResponse { items, cursor })
+}
@@ -0,0 +1,27 @@
+use cosmwasm_std::{Addr, Deps};
+
+use crate::{
+  error::ContractError,
+  msg::RecordResponse,
+  state::{
+    get_contract_id, get_contract_relationships, get_contract_tags, ID_2_INDEXED_VALUES, METADATA,
+  },
+};
+
+/// Get everything the repo knows about a contract in one call, i.e. its
+/// metadata, indexed values, tags and relationships.
+pub fn record(
+  deps: Deps,
+  contract_addr: &Addr,
+) -> Result<RecordResponse, ContractError> {
+  let contract_id = get_contract_id(deps.storage, contract_addr)?;
+  Ok(RecordResponse {
+    addr: contract_addr.clone(),
+    meta: METADATA.may_load(deps.storage, contract_addr.clone())?,
+    values: ID_2_INDEXED_VALUES
+      .may_load(deps.storage, contract_id)?
+      .map(|values| values.into()),
+    tags: get_contract_tags(deps.storage, contract_id)?,
+    relationships: get_contract_relationships(deps.storage, contract_id)?,
+  })
+}
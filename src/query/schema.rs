@@ -0,0 +1,37 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+
+use crate::{
+  error::ContractError,
+  models::{IndexMetadata, IndexType, Slot},
+  msg::{SchemaResponse, SlotSchema},
+  state::get_index_meta_map,
+};
+
+/// Describe the slots in use for each index type, for building filter UIs.
+pub fn schema(deps: Deps) -> Result<SchemaResponse, ContractError> {
+  Ok(SchemaResponse {
+    uint64: get_slot_schemas(deps, IndexType::Uint64)?,
+    uint128: get_slot_schemas(deps, IndexType::Uint128)?,
+    text: get_slot_schemas(deps, IndexType::Text)?,
+    timestamp: get_slot_schemas(deps, IndexType::Timestamp)?,
+    boolean: get_slot_schemas(deps, IndexType::Boolean)?,
+  })
+}
+
+fn get_slot_schemas(
+  deps: Deps,
+  kind: IndexType,
+) -> Result<Vec<SlotSchema>, ContractError> {
+  Ok(
+    get_index_meta_map(kind)
+      .range(deps.storage, None, None, Order::Ascending)
+      .collect::<StdResult<Vec<(Slot, IndexMetadata)>>>()?
+      .into_iter()
+      .map(|(slot, meta)| SlotSchema {
+        slot,
+        name: meta.name,
+        size: meta.size,
+      })
+      .collect(),
+  )
+}
@@ -0,0 +1,27 @@
+use cosmwasm_std::Deps;
+
+use crate::{
+  error::ContractError,
+  models::{IndexType, Slot, SLOT_COUNT},
+  msg::SlotSizeResponse,
+  state::get_index_meta_map,
+};
+
+/// Return the number of contracts with a value in the index slot, as
+/// maintained in its metadata, without scanning the index.
+pub fn slot_size(
+  deps: Deps,
+  kind: IndexType,
+  slot: Slot,
+) -> Result<SlotSizeResponse, ContractError> {
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+
+  let size = get_index_meta_map(kind)
+    .may_load(deps.storage, slot)?
+    .map(|meta| meta.size)
+    .unwrap_or(0);
+
+  Ok(SlotSizeResponse { kind, slot, size })
+}
@@ -0,0 +1,56 @@
+use cosmwasm_std::{Deps, Order};
+
+use crate::{
+  error::ContractError,
+  models::IndexType,
+  msg::StatsResponse,
+  state::{get_index_meta_map, COUNT, PRESETS, RELATIONSHIPS, TAGGED_CONTRACT_IDS, VIRTUAL_COUNT},
+};
+
+/// Upper limit on entries counted per map in a stats query
+pub const MAX_STATS_SCAN: usize = 1000;
+
+/// Return a summary of how much the repo has grown. `count`, `virtual_count`,
+/// and `total_indexed` come from maintained counters and are exact. The totals for
+/// tags, relationships, and presets come from scanning their maps and are
+/// capped at MAX_STATS_SCAN, so a value equal to the cap means "at least".
+pub fn stats(deps: Deps) -> Result<StatsResponse, ContractError> {
+  let mut total_indexed: u64 = 0;
+
+  for kind in [
+    IndexType::Uint64,
+    IndexType::Uint128,
+    IndexType::Timestamp,
+    IndexType::Text,
+    IndexType::Boolean,
+  ] {
+    for result in get_index_meta_map(kind).range(deps.storage, None, None, Order::Ascending) {
+      let (_, meta) = result?;
+      total_indexed += meta.size;
+    }
+  }
+
+  let total_tags = TAGGED_CONTRACT_IDS
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(MAX_STATS_SCAN)
+    .count() as u64;
+
+  let total_relationships = RELATIONSHIPS
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(MAX_STATS_SCAN)
+    .count() as u64;
+
+  let total_presets = PRESETS
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(MAX_STATS_SCAN)
+    .count() as u64;
+
+  Ok(StatsResponse {
+    count: COUNT.may_load(deps.storage)?.unwrap_or(0),
+    virtual_count: VIRTUAL_COUNT.may_load(deps.storage)?.unwrap_or(0),
+    total_indexed,
+    total_tags,
+    total_relationships,
+    total_presets,
+  })
+}
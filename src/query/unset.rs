@@ -0,0 +1,72 @@
+use std::marker::PhantomData;
+
+use cosmwasm_std::{Addr, Deps, Order};
+use cw_storage_plus::Bound;
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, IndexType, Slot, SLOT_COUNT},
+  msg::UnsetResponse,
+  state::{ID_2_ADDR, ID_2_INDEXED_VALUES},
+};
+
+use super::read::{scan_budget, DEFAULT_LIMIT, MAX_LIMIT, MIN_LIMIT};
+
+/// Page through contracts that have no value in the given index slot, ordered
+/// by contract ID. There's no index over unset values, so this is a linear
+/// scan over each contract's indexed values, reading at most `max_scan`
+/// entries per call. As a result, a page can hold fewer than `limit` contracts
+/// while more remain (in which case `truncated` is set), so keep following the
+/// cursor until it's None.
+pub fn unset(
+  deps: Deps,
+  kind: IndexType,
+  slot: Slot,
+  maybe_limit: Option<u32>,
+  maybe_cursor: Option<ContractID>,
+  maybe_max_scan: Option<u32>,
+) -> Result<UnsetResponse, ContractError> {
+  if slot >= SLOT_COUNT {
+    return Err(ContractError::SlotOutOfBounds { slot });
+  }
+
+  let limit = maybe_limit
+    .unwrap_or(DEFAULT_LIMIT)
+    .clamp(MIN_LIMIT, MAX_LIMIT) as usize;
+
+  let max_scan = scan_budget(maybe_max_scan);
+  let start = maybe_cursor
+    .and_then(|id| Some(Bound::Exclusive((id, PhantomData))))
+    .or(None);
+
+  let mut contracts: Vec<Addr> = Vec::with_capacity(limit);
+  let mut cursor: Option<ContractID> = None;
+  let mut n_scanned: u32 = 0;
+  let mut truncated = false;
+
+  for entry in ID_2_INDEXED_VALUES.range(deps.storage, start, None, Order::Ascending) {
+    let (id, values) = entry?;
+    n_scanned += 1;
+    if values.is_unset(kind, slot) {
+      // skip IDs whose create never completed
+      if let Some(addr) = ID_2_ADDR.may_load(deps.storage, id)? {
+        contracts.push(addr);
+      }
+    }
+    if contracts.len() == limit {
+      cursor = Some(id);
+      break;
+    }
+    if n_scanned == max_scan {
+      cursor = Some(id);
+      truncated = true;
+      break;
+    }
+  }
+
+  Ok(UnsetResponse {
+    contracts,
+    cursor,
+    truncated,
+  })
+}
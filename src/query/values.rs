@@ -13,5 +13,7 @@ pub fn values(
 ) -> Result<ValuesResponse, ContractError> {
   let contract_id = get_contract_id(deps.storage, contract_addr)?;
   let values = ID_2_INDEXED_VALUES.load(deps.storage, contract_id)?;
-  Ok(ValuesResponse { values })
+  Ok(ValuesResponse {
+    values: values.into(),
+  })
 }
@@ -0,0 +1,29 @@
+use cosmwasm_std::Deps;
+
+use crate::{
+  error::ContractError,
+  models::{ContractID, IndexedValuesView, MAX_BATCH_SIZE},
+  msg::ValuesByIdsResponse,
+  state::ID_2_INDEXED_VALUES,
+};
+
+/// Get the keys in each index for a batch of contract ID's, in the order
+/// given, with None for unknown ID's.
+pub fn values_by_ids(
+  deps: Deps,
+  ids: &[ContractID],
+) -> Result<ValuesByIdsResponse, ContractError> {
+  if ids.len() > MAX_BATCH_SIZE {
+    return Err(ContractError::BatchSizeExceeded {
+      max: MAX_BATCH_SIZE as u32,
+    });
+  }
+
+  let mut values: Vec<(ContractID, Option<IndexedValuesView>)> = Vec::with_capacity(ids.len());
+  for id in ids.iter() {
+    let maybe_values = ID_2_INDEXED_VALUES.may_load(deps.storage, *id)?;
+    values.push((*id, maybe_values.map(|values| values.into())));
+  }
+
+  Ok(ValuesByIdsResponse { values })
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::{Addr, Deps, Order};
+
+use crate::{
+  error::ContractError,
+  msg::WalletSummaryResponse,
+  state::{is_allowed, IX_CREATED_BY, PRESETS},
+};
+
+use super::stats::MAX_STATS_SCAN;
+
+/// Summarize a wallet's standing in the repo for per-user dashboards. Like
+/// in stats, the counts are capped at MAX_STATS_SCAN.
+pub fn wallet_summary(
+  deps: Deps,
+  wallet: &Addr,
+) -> Result<WalletSummaryResponse, ContractError> {
+  let created_count = IX_CREATED_BY
+    .prefix(wallet.clone())
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(MAX_STATS_SCAN)
+    .count() as u64;
+
+  let preset_count = PRESETS
+    .prefix(wallet.clone())
+    .keys_raw(deps.storage, None, None, Order::Ascending)
+    .take(MAX_STATS_SCAN)
+    .count() as u64;
+
+  Ok(WalletSummaryResponse {
+    created_count,
+    preset_count,
+    can_create: is_allowed(deps.storage, &deps.querier, wallet, "create")?,
+    can_remove: is_allowed(deps.storage, &deps.querier, wallet, "remove")?,
+  })
+}
@@ -1,11 +1,17 @@
 use crate::models::{
-  ContractMetadata, IndexMetadata, IndexSlotName, IndexedValues, InstantiationPreset, Slot,
+  AddressTag, ContractMetadata, IndexMetadata, IndexSlotName, IndexSlotValue, IndexType,
+  IndexedValues, InstantiationPreset, PendingCreate, Recount, ReverseIndexBackfill, Slot, SlotSwap,
+  MAX_TAG_LEN,
 };
 use crate::msg::InstantiateMsg;
 use crate::{error::ContractError, models::ContractID};
-use cosmwasm_std::{Addr, DepsMut, Empty, Env, MessageInfo, QuerierWrapper, Response, Storage};
+use cosmwasm_std::{
+  Addr, Api, Binary, DepsMut, Empty, Env, MessageInfo, Order, QuerierWrapper, Response, StdResult,
+  Storage, Timestamp,
+};
 use cw_acl::client::Acl;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
+use std::marker::PhantomData;
 
 pub type Uint64IndexMap<'a> = Map<'a, (u64, ContractID), bool>;
 pub type Uint128IndexMap<'a> = Map<'a, (u128, ContractID), bool>;
@@ -25,9 +31,43 @@ pub const DEFAULT_LABEL: Item<Option<String>> = Item::new("default_label");
 /// Default code ID to use in create if not overriden by param
 pub const DEFAULT_CODE_ID: Item<u64> = Item::new("default_code_id");
 
+/// Whether to seed the updated_at index at creation time. When false, a
+/// contract only enters the updated_at index upon its first update.
+pub const SEED_UPDATED_AT: Item<bool> = Item::new("seed_updated_at");
+
+/// Whether create should reject instantiate msgs that aren't valid JSON
+pub const VALIDATE_JSON: Item<bool> = Item::new("validate_json");
+
+/// Whether create should fail, rather than flag the contract in its metadata,
+/// when a new contract doesn't answer the Select query it's asked to verify.
+pub const REJECT_UNVERIFIED: Item<bool> = Item::new("reject_unverified");
+
+/// Whether tags and relationship names must be base64 encoded
+pub const REQUIRE_BASE64_TAGS: Item<bool> = Item::new("require_base64_tags");
+
+/// Whether update should reinsert a contract missing from the updated_at or
+/// rev index, rather than fail with NotInIndex.
+pub const SELF_HEAL_INDICES: Item<bool> = Item::new("self_heal_indices");
+
+/// Denoms that funds sent with create may be in. Any denom if unset.
+pub const REQUIRED_DENOMS: Item<Vec<String>> = Item::new("required_denoms");
+
 /// Total number of contracts in this repo
 pub const COUNT: Item<u64> = Item::new("count");
 
+/// Number of virtual contracts, which don't count toward COUNT
+pub const VIRTUAL_COUNT: Item<u64> = Item::new("virtual_count");
+
+/// ID of the next counted contract. Unlike COUNT, it never decreases, so the
+/// ID of a removed contract is never reused.
+pub const NEXT_ID: Item<ContractID> = Item::new("next_id");
+
+/// ID of the next virtual contract, relative to VIRTUAL_ID_OFFSET
+pub const NEXT_VIRTUAL_ID: Item<ContractID> = Item::new("next_virtual_id");
+
+/// Virtual contract ID's start here so they never clash with counted ones
+pub const VIRTUAL_ID_OFFSET: ContractID = 1 << 48;
+
 /// Address for ACL contract used by this repo
 pub const ACL_CONTRACT_ADDR: Item<Option<Addr>> = Item::new("acl_contract_addr");
 
@@ -38,9 +78,35 @@ pub const PRESETS: Map<(Addr, String), InstantiationPreset> = Map::new("presets"
 /// wallet address through a relationship name.
 pub const RELATIONSHIPS: Map<(Addr, String, ContractID), bool> = Map::new("relationships");
 
+/// REL_BY_NAME indexes RELATIONSHIPS by relationship name alone, for querying
+/// contracts across all subject addresses. Each value is the number of
+/// subject addresses with which the contract has the relationship.
+pub const REL_BY_NAME: Map<(String, ContractID), u32> = Map::new("rel_by_name");
+
+/// CONTRACT_RELATIONSHIPS is the reverse of RELATIONSHIPS, for looking up the
+/// relationships of a given contract.
+pub const CONTRACT_RELATIONSHIPS: Map<(ContractID, Addr, String), bool> =
+  Map::new("contract_relationships");
+
 /// TAGGED_ADDRESSES is for looking up contract addresses by string tag
 pub const TAGGED_CONTRACT_IDS: Map<(String, ContractID), bool> = Map::new("tagged_contract_ids");
 
+/// CONTRACT_TAGS is the reverse of TAGGED_CONTRACT_IDS, for looking up the
+/// tags of a given contract.
+pub const CONTRACT_TAGS: Map<(ContractID, String), bool> = Map::new("contract_tags");
+
+/// JSON document attached to each contract, for display config and the like
+pub const DOCS: Map<ContractID, Binary> = Map::new("docs");
+
+/// Labels in use by contracts in this repo, which must be unique
+pub const LABELS: Map<String, ContractID> = Map::new("labels");
+
+/// Label of each contract, for releasing it from LABELS upon removal
+pub const CONTRACT_LABELS: Map<ContractID, String> = Map::new("contract_labels");
+
+/// Creator of each contract, for removing it from IX_CREATED_BY upon removal
+pub const CONTRACT_CREATORS: Map<ContractID, Addr> = Map::new("contract_creators");
+
 /// Lookup table from contract ID to addr
 pub const ID_2_ADDR: Map<ContractID, Addr> = Map::new("id_2_addr");
 
@@ -51,6 +117,16 @@ pub const ADDR_2_ID: Map<Addr, ContractID> = Map::new("addr_2_id");
 /// prefixes when looking up the entry in a custom index below
 pub const ID_2_INDEXED_VALUES: Map<ContractID, IndexedValues> = Map::new("id_2_indexed_values");
 
+/// Every code ID a contract has had, along with the time it was set, keyed
+/// by the change's sequence number within the contract's history
+pub const CODE_ID_HISTORY: Map<(ContractID, u64), (u64, Timestamp)> = Map::new("code_id_history");
+
+/// State stored by create for the reply to consume
+pub const PENDING_CREATES: Map<ContractID, PendingCreate> = Map::new("pending_creates");
+
+/// New code ID of each contract being migrated, for the reply to consume
+pub const PENDING_MIGRATIONS: Map<ContractID, u64> = Map::new("pending_migrations");
+
 /// Metadata stored for each contract in this repo
 pub const METADATA: Map<Addr, ContractMetadata> = Map::new("contract_metadata");
 
@@ -61,6 +137,15 @@ pub const IX_META_STRING: Map<Slot, IndexMetadata> = Map::new("text_index_metada
 pub const IX_META_BOOL: Map<Slot, IndexMetadata> = Map::new("bool_index_metadata");
 pub const IX_META_TIMESTAMP: Map<Slot, IndexMetadata> = Map::new("ts_index_metadata");
 
+/// Progress of an in-flight SwapSlots operation
+pub const SLOT_SWAP: Item<SlotSwap> = Item::new("slot_swap");
+
+/// Progress of an in-flight RecountTotal operation
+pub const RECOUNT: Item<Recount> = Item::new("recount");
+
+/// Progress of an in-flight BackfillReverseIndices operation
+pub const REVERSE_INDEX_BACKFILL: Item<ReverseIndexBackfill> = Item::new("reverse_index_backfill");
+
 /// Built-in indices
 pub const IX_CREATED_BY: AddrIndexMap = Map::new("ix_created_by");
 pub const IX_CREATED_AT: Uint64IndexMap = Map::new("ix_created_at");
@@ -69,6 +154,9 @@ pub const IX_CODE_ID: Uint64IndexMap = Map::new("ix_code_id");
 pub const IX_HEIGHT: Uint64IndexMap = Map::new("ix_height");
 pub const IX_REV: Uint64IndexMap = Map::new("ix_rev");
 
+/// Contracts by code ID, then by created_at nanos within each code ID
+pub const IX_CODE_ID_CREATED: Map<(u64, u64, ContractID), bool> = Map::new("ix_code_id_created");
+
 /// Custom index slots
 pub const IX_U64_0: Uint64IndexMap = Map::new("ix_u64_0");
 pub const IX_U64_1: Uint64IndexMap = Map::new("ix_u64_1");
@@ -103,7 +191,7 @@ pub const IX_TEXT_4: TextIndexMap = Map::new("ix_text_4");
 /// Initialize contract state.
 pub fn initialize(
   deps: DepsMut,
-  _env: &Env,
+  env: &Env,
   info: &MessageInfo,
   msg: &InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -113,17 +201,21 @@ pub fn initialize(
 
   if let Some(default_label) = &msg.default_label {
     if default_label.is_empty() {
-      return Err(ContractError::InvalidLabel {});
+      return Err(ContractError::InvalidLabel { len: 0 });
     }
   }
 
+  if let Some(acl_addr) = &msg.acl_address {
+    validate_acl_address(deps.api, env, acl_addr)?;
+  }
+
   // if no default code ID given, set to the first element of allowed code IDs
   let default_code_id = msg.default_code_id.unwrap_or(msg.code_ids[0]);
 
   if !msg.code_ids.contains(&default_code_id) {
     deps
       .api
-      .debug(format!("cannot set default code ID if not in allowed code ID vec").as_str());
+      .debug("cannot set default code ID if not in allowed code ID vec");
     return Err(ContractError::CodeIdNotAllowed {});
   }
 
@@ -131,13 +223,35 @@ pub fn initialize(
   ACL_CONTRACT_ADDR.save(deps.storage, &msg.acl_address)?;
   DEFAULT_LABEL.save(deps.storage, &msg.default_label)?;
   DEFAULT_CODE_ID.save(deps.storage, &default_code_id)?;
+  SEED_UPDATED_AT.save(deps.storage, &msg.seed_updated_at.unwrap_or(true))?;
+  VALIDATE_JSON.save(deps.storage, &msg.validate_json.unwrap_or(false))?;
+  REJECT_UNVERIFIED.save(deps.storage, &msg.reject_unverified.unwrap_or(false))?;
+  REQUIRE_BASE64_TAGS.save(deps.storage, &msg.require_base64_tags.unwrap_or(false))?;
+  SELF_HEAL_INDICES.save(deps.storage, &msg.self_heal_indices.unwrap_or(false))?;
   COUNT.save(deps.storage, &0)?;
+  VIRTUAL_COUNT.save(deps.storage, &0)?;
+  NEXT_ID.save(deps.storage, &0)?;
+  NEXT_VIRTUAL_ID.save(deps.storage, &0)?;
 
   for code_id in msg.code_ids.iter() {
     ALLOWED_CODE_IDS.save(deps.storage, *code_id, &true)?;
   }
 
   if let Some(indices) = &msg.indices {
+    // each slot number may be used by only one index type, if so configured
+    if msg.exclusive_slots.unwrap_or(false) {
+      let mut slot_kinds: Vec<(IndexType, Slot)> = vec![];
+      for x in indices.iter() {
+        let (kind, slot) = x.kind_and_slot();
+        if slot_kinds.iter().any(|(k, s)| *s == slot && *k != kind) {
+          return Err(ContractError::ConflictingParams {
+            msg: format!("slot {} declared for more than one index type", slot),
+          });
+        }
+        slot_kinds.push((kind, slot));
+      }
+    }
+
     for x in indices.iter() {
       match x {
         IndexSlotName::Uint64 { slot, name } => {
@@ -162,16 +276,31 @@ pub fn initialize(
   Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
+/// An ACL address must be valid and can't be the repo itself.
+pub fn validate_acl_address(
+  api: &dyn Api,
+  env: &Env,
+  acl_addr: &Addr,
+) -> Result<(), ContractError> {
+  let acl_addr = api
+    .addr_validate(acl_addr.as_str())
+    .map_err(|_| ContractError::InvalidAclAddress {})?;
+  if acl_addr == env.contract.address {
+    return Err(ContractError::InvalidAclAddress {});
+  }
+  Ok(())
+}
+
 /// Helper function that returns true if given wallet (principal) is authorized
 /// by ACL to the given action. If there's no ACL, we only authorize the sender
 /// if it is the created_by address.
 pub fn is_allowed(
-  storage: &mut dyn Storage,
+  storage: &dyn Storage,
   querier: &QuerierWrapper<Empty>,
   principal: &Addr,
   action: &str,
 ) -> Result<bool, ContractError> {
-  if let Some(acl_addr) = ACL_CONTRACT_ADDR.load(storage)? {
+  if let Some(acl_addr) = ACL_CONTRACT_ADDR.may_load(storage)?.unwrap_or(None) {
     let acl = Acl::new(&acl_addr);
     Ok(acl.is_allowed(querier, principal, action)?)
   } else {
@@ -179,9 +308,60 @@ pub fn is_allowed(
   }
 }
 
-/// increment the collection count, returning pre-incremented value.
+/// increment the collection count, returning a new, never before used ID.
 pub fn get_next_contract_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
-  return Ok(COUNT.update(storage, |n| -> Result<u64, ContractError> { Ok(n + 1) })? - 1);
+  let id = peek_next_contract_id(storage)?;
+  let n = COUNT.may_load(storage)?.unwrap_or(0);
+  COUNT.save(storage, &(n + 1))?;
+  NEXT_ID.save(storage, &(id + 1))?;
+  Ok(id)
+}
+
+/// The ID the next counted create will use, without allocating it.
+pub fn peek_next_contract_id(storage: &dyn Storage) -> Result<ContractID, ContractError> {
+  if let Some(id) = NEXT_ID.may_load(storage)? {
+    return Ok(id);
+  }
+  let n = COUNT.may_load(storage)?.unwrap_or(0);
+  Ok(n.max(get_first_unused_id(storage, 0, VIRTUAL_ID_OFFSET)?))
+}
+
+/// increment the virtual count, returning an ID in the virtual namespace.
+pub fn get_next_virtual_contract_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
+  let n = VIRTUAL_COUNT.may_load(storage)?.unwrap_or(0);
+  VIRTUAL_COUNT.save(storage, &(n + 1))?;
+  let id = match NEXT_VIRTUAL_ID.may_load(storage)? {
+    Some(id) => id,
+    None => {
+      n.max(get_first_unused_id(storage, VIRTUAL_ID_OFFSET, ContractID::MAX)? - VIRTUAL_ID_OFFSET)
+    },
+  };
+  NEXT_VIRTUAL_ID.save(storage, &(id + 1))?;
+  Ok(VIRTUAL_ID_OFFSET + id)
+}
+
+/// The ID after the highest one in [start, end) that's still in use, for
+/// repos that allocated ID's before NEXT_ID existed. An ID removed from the
+/// top of the range leaves no trace and can't be accounted for.
+fn get_first_unused_id(
+  storage: &dyn Storage,
+  start: ContractID,
+  end: ContractID,
+) -> Result<ContractID, ContractError> {
+  let maybe_last_id = ID_2_INDEXED_VALUES
+    .keys(
+      storage,
+      Some(Bound::Inclusive((start, PhantomData))),
+      Some(Bound::Exclusive((end, PhantomData))),
+      Order::Descending,
+    )
+    .next()
+    .transpose()?;
+  Ok(maybe_last_id.map(|id| id + 1).unwrap_or(start))
+}
+
+pub fn is_virtual(contract_id: ContractID) -> bool {
+  contract_id >= VIRTUAL_ID_OFFSET
 }
 
 // Was the given contract address created through this contract's `create`?
@@ -203,6 +383,163 @@ pub fn get_contract_id(
   }
 }
 
+/// Append a code ID to a contract's code ID history.
+pub fn record_code_id(
+  storage: &mut dyn Storage,
+  contract_id: ContractID,
+  code_id: u64,
+  time: Timestamp,
+) -> Result<(), ContractError> {
+  // a contract can return to an earlier code ID, so each change gets its own
+  // entry after the last one.
+  let seq = match CODE_ID_HISTORY
+    .prefix(contract_id)
+    .keys(storage, None, None, Order::Descending)
+    .next()
+  {
+    Some(last_seq) => last_seq? + 1,
+    None => 0,
+  };
+  CODE_ID_HISTORY.save(storage, (contract_id, seq), &(code_id, time))?;
+  Ok(())
+}
+
+/// Move a contract to a new code ID in its metadata and the code ID indices,
+/// recording the change in its code ID history. The caller saves the metadata.
+pub fn set_code_id(
+  storage: &mut dyn Storage,
+  meta: &mut ContractMetadata,
+  code_id: u64,
+  time: Timestamp,
+) -> Result<(), ContractError> {
+  let contract_id = meta.id;
+  let created_at = meta.created_at.nanos();
+  IX_CODE_ID.remove(storage, (meta.code_id, contract_id));
+  IX_CODE_ID.save(storage, (code_id, contract_id), &true)?;
+  IX_CODE_ID_CREATED.remove(storage, (meta.code_id, created_at, contract_id));
+  IX_CODE_ID_CREATED.save(storage, (code_id, created_at, contract_id), &true)?;
+  record_code_id(storage, contract_id, code_id, time)?;
+  meta.code_id = code_id;
+  Ok(())
+}
+
+/// Check a tag or relationship name before it becomes part of a storage key.
+/// The client base64 encodes these (see UpdateBuilder), and the contract
+/// stores them as given, so it only enforces the encoding when configured to.
+pub fn validate_tag(
+  storage: &dyn Storage,
+  tag: &String,
+) -> Result<(), ContractError> {
+  if tag.is_empty() || tag.len() > MAX_TAG_LEN {
+    return Err(ContractError::InvalidTag {
+      msg: format!("length must be between 1 and {}", MAX_TAG_LEN),
+    });
+  }
+  if REQUIRE_BASE64_TAGS.may_load(storage)?.unwrap_or(false) && Binary::from_base64(tag).is_err() {
+    return Err(ContractError::InvalidTag {
+      msg: "not base64 encoded".to_string(),
+    });
+  }
+  Ok(())
+}
+
+/// Tag a contract, updating CONTRACT_TAGS to match.
+pub fn save_tag(
+  storage: &mut dyn Storage,
+  tag: &String,
+  contract_id: ContractID,
+) -> Result<(), ContractError> {
+  validate_tag(storage, tag)?;
+  TAGGED_CONTRACT_IDS.save(storage, (tag.clone(), contract_id), &true)?;
+  CONTRACT_TAGS.save(storage, (contract_id, tag.clone()), &true)?;
+  Ok(())
+}
+
+/// Untag a contract, updating CONTRACT_TAGS to match.
+pub fn remove_tag(
+  storage: &mut dyn Storage,
+  tag: &String,
+  contract_id: ContractID,
+) {
+  TAGGED_CONTRACT_IDS.remove(storage, (tag.clone(), contract_id));
+  CONTRACT_TAGS.remove(storage, (contract_id, tag.clone()));
+}
+
+/// Get the tags of a contract, in order.
+pub fn get_contract_tags(
+  storage: &dyn Storage,
+  contract_id: ContractID,
+) -> Result<Vec<String>, ContractError> {
+  Ok(
+    CONTRACT_TAGS
+      .prefix(contract_id)
+      .keys(storage, None, None, Order::Ascending)
+      .collect::<StdResult<Vec<String>>>()?,
+  )
+}
+
+/// Get the relationships of a contract, ordered by subject address.
+pub fn get_contract_relationships(
+  storage: &dyn Storage,
+  contract_id: ContractID,
+) -> Result<Vec<AddressTag>, ContractError> {
+  Ok(
+    CONTRACT_RELATIONSHIPS
+      .sub_prefix(contract_id)
+      .keys(storage, None, None, Order::Ascending)
+      .map(|result| result.and_then(|(address, tag)| Ok(AddressTag { address, tag })))
+      .collect::<StdResult<Vec<AddressTag>>>()?,
+  )
+}
+
+/// Relate a contract to a subject address, updating REL_BY_NAME and
+/// CONTRACT_RELATIONSHIPS to match. Returns false if already related.
+pub fn save_relationship(
+  storage: &mut dyn Storage,
+  subject: &Addr,
+  name: &String,
+  contract_id: ContractID,
+) -> Result<bool, ContractError> {
+  validate_tag(storage, name)?;
+  let key = (subject.clone(), name.clone(), contract_id);
+  if !RELATIONSHIPS.has(storage, key.clone()) {
+    RELATIONSHIPS.save(storage, key, &true)?;
+    CONTRACT_RELATIONSHIPS.save(storage, (contract_id, subject.clone(), name.clone()), &true)?;
+    REL_BY_NAME.update(
+      storage,
+      (name.clone(), contract_id),
+      |n| -> Result<u32, ContractError> { Ok(n.unwrap_or(0) + 1) },
+    )?;
+    return Ok(true);
+  }
+  Ok(false)
+}
+
+/// Unrelate a contract from a subject address, updating REL_BY_NAME and
+/// CONTRACT_RELATIONSHIPS to match. Returns false if not related.
+pub fn remove_relationship(
+  storage: &mut dyn Storage,
+  subject: &Addr,
+  name: &String,
+  contract_id: ContractID,
+) -> Result<bool, ContractError> {
+  let key = (subject.clone(), name.clone(), contract_id);
+  if RELATIONSHIPS.has(storage, key.clone()) {
+    RELATIONSHIPS.remove(storage, key);
+    CONTRACT_RELATIONSHIPS.remove(storage, (contract_id, subject.clone(), name.clone()));
+    let n = REL_BY_NAME
+      .may_load(storage, (name.clone(), contract_id))?
+      .unwrap_or(0);
+    if n > 1 {
+      REL_BY_NAME.save(storage, (name.clone(), contract_id), &(n - 1))?;
+    } else {
+      REL_BY_NAME.remove(storage, (name.clone(), contract_id));
+    }
+    return Ok(true);
+  }
+  Ok(false)
+}
+
 pub fn get_u64_index(slot: u8) -> Result<Uint64IndexMap<'static>, ContractError> {
   match slot {
     0 => Ok(IX_U64_0),
@@ -258,6 +595,37 @@ pub fn get_bool_index(slot: u8) -> Result<BoolIndexMap<'static>, ContractError>
   }
 }
 
+/// Make sure the principal is authorized to write to each of the given slots
+/// that requires an ACL action.
+pub fn ensure_slots_writable(
+  storage: &dyn Storage,
+  querier: &QuerierWrapper<Empty>,
+  principal: &Addr,
+  values: &[IndexSlotValue],
+) -> Result<(), ContractError> {
+  for value in values.iter() {
+    let (kind, slot) = value.kind_and_slot();
+    if let Some(meta) = get_index_meta_map(kind).may_load(storage, slot)? {
+      if let Some(action) = &meta.writable_by {
+        if !is_allowed(storage, querier, principal, action)? {
+          return Err(ContractError::NotAuthorized {});
+        }
+      }
+    }
+  }
+  Ok(())
+}
+
+pub fn get_index_meta_map(kind: IndexType) -> Map<'static, Slot, IndexMetadata> {
+  match kind {
+    IndexType::Uint64 => IX_META_U64,
+    IndexType::Uint128 => IX_META_U128,
+    IndexType::Timestamp => IX_META_TIMESTAMP,
+    IndexType::Text => IX_META_STRING,
+    IndexType::Boolean => IX_META_BOOL,
+  }
+}
+
 pub fn increment_index_size<'a>(
   storage: &mut dyn Storage,
   map: &Map<'a, Slot, IndexMetadata>,
@@ -266,6 +634,50 @@ pub fn increment_index_size<'a>(
   update_index_size(storage, map, slot, 1, true)
 }
 
+/// Remove a contract's values from the custom indices, shrinking each one.
+pub fn remove_indexed_values(
+  storage: &mut dyn Storage,
+  contract_id: ContractID,
+  values: &IndexedValues,
+) -> Result<(), ContractError> {
+  for (i, some_value) in values.uint64.iter().enumerate() {
+    if let Some(value) = some_value {
+      let slot = i as Slot;
+      get_u64_index(slot)?.remove(storage, (*value, contract_id));
+      decrement_index_size(storage, &IX_META_U64, slot)?;
+    }
+  }
+  for (i, some_value) in values.uint128.iter().enumerate() {
+    if let Some(value) = some_value {
+      let slot = i as Slot;
+      get_u128_index(slot)?.remove(storage, (*value, contract_id));
+      decrement_index_size(storage, &IX_META_U128, slot)?;
+    }
+  }
+  for (i, some_value) in values.text.iter().enumerate() {
+    if let Some(value) = some_value {
+      let slot = i as Slot;
+      get_text_index(slot)?.remove(storage, (value.clone(), contract_id));
+      decrement_index_size(storage, &IX_META_STRING, slot)?;
+    }
+  }
+  for (i, some_value) in values.timestamp.iter().enumerate() {
+    if let Some(value) = some_value {
+      let slot = i as Slot;
+      get_timestamp_index(slot)?.remove(storage, (*value, contract_id));
+      decrement_index_size(storage, &IX_META_TIMESTAMP, slot)?;
+    }
+  }
+  for (i, some_value) in values.boolean.iter().enumerate() {
+    if let Some(value) = some_value {
+      let slot = i as Slot;
+      get_bool_index(slot)?.remove(storage, (*value, contract_id));
+      decrement_index_size(storage, &IX_META_BOOL, slot)?;
+    }
+  }
+  Ok(())
+}
+
 pub fn decrement_index_size<'a>(
   storage: &mut dyn Storage,
   map: &Map<'a, Slot, IndexMetadata>,
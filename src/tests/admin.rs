@@ -0,0 +1,570 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{
+  from_slice, to_binary, Binary, ContractResult, CosmosMsg, Reply, SubMsgResponse, SubMsgResult,
+  SystemResult, WasmMsg, WasmQuery,
+};
+
+use super::*;
+use crate::models::IndexType;
+use crate::msg::RecordResponse;
+use crate::state::{IX_CODE_ID, IX_CODE_ID_CREATED, METADATA, PENDING_CREATES, VIRTUAL_ID_OFFSET};
+
+#[test]
+fn set_children_admin_updates_contracts_the_repo_admins() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-a", vec![]);
+  create(&mut deps, &env, "child-b", vec![]);
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { admin, .. } = &mut msg {
+    *admin = Some(addr("other-admin"));
+  }
+  create_as(&mut deps, &env, CREATOR, "child-c", msg);
+
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetChildrenAdmin {
+      contract_addrs: vec![addr("child-a"), addr("child-b"), addr("child-c")],
+      new_admin: addr("new-admin"),
+    },
+  )
+  .unwrap();
+
+  // child-c isn't administered by the repo, so it's skipped
+  let msgs: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+  assert_eq!(
+    msgs,
+    vec![
+      CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+        contract_addr: "child-a".to_string(),
+        admin: "new-admin".to_string(),
+      }),
+      CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+        contract_addr: "child-b".to_string(),
+        admin: "new-admin".to_string(),
+      }),
+    ]
+  );
+  for (child, admin) in [
+    ("child-a", "new-admin"),
+    ("child-b", "new-admin"),
+    ("child-c", "other-admin"),
+  ] {
+    let meta = METADATA.load(&deps.storage, addr(child)).unwrap();
+    assert_eq!(meta.admin, Some(addr(admin)));
+  }
+
+  // only the ACL (here, the repo's creator) may transfer admin rights
+  let err = admin_as(
+    &mut deps,
+    "someone",
+    AdminMsg::SetChildrenAdmin {
+      contract_addrs: vec![addr("child-a")],
+      new_admin: addr("someone"),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+}
+
+#[test]
+fn acl_address_must_be_valid_and_not_the_repo() {
+  let repo_addr = mock_env().contract.address;
+  for acl_addr in [repo_addr, addr("x")].iter() {
+    let mut msg = instantiate_msg();
+    msg.acl_address = Some(acl_addr.clone());
+    let err = instantiate(
+      mock_dependencies().as_mut(),
+      mock_env(),
+      mock_info(CREATOR, &[]),
+      msg,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidAclAddress {}));
+
+    let mut deps = setup();
+    let err = admin_as(
+      &mut deps,
+      CREATOR,
+      AdminMsg::SetAcl {
+        acl_contract_addr: acl_addr.clone(),
+      },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidAclAddress {}));
+  }
+}
+
+#[test]
+fn migrate_reply_rekeys_the_code_id_indices() {
+  let mut deps = setup();
+  let env = mock_env();
+  let id = create(&mut deps, &env, "child", vec![]);
+  let created_at = env.block.time.nanos();
+
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Migrate {
+      contract_addr: addr("child"),
+      new_code_id: 2,
+      msg: Binary::from(b"{}"),
+    },
+  )
+  .unwrap();
+  assert!(matches!(
+    &res.messages[0].msg,
+    CosmosMsg::Wasm(WasmMsg::Migrate { new_code_id: 2, .. })
+  ));
+  reply(
+    deps.as_mut(),
+    env.clone(),
+    Reply {
+      id: res.messages[0].id,
+      result: SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        data: None,
+      }),
+    },
+  )
+  .unwrap();
+
+  assert!(!IX_CODE_ID.has(&deps.storage, (CODE_ID, id)));
+  assert!(IX_CODE_ID.has(&deps.storage, (2, id)));
+  assert!(!IX_CODE_ID_CREATED.has(&deps.storage, (CODE_ID, created_at, id)));
+  assert!(IX_CODE_ID_CREATED.has(&deps.storage, (2, created_at, id)));
+  assert_eq!(
+    METADATA.load(&deps.storage, addr("child")).unwrap().code_id,
+    2
+  );
+}
+
+#[test]
+fn migrate_rejects_code_ids_that_arent_allowed() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let err = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Migrate {
+      contract_addr: addr("child"),
+      new_code_id: 99,
+      msg: Binary::from(b"{}"),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::CodeIdNotAllowed {}));
+}
+
+#[test]
+fn update_contract_admin_hands_off_or_clears_the_admin() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-a", vec![]);
+  create(&mut deps, &env, "child-b", vec![]);
+
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::UpdateContractAdmin {
+      contract_addr: addr("child-a"),
+      admin: Some(addr("new-admin")),
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    res.messages[0].msg,
+    CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+      contract_addr: "child-a".to_string(),
+      admin: "new-admin".to_string(),
+    })
+  );
+  let meta = METADATA.load(&deps.storage, addr("child-a")).unwrap();
+  assert_eq!(meta.admin, Some(addr("new-admin")));
+
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::UpdateContractAdmin {
+      contract_addr: addr("child-b"),
+      admin: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    res.messages[0].msg,
+    CosmosMsg::Wasm(WasmMsg::ClearAdmin {
+      contract_addr: "child-b".to_string(),
+    })
+  );
+  let meta = METADATA.load(&deps.storage, addr("child-b")).unwrap();
+  assert_eq!(meta.admin, None);
+
+  // the repo no longer administers child-a, so it can't hand it off again
+  let err = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::UpdateContractAdmin {
+      contract_addr: addr("child-a"),
+      admin: Some(addr("other-admin")),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotContractAdmin {}));
+}
+
+/// Point the repo at an ACL that answers every check with `is_allowed`,
+/// logging the action each check was made for.
+fn mock_acl(
+  deps: &mut MockDeps,
+  is_allowed: bool,
+) -> Rc<RefCell<Vec<String>>> {
+  let checked: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(vec![]));
+  let log = checked.clone();
+  deps.querier.update_wasm(move |query| match query {
+    WasmQuery::Smart { msg, .. } => {
+      let (_, action): (String, String) = from_slice(msg.as_slice()).unwrap();
+      log.borrow_mut().push(action);
+      SystemResult::Ok(ContractResult::Ok(to_binary(&is_allowed).unwrap()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  admin_as(
+    deps,
+    CREATOR,
+    AdminMsg::SetAcl {
+      acl_contract_addr: addr("acl"),
+    },
+  )
+  .unwrap();
+  checked.borrow_mut().clear();
+  checked
+}
+
+#[test]
+fn each_admin_msg_is_checked_against_its_acl_action() {
+  let mut deps = setup();
+  let checked = mock_acl(&mut deps, false);
+  let msgs = vec![
+    (
+      AdminMsg::BackfillReverseIndices { limit: None },
+      "backfill_reverse_indices",
+    ),
+    (
+      AdminMsg::BatchSetIndices { updates: vec![] },
+      "batch_set_indices",
+    ),
+    (
+      AdminMsg::FinalizeCreate {
+        contract_id: 0,
+        contract_addr: addr("child"),
+        code_id: CODE_ID,
+      },
+      "finalize_create",
+    ),
+    (
+      AdminMsg::Migrate {
+        contract_addr: addr("child"),
+        new_code_id: CODE_ID,
+        msg: Binary::from(b"{}"),
+      },
+      "migrate",
+    ),
+    (AdminMsg::PurgeOrphan { contract_id: 0 }, "purge_orphan"),
+    (
+      AdminMsg::RecountTotal {
+        limit: None,
+        cursor: None,
+      },
+      "recount_total",
+    ),
+    (
+      AdminMsg::Remove {
+        contract_addr: addr("child"),
+      },
+      "remove",
+    ),
+    (
+      AdminMsg::RenameIndex {
+        name: IndexSlotName::Text {
+          slot: 0,
+          name: None,
+        },
+      },
+      "rename_index",
+    ),
+    (
+      AdminMsg::RetagContract {
+        contract_addr: addr("child"),
+        old_tag: "a".to_string(),
+        new_tag: "b".to_string(),
+      },
+      "retag_contract",
+    ),
+    (
+      AdminMsg::SetAcl {
+        acl_contract_addr: addr("other-acl"),
+      },
+      "set_acl",
+    ),
+    (
+      AdminMsg::SetChildrenAdmin {
+        contract_addrs: vec![],
+        new_admin: addr("new-admin"),
+      },
+      "set_children_admin",
+    ),
+    (
+      AdminMsg::SetRequiredDenoms { denoms: None },
+      "set_required_denoms",
+    ),
+    (
+      AdminMsg::SetSelfHealIndices { enabled: true },
+      "set_self_heal_indices",
+    ),
+    (
+      AdminMsg::SetSlotWriter {
+        kind: IndexType::Uint64,
+        slot: 0,
+        action: None,
+      },
+      "set_slot_writer",
+    ),
+    (
+      AdminMsg::SwapSlots {
+        kind: IndexType::Uint64,
+        a: 0,
+        b: 1,
+        limit: None,
+        cursor: None,
+      },
+      "swap_slots",
+    ),
+    (
+      AdminMsg::UpdateAllowedCodeIds { code_ids: vec![] },
+      "update_allowed_code_ids",
+    ),
+    (
+      AdminMsg::UpdateContractAdmin {
+        contract_addr: addr("child"),
+        admin: None,
+      },
+      "update_admin",
+    ),
+  ];
+
+  for (msg, action) in msgs {
+    let err = admin_as(&mut deps, "operator", msg).unwrap_err();
+    assert!(matches!(err, ContractError::NotAuthorized {}));
+    assert_eq!(checked.borrow_mut().pop(), Some(action.to_string()));
+    assert!(checked.borrow().is_empty());
+  }
+}
+
+#[test]
+fn admin_msgs_dispatch_once_the_acl_allows_them() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let checked = mock_acl(&mut deps, true);
+  let msgs = vec![
+    (
+      AdminMsg::UpdateAllowedCodeIds {
+        code_ids: vec![CODE_ID],
+      },
+      "update_allowed_code_ids",
+    ),
+    (
+      AdminMsg::SetSelfHealIndices { enabled: true },
+      "set_self_heal_indices",
+    ),
+    (
+      AdminMsg::RecountTotal {
+        limit: None,
+        cursor: None,
+      },
+      "recount_total",
+    ),
+    (
+      AdminMsg::Remove {
+        contract_addr: addr("child"),
+      },
+      "remove",
+    ),
+  ];
+
+  for (msg, action) in msgs {
+    let res = admin_as(&mut deps, "operator", msg).unwrap();
+    assert_eq!(res.attributes[0].value, action);
+    assert_eq!(checked.borrow_mut().pop(), Some(action.to_string()));
+  }
+
+  // clearing the ACL is left to the creator, whatever the ACL says
+  let err = admin_as(&mut deps, "operator", AdminMsg::ClearAcl {}).unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  admin_as(&mut deps, CREATOR, AdminMsg::ClearAcl {}).unwrap();
+  assert!(checked.borrow().is_empty());
+}
+
+fn retag(
+  deps: &mut MockDeps,
+  sender: &str,
+  old_tag: &str,
+  new_tag: &str,
+) -> Result<Response, ContractError> {
+  admin_as(
+    deps,
+    sender,
+    AdminMsg::RetagContract {
+      contract_addr: addr("child"),
+      old_tag: old_tag.to_string(),
+      new_tag: new_tag.to_string(),
+    },
+  )
+}
+
+#[test]
+fn retag_contract_replaces_one_tag() {
+  let mut deps = setup();
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { tags, .. } = &mut msg {
+    *tags = Some(vec!["tpyo".to_string(), "kept".to_string()]);
+  }
+  let id = create_as(&mut deps, &mock_env(), CREATOR, "child", msg);
+  let tagged = |deps: &MockDeps, tag: &str| {
+    read_all_ids(
+      deps,
+      read_keys_msg(Target::Tag(tag.to_string()), None, 10, None),
+    )
+  };
+
+  let err = retag(&mut deps, "someone", "tpyo", "typo").unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+
+  retag(&mut deps, CREATOR, "tpyo", "typo").unwrap();
+  assert!(tagged(&deps, "tpyo").is_empty());
+  assert_eq!(tagged(&deps, "typo"), vec![id]);
+  assert_eq!(tagged(&deps, "kept"), vec![id]);
+  let record: RecordResponse = query_as(
+    &deps,
+    QueryMsg::Record {
+      contract_addr: addr("child"),
+    },
+  );
+  let mut tags = record.tags;
+  tags.sort();
+  assert_eq!(tags, vec!["kept".to_string(), "typo".to_string()]);
+
+  // and back again
+  retag(&mut deps, CREATOR, "typo", "tpyo").unwrap();
+  assert_eq!(tagged(&deps, "tpyo"), vec![id]);
+  assert!(tagged(&deps, "typo").is_empty());
+
+  let err = retag(&mut deps, CREATOR, "missing", "typo").unwrap_err();
+  assert!(matches!(err, ContractError::NotFound {}));
+  assert!(tagged(&deps, "typo").is_empty());
+}
+
+#[test]
+fn replies_are_routed_by_the_kind_in_their_id() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-0", vec![]);
+  let migrated_id = create(&mut deps, &env, "child-1", vec![]);
+
+  // a create and a migrate pending for the same low bits of the reply ID
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap();
+  let create_reply_id = res.messages[0].id;
+  let pending_id = pending_id(&res);
+  assert_eq!(create_reply_id, ReplyKind::Create.encode(pending_id));
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Migrate {
+      contract_addr: addr("child-1"),
+      new_code_id: 2,
+      msg: Binary::from(b"{}"),
+    },
+  )
+  .unwrap();
+  let migrate_reply_id = res.messages[0].id;
+  assert_eq!(migrate_reply_id, ReplyKind::Migrate.encode(migrated_id));
+  assert_ne!(migrate_reply_id, ReplyKind::Create.encode(migrated_id));
+
+  let ok = || {
+    SubMsgResult::Ok(SubMsgResponse {
+      events: vec![],
+      data: None,
+    })
+  };
+  reply(
+    deps.as_mut(),
+    env.clone(),
+    Reply {
+      id: migrate_reply_id,
+      result: ok(),
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    METADATA
+      .load(&deps.storage, addr("child-1"))
+      .unwrap()
+      .code_id,
+    2
+  );
+  assert!(PENDING_CREATES.has(&deps.storage, pending_id));
+  reply_created(&mut deps, &env, pending_id, "child-2", CODE_ID).unwrap();
+  assert!(!PENDING_CREATES.has(&deps.storage, pending_id));
+
+  // virtual IDs fit below the kind
+  assert_eq!(
+    ReplyKind::decode(ReplyKind::Migrate.encode(VIRTUAL_ID_OFFSET)),
+    Some((ReplyKind::Migrate, VIRTUAL_ID_OFFSET))
+  );
+  let unknown_id = (2 << 56) | pending_id;
+  let err = reply(
+    deps.as_mut(),
+    env,
+    Reply {
+      id: unknown_id,
+      result: ok(),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::UnknownReplyId { id } if id == unknown_id));
+}
+
+#[test]
+fn clear_acl_reverts_to_creator_only() {
+  let mut deps = setup();
+  let checked = mock_acl(&mut deps, false);
+  let update_code_ids = || AdminMsg::UpdateAllowedCodeIds {
+    code_ids: vec![CODE_ID],
+  };
+
+  // the ACL now decides, even for the creator
+  let err = admin_as(&mut deps, CREATOR, update_code_ids()).unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+
+  // only the creator may clear it, without asking the ACL
+  checked.borrow_mut().clear();
+  let err = admin_as(&mut deps, "operator", AdminMsg::ClearAcl {}).unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  admin_as(&mut deps, CREATOR, AdminMsg::ClearAcl {}).unwrap();
+  assert!(checked.borrow().is_empty());
+
+  admin_as(&mut deps, CREATOR, update_code_ids()).unwrap();
+  let err = admin_as(&mut deps, "operator", update_code_ids()).unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  assert!(checked.borrow().is_empty());
+}
@@ -0,0 +1,241 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Order;
+
+use super::*;
+use crate::models::AddressTag;
+use crate::msg::{CheckMappingsResponse, RecordResponse};
+use crate::state::{ADDR_2_ID, CONTRACT_RELATIONSHIPS, CONTRACT_TAGS, NEXT_ID, REL_BY_NAME};
+
+/// Contracts related to two subjects, one of them twice by the same name
+fn create_related(deps: &mut MockDeps) {
+  let rels = vec![
+    vec![("alice", "owned_by"), ("bob", "owned_by")],
+    vec![("alice", "owned_by"), ("alice", "managed_by")],
+    vec![("bob", "managed_by")],
+  ];
+  for (i, rels) in rels.iter().enumerate() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { relationships, .. } = &mut msg {
+      *relationships = Some(
+        rels
+          .iter()
+          .map(|(subject, name)| AddressTag {
+            address: addr(subject),
+            tag: name.to_string(),
+          })
+          .collect(),
+      );
+    }
+    create_as(deps, &mock_env(), CREATOR, &format!("child-{}", i), msg);
+  }
+}
+
+/// Drop the reverse relationship entries, as in a repo from before they existed
+fn strip_reverse_relationships(deps: &mut MockDeps) {
+  for key in CONTRACT_RELATIONSHIPS
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .collect::<Vec<_>>()
+  {
+    CONTRACT_RELATIONSHIPS.remove(&mut deps.storage, key.unwrap());
+  }
+  for key in REL_BY_NAME
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .collect::<Vec<_>>()
+  {
+    REL_BY_NAME.remove(&mut deps.storage, key.unwrap());
+  }
+}
+
+/// Drop every reverse index entry, as in a repo from before they existed
+fn strip_reverse_indices(deps: &mut MockDeps) {
+  strip_reverse_relationships(deps);
+  for key in CONTRACT_TAGS
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .collect::<Vec<_>>()
+  {
+    CONTRACT_TAGS.remove(&mut deps.storage, key.unwrap());
+  }
+}
+
+fn record(deps: &MockDeps) -> RecordResponse {
+  query_as(
+    deps,
+    QueryMsg::Record {
+      contract_addr: addr("child"),
+    },
+  )
+}
+
+/// Run the backfill in chunks of the given size until done, returning the
+/// number of txs it took.
+fn backfill(
+  deps: &mut MockDeps,
+  limit: u32,
+) -> usize {
+  for n in 1..100 {
+    let res = admin_as(
+      deps,
+      CREATOR,
+      AdminMsg::BackfillReverseIndices { limit: Some(limit) },
+    )
+    .unwrap();
+    if res
+      .attributes
+      .iter()
+      .any(|a| a.key == "done" && a.value == "true")
+    {
+      return n;
+    }
+  }
+  panic!("backfill never finished");
+}
+
+#[test]
+fn backfill_restores_reverse_relationships() {
+  let mut deps = setup();
+  create_related(&mut deps);
+  let before = storage_snapshot(&deps);
+
+  strip_reverse_relationships(&mut deps);
+  let owned_by = || Target::RelationshipByName("owned_by".to_string());
+  assert!(read_all_ids(&deps, read_keys_msg(owned_by(), None, 10, None)).is_empty());
+
+  // five relationships, two per chunk, then an empty chunk of tags
+  assert_eq!(backfill(&mut deps, 2), 4);
+  assert_eq!(storage_snapshot(&deps), before);
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(owned_by(), None, 10, None)),
+    vec![0, 1]
+  );
+
+  // rerunning it doesn't count anything twice
+  backfill(&mut deps, 2);
+  assert_eq!(storage_snapshot(&deps), before);
+}
+
+#[test]
+fn backfill_restores_contract_tags() {
+  let mut deps = setup();
+  for (i, tags) in [vec!["red", "blue"], vec!["red"], vec![]]
+    .iter()
+    .enumerate()
+  {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { tags: t, .. } = &mut msg {
+      *t = Some(tags.iter().map(|x| x.to_string()).collect());
+    }
+    create_as(
+      &mut deps,
+      &mock_env(),
+      CREATOR,
+      &format!("child-{}", i),
+      msg,
+    );
+  }
+  let before = storage_snapshot(&deps);
+
+  strip_reverse_indices(&mut deps);
+
+  // an empty chunk of relationships, then three tags, two per chunk
+  assert_eq!(backfill(&mut deps, 2), 3);
+  assert_eq!(storage_snapshot(&deps), before);
+
+  let mut msg = read_keys_msg(Target::All, None, 10, None);
+  if let QueryMsg::Read {
+    raw_keys,
+    with_tags,
+    ..
+  } = &mut msg
+  {
+    *raw_keys = None;
+    *with_tags = Some(true);
+  }
+  let page: ReadResponse = query_as(&deps, msg);
+  let tags: Vec<Vec<String>> = page.page.into_iter().map(|x| x.tags.unwrap()).collect();
+  assert_eq!(
+    tags,
+    vec![
+      vec!["blue".to_string(), "red".to_string()],
+      vec!["red".to_string()],
+      vec![]
+    ]
+  );
+}
+
+#[test]
+fn backfilled_contract_has_a_full_record_and_is_removed_cleanly() {
+  let mut deps = setup();
+  let before = storage_snapshot(&deps);
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    tags,
+    relationships,
+    ..
+  } = &mut msg
+  {
+    *tags = Some(vec!["red".to_string()]);
+    *relationships = Some(vec![AddressTag {
+      address: addr("alice"),
+      tag: "owned_by".to_string(),
+    }]);
+  }
+  create_as(&mut deps, &mock_env(), CREATOR, "child", msg);
+  strip_reverse_indices(&mut deps);
+
+  assert!(record(&deps).tags.is_empty());
+  assert!(record(&deps).relationships.is_empty());
+
+  backfill(&mut deps, 10);
+  assert_eq!(record(&deps).tags, vec!["red".to_string()]);
+  assert_eq!(
+    record(&deps).relationships,
+    vec![AddressTag {
+      address: addr("alice"),
+      tag: "owned_by".to_string(),
+    }]
+  );
+
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+  NEXT_ID.save(&mut deps.storage, &0).unwrap();
+  assert_eq!(storage_snapshot(&deps), before);
+}
+
+#[test]
+fn check_mappings_reports_a_deliberate_desync() {
+  let mut deps = setup();
+  let ids: Vec<ContractID> = (0..4)
+    .map(|i| create(&mut deps, &mock_env(), &format!("child-{}", i), vec![]))
+    .collect();
+  ADDR_2_ID.remove(&mut deps.storage, addr("child-1"));
+  ADDR_2_ID
+    .save(&mut deps.storage, addr("child-3"), &ids[2])
+    .unwrap();
+
+  let mut mismatches: Vec<(ContractID, Addr)> = vec![];
+  let mut cursor: Option<ContractID> = None;
+  loop {
+    let res: CheckMappingsResponse = query_as(
+      &deps,
+      QueryMsg::CheckMappings {
+        limit: Some(2),
+        cursor,
+      },
+    );
+    mismatches.extend(res.mismatches);
+    if res.cursor.is_none() {
+      break;
+    }
+    cursor = res.cursor;
+  }
+  assert_eq!(
+    mismatches,
+    vec![(ids[1], addr("child-1")), (ids[3], addr("child-3"))]
+  );
+}
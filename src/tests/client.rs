@@ -0,0 +1,405 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockQuerier};
+use cosmwasm_std::{
+  to_binary, Binary, ContractResult, QuerierWrapper, SystemResult, Timestamp, Uint128, Uint64,
+  WasmQuery,
+};
+
+use super::update::{update_as, update_msg};
+use super::*;
+use crate::client::{Repository, MAX_READ_ALL_PAGES};
+use crate::models::IndexBounds;
+use crate::msg::{
+  CodeIdHistoryResponse, ContractStateEnvelope, CountResponse, SelectResponse, ValuesResponse,
+};
+
+/// A querier answering every read with the given number of one-contract
+/// pages, counting the reads it answers.
+fn paged_querier(
+  n_pages: usize,
+  reads: Rc<Cell<usize>>,
+) -> MockQuerier {
+  let mut querier = MockQuerier::default();
+  querier.update_wasm(move |query| match query {
+    WasmQuery::Smart { .. } => {
+      reads.set(reads.get() + 1);
+      let page = ReadResponse {
+        page: vec![ContractStateEnvelope {
+          address: addr(&format!("child-{}", reads.get())),
+          meta: None,
+          rev: None,
+          state: None,
+          tags: None,
+        }],
+        count: 1,
+        has_more: reads.get() < n_pages,
+        cursor: if reads.get() < n_pages {
+          Some(("cursor".to_string(), reads.get() as ContractID))
+        } else {
+          None
+        },
+        raw_keys: None,
+        is_first_page: reads.get() == 1,
+        page_size: 1,
+      };
+      SystemResult::Ok(ContractResult::Ok(to_binary(&page).unwrap()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  querier
+}
+
+#[test]
+fn read_all_follows_the_cursor_to_the_last_page() {
+  let reads = Rc::new(Cell::new(0));
+  let querier = paged_querier(3, reads.clone());
+  let repo = Repository::new(&addr("repo"));
+  let envelopes = repo
+    .read_all(&QuerierWrapper::new(&querier), &Target::All, 1)
+    .unwrap();
+  assert_eq!(envelopes.len(), 3);
+  assert_eq!(reads.get(), 3);
+}
+
+#[test]
+fn read_all_fails_rather_than_truncate() {
+  let reads = Rc::new(Cell::new(0));
+  let querier = paged_querier(MAX_READ_ALL_PAGES + 1, reads.clone());
+  let repo = Repository::new(&addr("repo"));
+  assert!(repo
+    .read_all(&QuerierWrapper::new(&querier), &Target::All, 1)
+    .is_err());
+  assert_eq!(reads.get(), MAX_READ_ALL_PAGES);
+}
+
+/// A querier answering every query with the given response.
+fn fixed_querier(response: Binary) -> MockQuerier {
+  let mut querier = MockQuerier::default();
+  querier.update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(response.clone())));
+  querier
+}
+
+#[test]
+fn empty_read_decodes_to_an_empty_page() {
+  let deps = setup();
+  let target = Target::Tag("nothing".to_string());
+  let empty = query(
+    deps.as_ref(),
+    mock_env(),
+    read_keys_msg(target.clone(), None, 10, None),
+  )
+  .unwrap();
+
+  // both the repo's own empty response and a bare object decode cleanly
+  let repo = Repository::new(&addr("repo"));
+  for response in [empty, Binary::from(b"{}")].iter() {
+    let querier = fixed_querier(response.clone());
+    let querier = QuerierWrapper::new(&querier);
+    let page = repo
+      .read(&querier, &target, None, None, None, None, None, None, None)
+      .unwrap();
+    assert!(page.page.is_empty());
+    assert_eq!(page.cursor, None);
+    assert!(!page.has_more);
+    assert!(repo.read_all(&querier, &target, 10).unwrap().is_empty());
+  }
+}
+
+/// A querier expecting only the given msg, answering it with what the repo
+/// itself responds.
+fn repo_querier(
+  deps: &MockDeps,
+  msg: QueryMsg,
+) -> MockQuerier {
+  let response = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+  let mut querier = MockQuerier::default();
+  querier.update_wasm(move |query| match query {
+    WasmQuery::Smart {
+      contract_addr,
+      msg: sent,
+    } => {
+      assert_eq!(contract_addr, "repo");
+      assert_eq!(*sent, to_binary(&msg).unwrap());
+      SystemResult::Ok(ContractResult::Ok(response.clone()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  querier
+}
+
+#[test]
+fn typed_queries_decode_the_repo_responses() {
+  let mut deps = setup();
+  create(
+    &mut deps,
+    &mock_env(),
+    "child",
+    vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(7),
+    }],
+  );
+  let repo = Repository::new(&addr("repo"));
+
+  let querier = repo_querier(&deps, QueryMsg::Count {});
+  let count = repo.count(&QuerierWrapper::new(&querier)).unwrap();
+  assert_eq!(count.count, 1);
+  assert_eq!(count, query_as::<CountResponse>(&deps, QueryMsg::Count {}));
+
+  let msg = QueryMsg::Values {
+    contract_addr: addr("child"),
+  };
+  let querier = repo_querier(&deps, msg.clone());
+  let values = repo
+    .values(&QuerierWrapper::new(&querier), &addr("child"))
+    .unwrap();
+  assert_eq!(values, query_as::<ValuesResponse>(&deps, msg));
+
+  let msg = QueryMsg::Select {
+    wallet: None,
+    fields: Some(vec!["code_ids".to_string()]),
+  };
+  let querier = repo_querier(&deps, msg.clone());
+  let config = repo
+    .select_config(
+      &QuerierWrapper::new(&querier),
+      Some(vec!["code_ids".to_string()]),
+      None,
+    )
+    .unwrap();
+  assert_eq!(config.code_ids, Some(vec![CODE_ID, CODE_ID + 1]));
+  assert_eq!(config, query_as::<SelectResponse>(&deps, msg));
+
+  let msg = QueryMsg::CodeIdHistory {
+    contract_addr: addr("child"),
+  };
+  let querier = repo_querier(&deps, msg.clone());
+  let history = repo
+    .code_id_history(&QuerierWrapper::new(&querier), &addr("child"))
+    .unwrap();
+  assert_eq!(history.history.len(), 1);
+  assert_eq!(history, query_as::<CodeIdHistoryResponse>(&deps, msg));
+}
+
+/// A querier answering queries to "repo" with the repo's state as of now, and
+/// those to its children with an empty state.
+fn contract_querier(deps: &MockDeps) -> MockQuerier {
+  let snapshot = storage_snapshot(deps);
+  let mut querier = MockQuerier::default();
+  querier.update_wasm(move |request| match request {
+    WasmQuery::Smart { contract_addr, msg } if contract_addr == "repo" => {
+      let mut repo = mock_dependencies();
+      for (key, value) in snapshot.iter() {
+        repo.storage.set(key, value);
+      }
+      repo
+        .querier
+        .update_wasm(|_| SystemResult::Ok(ContractResult::Ok(b"{}".into())));
+      let res = query(repo.as_ref(), mock_env(), from_binary(msg).unwrap());
+      SystemResult::Ok(res.map_err(|err| err.to_string()).into())
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  querier
+}
+
+#[test]
+fn select_by_rev_ranks_the_most_revised_first() {
+  let mut deps = setup();
+  let env = mock_env();
+  for i in 0..4 {
+    create(&mut deps, &env, &format!("child-{}", i), vec![]);
+  }
+  for (child, n_updates) in [("child-1", 3u64), ("child-2", 2), ("child-3", 1)].iter() {
+    for i in 0..*n_updates {
+      let value = IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(i + 1),
+      };
+      update_as(&mut deps, &env, child, update_msg(vec![value])).unwrap();
+    }
+  }
+
+  let querier = contract_querier(&deps);
+  let repo = Repository::new(&addr("repo"));
+  let select = |min_rev: u64| -> Vec<Addr> {
+    repo
+      .select_by_rev(
+        &QuerierWrapper::new(&querier),
+        min_rev,
+        None,
+        None,
+        None,
+        None,
+        None,
+      )
+      .unwrap()
+      .page
+      .into_iter()
+      .map(|e| e.address)
+      .collect()
+  };
+  assert_eq!(
+    select(1),
+    vec![addr("child-1"), addr("child-2"), addr("child-3")]
+  );
+  assert_eq!(select(2), vec![addr("child-1"), addr("child-2")]);
+  assert!(select(4).is_empty());
+}
+
+#[test]
+fn bounds_helpers_build_the_expected_variants() {
+  let t = |seconds| Timestamp::from_seconds(seconds);
+  let cases = vec![
+    (
+      IndexBounds::u64_equals(1, 5),
+      IndexBounds::Uint64 {
+        slot: 1,
+        between: None,
+        equals: Some(Uint64::new(5)),
+      },
+    ),
+    (
+      IndexBounds::u64_between(1, Some(5), None),
+      IndexBounds::Uint64 {
+        slot: 1,
+        between: Some((Some(Uint64::new(5)), None)),
+        equals: None,
+      },
+    ),
+    (
+      IndexBounds::u128_equals(2, 5),
+      IndexBounds::Uint128 {
+        slot: 2,
+        between: None,
+        equals: Some(Uint128::new(5)),
+      },
+    ),
+    (
+      IndexBounds::u128_between(2, None, Some(9)),
+      IndexBounds::Uint128 {
+        slot: 2,
+        between: Some((None, Some(Uint128::new(9)))),
+        equals: None,
+      },
+    ),
+    (
+      IndexBounds::timestamp_between(0, Some(t(1)), Some(t(2))),
+      IndexBounds::Timestamp {
+        slot: 0,
+        between: Some((Some(t(1)), Some(t(2)))),
+        equals: None,
+        relative_to_now: None,
+      },
+    ),
+    (
+      IndexBounds::timestamp_in_hour(0, t(2 * 3600 + 59)),
+      IndexBounds::timestamp_between(0, Some(t(2 * 3600)), Some(t(3 * 3600))),
+    ),
+    (
+      IndexBounds::timestamp_in_day(0, t(86400 + 7200)),
+      IndexBounds::timestamp_between(0, Some(t(86400)), Some(t(2 * 86400))),
+    ),
+    (
+      IndexBounds::text_equals(3, "abc"),
+      IndexBounds::Text {
+        slot: 3,
+        between: None,
+        equals: Some("abc".to_string()),
+      },
+    ),
+    (
+      IndexBounds::text_prefix(3, "ab"),
+      IndexBounds::Text {
+        slot: 3,
+        between: Some((Some("ab".to_string()), Some(format!("ab{}", char::MAX)))),
+        equals: None,
+      },
+    ),
+    (
+      IndexBounds::boolean_is(0, true),
+      IndexBounds::Boolean {
+        slot: 0,
+        start: Some(true),
+        stop: None,
+      },
+    ),
+    (
+      IndexBounds::boolean_is(0, false),
+      IndexBounds::Boolean {
+        slot: 0,
+        start: Some(false),
+        stop: Some(true),
+      },
+    ),
+    (
+      IndexBounds::code_id_is(7),
+      IndexBounds::CodeId {
+        between: None,
+        equals: Some(Uint64::new(7)),
+        equals_any: None,
+      },
+    ),
+    (
+      IndexBounds::created_by(&addr("wallet")),
+      IndexBounds::CreatedBy {
+        between: None,
+        equals: Some(addr("wallet")),
+        equals_any: None,
+      },
+    ),
+    (
+      IndexBounds::created_between(Some(t(1)), None),
+      IndexBounds::CreatedAt {
+        between: Some((Some(t(1)), None)),
+        equals: None,
+      },
+    ),
+    (
+      IndexBounds::updated_between(None, Some(t(2))),
+      IndexBounds::UpdatedAt {
+        between: Some((None, Some(t(2)))),
+        equals: None,
+      },
+    ),
+  ];
+  for (built, expected) in cases {
+    assert_eq!(built, expected);
+  }
+}
+
+#[test]
+fn bounds_helpers_select_the_matching_contracts() {
+  let mut deps = setup();
+  let text = |value: &str| IndexSlotValue::Text {
+    slot: 0,
+    value: value.to_string(),
+  };
+  let boolean = |value| IndexSlotValue::Boolean { slot: 0, value };
+  let a = create(
+    &mut deps,
+    &mock_env(),
+    "child-0",
+    vec![text("apple"), boolean(true)],
+  );
+  let b = create(
+    &mut deps,
+    &mock_env(),
+    "child-1",
+    vec![text("apricot"), boolean(false)],
+  );
+  let c = create(
+    &mut deps,
+    &mock_env(),
+    "child-2",
+    vec![text("banana"), boolean(true)],
+  );
+  let ids = |bounds| read_all_ids(&deps, read_keys_msg(Target::Index(bounds), None, 10, None));
+
+  assert_eq!(ids(IndexBounds::text_equals(0, "banana")), vec![c]);
+  assert_eq!(ids(IndexBounds::boolean_is(0, true)), vec![a, c]);
+  assert_eq!(ids(IndexBounds::boolean_is(0, false)), vec![b]);
+  assert_eq!(ids(IndexBounds::code_id_is(CODE_ID)), vec![a, b, c]);
+}
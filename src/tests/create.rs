@@ -0,0 +1,751 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockStorage};
+use cosmwasm_std::{
+  coin, from_binary, to_binary, Binary, Coin, ContractResult, Reply, ReplyOn, SubMsgResponse,
+  SubMsgResult, SystemResult, WasmQuery,
+};
+
+use super::*;
+use crate::models::{AddressTag, IndexType, ReplyKind};
+use crate::msg::{CountResponse, CreateReplyData, NextIdResponse};
+use crate::state::{
+  ACL_CONTRACT_ADDR, ADDR_2_ID, COUNT, DEFAULT_LABEL, ID_2_ADDR, IX_META_U64, IX_UPDATED_AT,
+  METADATA, PENDING_CREATES, VIRTUAL_ID_OFFSET,
+};
+
+#[test]
+fn reply_registers_created_contract() {
+  let mut deps = setup();
+  let env = mock_env();
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap();
+  let id = pending_id(&res);
+  assert!(PENDING_CREATES.has(&deps.storage, id));
+  assert!(!ID_2_ADDR.has(&deps.storage, id));
+
+  let res = reply_created(&mut deps, &env, id, "child", CODE_ID).unwrap();
+  let data: CreateReplyData = from_binary(&res.data.unwrap()).unwrap();
+  assert_eq!(data.contract_addr, addr("child"));
+
+  assert!(!PENDING_CREATES.has(&deps.storage, id));
+  assert_eq!(ID_2_ADDR.load(&deps.storage, id).unwrap(), addr("child"));
+  assert_eq!(ADDR_2_ID.load(&deps.storage, addr("child")).unwrap(), id);
+  let meta = METADATA.load(&deps.storage, addr("child")).unwrap();
+  assert_eq!(meta.id, id);
+  assert_eq!(meta.code_id, CODE_ID);
+  assert_eq!(meta.admin, Some(env.contract.address.clone()));
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 1);
+
+  let count: CountResponse = query_as(&deps, QueryMsg::Count {});
+  assert_eq!(count.count, 1);
+}
+
+#[test]
+fn repeated_reply_is_ignored() {
+  let mut deps = setup();
+  let env = mock_env();
+  let id = create(&mut deps, &env, "child", vec![]);
+  let res = reply_created(&mut deps, &env, id, "child", CODE_ID).unwrap();
+  assert_eq!(res, Response::default());
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 1);
+}
+
+#[test]
+fn reply_without_contract_address_fails() {
+  let mut deps = setup();
+  let env = mock_env();
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap();
+  let id = pending_id(&res);
+
+  let err = reply(
+    deps.as_mut(),
+    env.clone(),
+    Reply {
+      id: ReplyKind::Create.encode(id),
+      result: SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        data: None,
+      }),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::MissingInstantiateEvent {}));
+
+  let err = reply(
+    deps.as_mut(),
+    env,
+    Reply {
+      id: ReplyKind::Create.encode(id),
+      result: SubMsgResult::Err("out of gas".to_string()),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::CreateFailed {}));
+}
+
+#[test]
+fn seed_updated_at_controls_initial_updated_at_entry() {
+  let env = mock_env();
+  let nanos = env.block.time.nanos();
+
+  let mut deps = setup();
+  let id = create(&mut deps, &env, "child", vec![]);
+  assert!(IX_UPDATED_AT.has(&deps.storage, (nanos, id)));
+
+  let mut deps = setup_with(InstantiateMsg {
+    seed_updated_at: Some(false),
+    ..instantiate_msg()
+  });
+  let id = create(&mut deps, &env, "child", vec![]);
+  assert!(!IX_UPDATED_AT.has(&deps.storage, (nanos, id)));
+
+  // the first update indexes the contract by its update time
+  let later = env_at(env.block.time.seconds() + 10);
+  execute(
+    deps.as_mut(),
+    later.clone(),
+    mock_info("child", &[]),
+    ExecuteMsg::Update {
+      values: Some(vec![IndexSlotValue::Text {
+        slot: 0,
+        value: "x".to_string(),
+      }]),
+      relationships: None,
+      tags: None,
+      code_id: None,
+    },
+  )
+  .unwrap();
+  assert!(IX_UPDATED_AT.has(&deps.storage, (later.block.time.nanos(), id)));
+  assert!(!IX_UPDATED_AT.has(&deps.storage, (nanos, id)));
+}
+
+fn create_with_funds(
+  deps: &mut MockDeps,
+  funds: &[Coin],
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, funds),
+    create_msg(vec![]),
+  )
+}
+
+#[test]
+fn any_denom_is_accepted_when_none_are_required() {
+  let mut deps = setup();
+  create_with_funds(&mut deps, &[coin(1, "ujuno"), coin(2, "uatom")]).unwrap();
+}
+
+#[test]
+fn required_denoms_accept_only_listed_denoms() {
+  let mut deps = setup();
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetRequiredDenoms {
+      denoms: Some(vec!["ujuno".to_string()]),
+    },
+  )
+  .unwrap();
+
+  create_with_funds(&mut deps, &[]).unwrap();
+  create_with_funds(&mut deps, &[coin(1, "ujuno")]).unwrap();
+
+  let err = create_with_funds(&mut deps, &[coin(1, "ujuno"), coin(2, "uatom")]).unwrap_err();
+  assert!(matches!(err, ContractError::DenomNotAccepted { denom } if denom == "uatom"));
+
+  // unsetting the denoms accepts any again
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetRequiredDenoms { denoms: None },
+  )
+  .unwrap();
+  create_with_funds(&mut deps, &[coin(2, "uatom")]).unwrap();
+}
+
+fn create_with_init_msg(
+  deps: &mut MockDeps,
+  init_msg: &[u8],
+) -> Result<Response, ContractError> {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { msg, .. } = &mut msg {
+    *msg = Some(Binary::from(init_msg));
+  }
+  execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg)
+}
+
+#[test]
+fn init_msgs_are_only_checked_for_json_when_enabled() {
+  let mut deps = setup();
+  create_with_init_msg(&mut deps, b"{\"a\":1}").unwrap();
+  create_with_init_msg(&mut deps, b"not json").unwrap();
+
+  let mut msg = instantiate_msg();
+  msg.validate_json = Some(true);
+  let mut deps = setup_with(msg);
+  create_with_init_msg(&mut deps, b"{\"a\":1}").unwrap();
+  let err = create_with_init_msg(&mut deps, b"not json").unwrap_err();
+  assert!(matches!(err, ContractError::InvalidJson { .. }));
+}
+
+fn salted_create_msg(salt: &[u8]) -> ExecuteMsg {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    salt: maybe_salt, ..
+  } = &mut msg
+  {
+    *maybe_salt = Some(Binary::from(salt));
+  }
+  msg
+}
+
+/// MockApi only humanizes canonical addresses of its own length, so predicted
+/// addresses are checked with an Api that maps addresses to and from hex.
+#[cfg(feature = "cosmwasm_1_2")]
+struct HexApi(cosmwasm_std::testing::MockApi);
+
+#[cfg(feature = "cosmwasm_1_2")]
+impl cosmwasm_std::Api for HexApi {
+  fn addr_validate(
+    &self,
+    human: &str,
+  ) -> cosmwasm_std::StdResult<Addr> {
+    Ok(Addr::unchecked(human))
+  }
+
+  fn addr_canonicalize(
+    &self,
+    human: &str,
+  ) -> cosmwasm_std::StdResult<cosmwasm_std::CanonicalAddr> {
+    Ok(human.as_bytes().into())
+  }
+
+  fn addr_humanize(
+    &self,
+    canonical: &cosmwasm_std::CanonicalAddr,
+  ) -> cosmwasm_std::StdResult<Addr> {
+    Ok(Addr::unchecked(
+      cosmwasm_std::HexBinary::from(canonical.as_slice()).to_hex(),
+    ))
+  }
+
+  fn secp256k1_verify(
+    &self,
+    message_hash: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+  ) -> Result<bool, cosmwasm_std::VerificationError> {
+    self.0.secp256k1_verify(message_hash, signature, public_key)
+  }
+
+  fn secp256k1_recover_pubkey(
+    &self,
+    message_hash: &[u8],
+    signature: &[u8],
+    recovery_param: u8,
+  ) -> Result<Vec<u8>, cosmwasm_std::RecoverPubkeyError> {
+    self
+      .0
+      .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+  }
+
+  fn ed25519_verify(
+    &self,
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+  ) -> Result<bool, cosmwasm_std::VerificationError> {
+    self.0.ed25519_verify(message, signature, public_key)
+  }
+
+  fn ed25519_batch_verify(
+    &self,
+    messages: &[&[u8]],
+    signatures: &[&[u8]],
+    public_keys: &[&[u8]],
+  ) -> Result<bool, cosmwasm_std::VerificationError> {
+    self
+      .0
+      .ed25519_batch_verify(messages, signatures, public_keys)
+  }
+
+  fn debug(
+    &self,
+    message: &str,
+  ) {
+    self.0.debug(message)
+  }
+}
+
+#[cfg(feature = "cosmwasm_1_2")]
+#[test]
+fn salted_create_instantiates_at_the_predicted_address() {
+  use crate::msg::PredictAddressResponse;
+  use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage};
+  use cosmwasm_std::{
+    instantiate2_address, to_binary, CodeInfoResponse, ContractResult, HexBinary, OwnedDeps,
+    SystemResult, WasmQuery,
+  };
+
+  let mut deps = OwnedDeps {
+    storage: MockStorage::default(),
+    api: HexApi(MockApi::default()),
+    querier: MockQuerier::default(),
+    custom_query_type: std::marker::PhantomData,
+  };
+  let checksum = HexBinary::from(vec![7u8; 32]);
+  let code_info = CodeInfoResponse::new(CODE_ID, CREATOR.to_string(), checksum.clone());
+  deps.querier.update_wasm(move |query| match query {
+    WasmQuery::CodeInfo { .. } => {
+      SystemResult::Ok(ContractResult::Ok(to_binary(&code_info).unwrap()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+
+  let env = mock_env();
+  instantiate(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    instantiate_msg(),
+  )
+  .unwrap();
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    salted_create_msg(b"salt"),
+  )
+  .unwrap();
+  match &res.messages[0].msg {
+    CosmosMsg::Wasm(WasmMsg::Instantiate2 { code_id, salt, .. }) => {
+      assert_eq!(*code_id, CODE_ID);
+      assert_eq!(salt.as_slice(), b"salt");
+    },
+    other => panic!("not an instantiate2: {:?}", other),
+  }
+
+  let predicted: PredictAddressResponse = from_binary(
+    &query(
+      deps.as_ref(),
+      env.clone(),
+      QueryMsg::PredictAddress {
+        code_id: CODE_ID,
+        salt: Binary::from(b"salt"),
+      },
+    )
+    .unwrap(),
+  )
+  .unwrap();
+  let creator = env.contract.address.as_bytes().into();
+  let expected = instantiate2_address(checksum.as_slice(), &creator, b"salt").unwrap();
+  assert_eq!(
+    predicted.address,
+    HexBinary::from(expected.as_slice()).to_hex()
+  );
+}
+
+#[cfg(not(feature = "cosmwasm_1_2"))]
+#[test]
+fn salts_are_rejected_without_instantiate2() {
+  let mut deps = setup();
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    salted_create_msg(b"salt"),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidSalt { .. }));
+
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    QueryMsg::PredictAddress {
+      code_id: CODE_ID,
+      salt: Binary::from(b"salt"),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidSalt { .. }));
+}
+
+#[test]
+fn failed_index_write_reports_its_cause() {
+  let mut deps = setup();
+  IX_META_U64.remove(&mut deps.storage, 0);
+
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: 1u64.into(),
+    }]),
+  )
+  .unwrap_err();
+  match err {
+    ContractError::IndexWriteFailed {
+      kind: IndexType::Uint64,
+      slot: 0,
+      msg,
+    } => assert_eq!(msg, ContractError::InvalidIndexSlot {}.to_string()),
+    other => panic!("unexpected error: {:?}", other),
+  }
+}
+
+#[test]
+fn failed_create_leaves_count_unchanged() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-0", vec![]);
+  let before = storage_snapshot(&deps);
+
+  // instantiating from a code ID the chain doesn't have fails the submsg
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap();
+  assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+  let err = reply(
+    deps.as_mut(),
+    env.clone(),
+    Reply {
+      id: res.messages[0].id,
+      result: SubMsgResult::Err("no such code ID".to_string()),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::CreateFailed {}));
+
+  // the failed reply aborts the tx, so the chain discards what create wrote
+  let mut storage = MockStorage::new();
+  for (key, value) in before.iter() {
+    storage.set(key, value);
+  }
+  deps.storage = storage;
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 1);
+
+  create(&mut deps, &env, "child-1", vec![]);
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 2);
+}
+
+#[test]
+fn finalize_create_registers_a_create_whose_reply_was_lost() {
+  let mut deps = setup();
+  let env = mock_env();
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap();
+  let id = pending_id(&res);
+
+  let finalize = |contract_addr: &str| AdminMsg::FinalizeCreate {
+    contract_id: id,
+    contract_addr: addr(contract_addr),
+    code_id: CODE_ID,
+  };
+  admin_as(&mut deps, CREATOR, finalize("child")).unwrap();
+  assert_eq!(ID_2_ADDR.load(&deps.storage, id).unwrap(), addr("child"));
+  assert_eq!(ADDR_2_ID.load(&deps.storage, addr("child")).unwrap(), id);
+  assert_eq!(
+    METADATA.load(&deps.storage, addr("child")).unwrap().code_id,
+    CODE_ID
+  );
+  assert!(!PENDING_CREATES.has(&deps.storage, id));
+
+  // once registered, the ID can't be finalized again
+  let err = admin_as(&mut deps, CREATOR, finalize("other")).unwrap_err();
+  assert!(matches!(err, ContractError::NotAnOrphan { .. }));
+}
+
+#[test]
+fn reply_payload_round_trips() {
+  let mut deps = setup();
+  let env = mock_env();
+  let payload = Binary::from(b"caller context");
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { reply_payload, .. } = &mut msg {
+    *reply_payload = Some(payload.clone());
+  }
+  let res = execute(deps.as_mut(), env.clone(), mock_info(CREATOR, &[]), msg).unwrap();
+  let id = pending_id(&res);
+
+  let res = reply_created(&mut deps, &env, id, "child", CODE_ID).unwrap();
+  let data: CreateReplyData = from_binary(&res.data.unwrap()).unwrap();
+  assert_eq!(data.contract_addr, addr("child"));
+  assert_eq!(data.payload, Some(payload));
+}
+
+fn virtual_create_msg(maybe_label: Option<&str>) -> ExecuteMsg {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    counts_toward_total,
+    label,
+    ..
+  } = &mut msg
+  {
+    *counts_toward_total = Some(false);
+    *label = maybe_label.map(|l| l.to_string());
+  }
+  msg
+}
+
+#[test]
+fn virtual_create_leaves_count_unchanged() {
+  let mut deps = setup();
+  let env = mock_env();
+  assert_eq!(create(&mut deps, &env, "child-0", vec![]), 0);
+
+  let err = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    virtual_create_msg(None),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::LabelRequired {}));
+
+  let id = create_as(
+    &mut deps,
+    &env,
+    CREATOR,
+    "virtual-0",
+    virtual_create_msg(Some("virtual-0")),
+  );
+  assert_eq!(id, VIRTUAL_ID_OFFSET);
+  assert_eq!(
+    ADDR_2_ID.load(&deps.storage, addr("virtual-0")).unwrap(),
+    id
+  );
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 1);
+  let count: CountResponse = query_as(&deps, QueryMsg::Count {});
+  assert_eq!(count.count, 1);
+  assert_eq!(count.virtual_count, 1);
+
+  // counted creates carry on numbering where they left off
+  assert_eq!(create(&mut deps, &env, "child-1", vec![]), 1);
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 2);
+}
+
+#[test]
+fn virtual_create_requires_its_own_acl_action() {
+  let mut deps = setup();
+  deps.querier.update_wasm(|query| match query {
+    WasmQuery::Smart { msg, .. } => {
+      let is_allowed = !String::from_utf8_lossy(msg.as_slice()).contains("create_virtual");
+      SystemResult::Ok(ContractResult::Ok(to_binary(&is_allowed).unwrap()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetAcl {
+      acl_contract_addr: addr("acl"),
+    },
+  )
+  .unwrap();
+
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    virtual_create_msg(Some("virtual-0")),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  create(&mut deps, &mock_env(), "child-0", vec![]);
+}
+
+#[test]
+fn next_id_is_the_id_of_the_next_create() {
+  let mut deps = setup();
+  let env = mock_env();
+  let next_id = |deps: &MockDeps| query_as::<NextIdResponse>(deps, QueryMsg::NextId {}).next_id;
+  assert_eq!(next_id(&deps), 0);
+
+  for i in 0..3 {
+    let expected = next_id(&deps);
+    assert_eq!(
+      expected,
+      COUNT.may_load(&deps.storage).unwrap().unwrap_or(0)
+    );
+    assert_eq!(
+      create(&mut deps, &env, &format!("child-{}", i), vec![]),
+      expected
+    );
+  }
+  assert_eq!(next_id(&deps), 3);
+
+  // removed IDs aren't handed out again, so it keeps counting past COUNT
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child-2"),
+    },
+  )
+  .unwrap();
+  assert_eq!(next_id(&deps), 3);
+  assert_eq!(create(&mut deps, &env, "child-3", vec![]), 3);
+}
+
+fn create_relating_self_msg(address: &str) -> ExecuteMsg {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    self_relationships, ..
+  } = &mut msg
+  {
+    *self_relationships = Some(vec![AddressTag {
+      address: addr(address),
+      tag: "owns".to_string(),
+    }]);
+  }
+  msg
+}
+
+#[test]
+fn self_relationships_are_written_once_the_contract_exists() {
+  let mut deps = setup();
+  let env = mock_env();
+  let parent_id = create(&mut deps, &env, "parent", vec![]);
+  let owned_by = |deps: &MockDeps, subject: &str| {
+    read_all_ids(
+      deps,
+      read_keys_msg(
+        Target::Relationship((addr(subject), "owns".to_string())),
+        None,
+        10,
+        None,
+      ),
+    )
+  };
+
+  let res = execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(CREATOR, &[]),
+    create_relating_self_msg("parent"),
+  )
+  .unwrap();
+  assert!(owned_by(&deps, "child").is_empty());
+  reply_created(&mut deps, &env, pending_id(&res), "child", CODE_ID).unwrap();
+  assert_eq!(owned_by(&deps, "child"), vec![parent_id]);
+
+  let err = execute(
+    deps.as_mut(),
+    env,
+    mock_info(CREATOR, &[]),
+    create_relating_self_msg("stranger"),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::ContractNotRegistered {}));
+}
+
+/// Children named "good-*" answer Select, and all others fail it.
+fn mock_children(deps: &mut MockDeps) {
+  deps.querier.update_wasm(|query| match query {
+    WasmQuery::Smart { contract_addr, .. } if contract_addr.starts_with("good-") => {
+      SystemResult::Ok(ContractResult::Ok(b"{}".into()))
+    },
+    WasmQuery::Smart { .. } => SystemResult::Ok(ContractResult::Err("unknown variant".to_string())),
+    other => panic!("unexpected query: {:?}", other),
+  });
+}
+
+fn verifying_create(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  verify: Option<bool>,
+) -> Result<Response, ContractError> {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    verify_interface, ..
+  } = &mut msg
+  {
+    *verify_interface = verify;
+  }
+  let res = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+  reply_created(deps, &mock_env(), pending_id(&res), contract_addr, CODE_ID)
+}
+
+#[test]
+fn verify_interface_flags_children_that_dont_answer_select() {
+  let mut deps = setup();
+  mock_children(&mut deps);
+  let unverified = |deps: &MockDeps, contract_addr: &str| {
+    METADATA
+      .load(&deps.storage, addr(contract_addr))
+      .unwrap()
+      .interface_unverified
+  };
+
+  verifying_create(&mut deps, "good-0", Some(true)).unwrap();
+  verifying_create(&mut deps, "bad-0", Some(true)).unwrap();
+  verifying_create(&mut deps, "bad-1", None).unwrap();
+  assert_eq!(unverified(&deps, "good-0"), Some(false));
+  assert_eq!(unverified(&deps, "bad-0"), Some(true));
+  assert_eq!(unverified(&deps, "bad-1"), None);
+
+  // or rejects them outright, if so configured
+  let mut msg = instantiate_msg();
+  msg.reject_unverified = Some(true);
+  let mut deps = setup_with(msg);
+  mock_children(&mut deps);
+  verifying_create(&mut deps, "good-0", Some(true)).unwrap();
+  let err = verifying_create(&mut deps, "bad-0", Some(true)).unwrap_err();
+  assert!(matches!(err, ContractError::InterfaceUnverified {}));
+  verifying_create(&mut deps, "bad-1", Some(false)).unwrap();
+}
+
+#[test]
+fn repo_missing_optional_items_reads_as_empty() {
+  let mut deps = setup();
+  // as an older storage layout might have left them
+  COUNT.remove(&mut deps.storage);
+  ACL_CONTRACT_ADDR.remove(&mut deps.storage);
+  DEFAULT_LABEL.remove(&mut deps.storage);
+
+  let count: CountResponse = query_as(&deps, QueryMsg::Count {});
+  assert_eq!(count.count, 0);
+  let next_id: NextIdResponse = query_as(&deps, QueryMsg::NextId {});
+  assert_eq!(next_id.next_id, 0);
+
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { label, .. } = &mut msg {
+    *label = Some("custom".to_string());
+  }
+  assert_eq!(create_as(&mut deps, &mock_env(), CREATOR, "child", msg), 0);
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 1);
+
+  // without a default label, each create must name its contract
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![]),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::LabelRequired {}));
+}
@@ -0,0 +1,69 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::Binary;
+
+use super::*;
+use crate::models::MAX_DOC_SIZE;
+use crate::msg::DocResponse;
+use crate::state::DOCS;
+
+fn set_doc_as(
+  deps: &mut MockDeps,
+  sender: &str,
+  doc: &[u8],
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(sender, &[]),
+    ExecuteMsg::SetDoc {
+      contract_addr: addr("child"),
+      doc: Binary::from(doc),
+    },
+  )
+}
+
+fn doc(deps: &MockDeps) -> Option<Binary> {
+  let res: DocResponse = query_as(
+    deps,
+    QueryMsg::Doc {
+      contract_addr: addr("child"),
+    },
+  );
+  res.doc
+}
+
+#[test]
+fn doc_is_set_by_the_contract_or_acl_and_removed_with_it() {
+  let mut deps = setup();
+  let id = create(&mut deps, &mock_env(), "child", vec![]);
+  assert_eq!(doc(&deps), None);
+
+  set_doc_as(&mut deps, "child", br#"{"theme":"dark"}"#).unwrap();
+  assert_eq!(doc(&deps), Some(Binary::from(br#"{"theme":"dark"}"#)));
+  set_doc_as(&mut deps, CREATOR, br#"{"theme":"light"}"#).unwrap();
+  assert_eq!(doc(&deps), Some(Binary::from(br#"{"theme":"light"}"#)));
+
+  let err = set_doc_as(&mut deps, "someone", b"{}").unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+
+  // the size cap counts the doc's bytes
+  let padding = "x".repeat(MAX_DOC_SIZE - 8);
+  let doc_at_max = format!(r#"{{"a":"{}"}}"#, padding);
+  assert_eq!(doc_at_max.len(), MAX_DOC_SIZE);
+  set_doc_as(&mut deps, "child", doc_at_max.as_bytes()).unwrap();
+  let oversized = format!(r#"{{"a":"{}x"}}"#, padding);
+  let err = set_doc_as(&mut deps, "child", oversized.as_bytes()).unwrap_err();
+  assert!(matches!(err, ContractError::DocTooLarge { .. }));
+  assert_eq!(doc(&deps), Some(Binary::from(doc_at_max.as_bytes())));
+
+  assert!(DOCS.has(&deps.storage, id));
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+  assert!(!DOCS.has(&deps.storage, id));
+}
@@ -0,0 +1,180 @@
+//! Each specific error in its scenario, one test per variant.
+
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{Binary, Timestamp, Uint128, Uint64};
+
+use super::*;
+use crate::models::{IndexBounds, IndexType};
+
+fn query_err(
+  deps: &MockDeps,
+  msg: QueryMsg,
+) -> ContractError {
+  query(deps.as_ref(), mock_env(), msg).unwrap_err()
+}
+
+fn histogram_msg(
+  kind: IndexType,
+  bucket_width: u128,
+) -> QueryMsg {
+  QueryMsg::Histogram {
+    kind,
+    slot: 0,
+    bucket_width: Uint128::new(bucket_width),
+    between: None,
+    max_scan: None,
+    cursor: None,
+  }
+}
+
+/// Repo with u64 slots 0, 1 and 2, each set on three contracts.
+fn setup_three_slots() -> MockDeps {
+  let mut msg = instantiate_msg();
+  msg.indices = Some(
+    (0..3)
+      .map(|slot| IndexSlotName::Uint64 { slot, name: None })
+      .collect(),
+  );
+  let mut deps = setup_with(msg);
+  for i in 0..3 {
+    let values = (0..3)
+      .map(|slot| IndexSlotValue::Uint64 {
+        slot,
+        value: Uint64::new(i),
+      })
+      .collect();
+    create(&mut deps, &mock_env(), &format!("child-{}", i), values);
+  }
+  deps
+}
+
+fn swap_msg(
+  b: u8,
+  cursor: Option<ContractID>,
+) -> AdminMsg {
+  AdminMsg::SwapSlots {
+    kind: IndexType::Uint64,
+    a: 0,
+    b,
+    limit: Some(1),
+    cursor,
+  }
+}
+
+#[test]
+fn conflicting_params() {
+  let deps = setup();
+  let mut msg = read_keys_msg(Target::All, None, 10, None);
+  if let QueryMsg::Read { fields, .. } = &mut msg {
+    *fields = Some(vec![]);
+  }
+  assert!(matches!(
+    query_err(&deps, msg),
+    ContractError::ConflictingParams { .. }
+  ));
+}
+
+#[test]
+fn conflicting_bounds() {
+  let deps = setup();
+  let target = Target::Index(IndexBounds::CreatedAt {
+    between: Some((None, Some(Timestamp::from_seconds(1)))),
+    equals: Some(Timestamp::from_seconds(1)),
+  });
+  assert!(matches!(
+    query_err(&deps, read_keys_msg(target, None, 10, None)),
+    ContractError::ConflictingBounds {}
+  ));
+}
+
+#[test]
+fn invalid_set_size() {
+  let deps = setup();
+  let target = Target::Index(IndexBounds::CodeId {
+    between: None,
+    equals: None,
+    equals_any: Some(vec![]),
+  });
+  assert!(matches!(
+    query_err(&deps, read_keys_msg(target, None, 10, None)),
+    ContractError::InvalidSetSize { max: 10 }
+  ));
+}
+
+#[test]
+fn invalid_bucket_width() {
+  let deps = setup();
+  assert!(matches!(
+    query_err(&deps, histogram_msg(IndexType::Uint64, 0)),
+    ContractError::InvalidBucketWidth {}
+  ));
+}
+
+#[test]
+fn numeric_index_required() {
+  let deps = setup();
+  assert!(matches!(
+    query_err(&deps, histogram_msg(IndexType::Text, 10)),
+    ContractError::NumericIndexRequired {}
+  ));
+}
+
+#[test]
+fn invalid_json() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info("child", &[]),
+    ExecuteMsg::SetDoc {
+      contract_addr: addr("child"),
+      doc: Binary::from(b"not json"),
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidJson { .. }));
+}
+
+#[test]
+fn not_an_orphan() {
+  let mut deps = setup();
+  let id = create(&mut deps, &mock_env(), "child", vec![]);
+  let err = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::PurgeOrphan { contract_id: id },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotAnOrphan { contract_id } if contract_id == id));
+}
+
+#[test]
+fn same_slot() {
+  let mut deps = setup_three_slots();
+  let err = admin_as(&mut deps, CREATOR, swap_msg(0, None)).unwrap_err();
+  assert!(matches!(err, ContractError::SameSlot { slot: 0 }));
+}
+
+#[test]
+fn slot_swap_in_progress() {
+  let mut deps = setup_three_slots();
+  admin_as(&mut deps, CREATOR, swap_msg(1, None)).unwrap();
+  let err = admin_as(&mut deps, CREATOR, swap_msg(2, None)).unwrap_err();
+  assert!(matches!(err, ContractError::SlotSwapInProgress {}));
+}
+
+#[test]
+fn no_slot_swap_in_progress() {
+  let mut deps = setup_three_slots();
+  let err = admin_as(&mut deps, CREATOR, swap_msg(1, Some(0))).unwrap_err();
+  assert!(matches!(err, ContractError::NoSlotSwapInProgress {}));
+}
+
+#[test]
+fn cursor_mismatch() {
+  let mut deps = setup_three_slots();
+  admin_as(&mut deps, CREATOR, swap_msg(1, None)).unwrap();
+  let err = admin_as(&mut deps, CREATOR, swap_msg(1, Some(2))).unwrap_err();
+  assert!(matches!(err, ContractError::CursorMismatch {}));
+}
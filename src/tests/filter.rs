@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{to_binary, Binary, ContractResult, SystemError, SystemResult, WasmQuery};
+
+use super::*;
+use crate::msg::{ChildFilter, ImplementorQueryMsg};
+use crate::query::{MAX_FILTER_SCAN, MAX_LIMIT};
+
+/// Answer Select queries with a state that depends on the child's address:
+/// "active-*" children are active, "idle-*" ones aren't, "broken-*" ones fail
+/// to answer, and "blank-*" ones have no status at all.
+fn mock_children(deps: &mut MockDeps) {
+  deps.querier.update_wasm(|query| match query {
+    WasmQuery::Smart { contract_addr, .. } => {
+      let state = if contract_addr.starts_with("active-") {
+        r#"{"status":{"active":true}}"#
+      } else if contract_addr.starts_with("idle-") {
+        r#"{"status":{"active":false}}"#
+      } else if contract_addr.starts_with("blank-") {
+        r#"{}"#
+      } else {
+        return SystemResult::Err(SystemError::NoSuchContract {
+          addr: contract_addr.clone(),
+        });
+      };
+      SystemResult::Ok(ContractResult::Ok(state.as_bytes().into()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+}
+
+fn filtered_read(
+  limit: u32,
+  cursor: Option<(String, ContractID)>,
+) -> QueryMsg {
+  let mut msg = read_keys_msg(Target::All, None, limit, cursor);
+  if let QueryMsg::Read {
+    raw_keys, filter, ..
+  } = &mut msg
+  {
+    *raw_keys = None;
+    *filter = Some(ChildFilter {
+      path: "status.active".to_string(),
+      equals: "true".to_string(),
+    });
+  }
+  msg
+}
+
+fn page_addrs(page: &ReadResponse) -> Vec<String> {
+  page.page.iter().map(|e| e.address.to_string()).collect()
+}
+
+#[test]
+fn filter_keeps_only_matching_children() {
+  let mut deps = setup();
+  mock_children(&mut deps);
+  for contract_addr in ["active-0", "idle-0", "broken-0", "blank-0", "active-1"].iter() {
+    create(&mut deps, &mock_env(), contract_addr, vec![]);
+  }
+
+  let page: ReadResponse = query_as(&deps, filtered_read(10, None));
+  assert_eq!(page_addrs(&page), vec!["active-0", "active-1"]);
+  assert!(!page.has_more);
+  assert_eq!(page.cursor, None);
+}
+
+#[test]
+fn filter_resumes_after_the_last_match_at_the_limit() {
+  let mut deps = setup();
+  mock_children(&mut deps);
+  for contract_addr in ["active-0", "idle-0", "active-1", "active-2"].iter() {
+    create(&mut deps, &mock_env(), contract_addr, vec![]);
+  }
+
+  let page: ReadResponse = query_as(&deps, filtered_read(2, None));
+  assert_eq!(page_addrs(&page), vec!["active-0", "active-1"]);
+  assert!(page.has_more);
+
+  let page: ReadResponse = query_as(&deps, filtered_read(2, page.cursor));
+  assert_eq!(page_addrs(&page), vec!["active-2"]);
+  assert!(!page.has_more);
+}
+
+#[test]
+fn filter_stops_at_the_scan_cap_and_resumes_after_it() {
+  let mut deps = setup();
+  mock_children(&mut deps);
+  for i in 0..MAX_FILTER_SCAN {
+    create(&mut deps, &mock_env(), &format!("idle-{}", i), vec![]);
+  }
+  let last = create(&mut deps, &mock_env(), "active-0", vec![]);
+
+  // the whole first page is scanned without a match
+  let page: ReadResponse = query_as(&deps, filtered_read(10, None));
+  assert!(page.page.is_empty());
+  assert!(page.has_more);
+  let cursor = page.cursor.clone().unwrap();
+  assert_eq!(cursor.1, last - 1);
+
+  let page: ReadResponse = query_as(&deps, filtered_read(10, Some(cursor)));
+  assert_eq!(page_addrs(&page), vec!["active-0"]);
+  assert!(!page.has_more);
+}
+
+#[test]
+fn full_page_sends_every_child_the_same_select_msg() {
+  let mut deps = setup();
+  for i in 0..MAX_LIMIT {
+    create(&mut deps, &mock_env(), &format!("active-{}", i), vec![]);
+  }
+  let sent: Rc<RefCell<Vec<Binary>>> = Rc::new(RefCell::new(vec![]));
+  let log = sent.clone();
+  deps.querier.update_wasm(move |query| match query {
+    WasmQuery::Smart { msg, .. } => {
+      log.borrow_mut().push(msg.clone());
+      SystemResult::Ok(ContractResult::Ok(b"{}".into()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+
+  let mut msg = read_keys_msg(Target::All, None, MAX_LIMIT, None);
+  if let QueryMsg::Read {
+    raw_keys, fields, ..
+  } = &mut msg
+  {
+    *raw_keys = None;
+    *fields = Some(vec!["status".to_string()]);
+  }
+  let page: ReadResponse = query_as(&deps, msg);
+  assert_eq!(page.page.len(), MAX_LIMIT as usize);
+  assert!(page.page.iter().all(|e| e.state.is_some()));
+
+  let expected = to_binary(&ImplementorQueryMsg::Select {
+    wallet: None,
+    fields: Some(vec!["status".to_string()]),
+  })
+  .unwrap();
+  let sent = sent.borrow();
+  assert_eq!(sent.len(), MAX_LIMIT as usize);
+  assert!(sent.iter().all(|msg| *msg == expected));
+}
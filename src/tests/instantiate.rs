@@ -0,0 +1,108 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::Uint64;
+
+use super::*;
+use crate::models::Slot;
+use crate::msg::{SchemaResponse, SlotSchema};
+use crate::state::{IX_META_STRING, IX_META_U64};
+
+/// Declare slot 0 as both a u64 and a text index.
+fn shared_slot_msg(exclusive_slots: Option<bool>) -> InstantiateMsg {
+  let mut msg = instantiate_msg();
+  msg.indices = Some(vec![
+    IndexSlotName::Uint64 {
+      slot: 0,
+      name: Some("score".to_string()),
+    },
+    IndexSlotName::Text {
+      slot: 0,
+      name: Some("nickname".to_string()),
+    },
+  ]);
+  msg.exclusive_slots = exclusive_slots;
+  msg
+}
+
+#[test]
+fn slots_are_shared_across_types_by_default() {
+  let deps = setup_with(shared_slot_msg(None));
+  assert!(IX_META_U64.has(&deps.storage, 0));
+  assert!(IX_META_STRING.has(&deps.storage, 0));
+}
+
+#[test]
+fn exclusive_slots_reject_a_slot_shared_across_types() {
+  let mut deps = mock_dependencies();
+  let err = instantiate(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    shared_slot_msg(Some(true)),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::ConflictingParams { .. }));
+}
+
+#[test]
+fn exclusive_slots_accept_distinct_slots() {
+  let mut msg = shared_slot_msg(Some(true));
+  msg.indices = Some(vec![
+    IndexSlotName::Uint64 {
+      slot: 0,
+      name: None,
+    },
+    IndexSlotName::Text {
+      slot: 1,
+      name: None,
+    },
+  ]);
+  let deps = setup_with(msg);
+  assert!(IX_META_U64.has(&deps.storage, 0));
+  assert!(IX_META_STRING.has(&deps.storage, 1));
+}
+
+#[test]
+fn schema_lists_only_declared_slots() {
+  let mut msg = instantiate_msg();
+  msg.indices = Some(vec![
+    IndexSlotName::Uint64 {
+      slot: 0,
+      name: Some("score".to_string()),
+    },
+    IndexSlotName::Uint64 {
+      slot: 2,
+      name: None,
+    },
+    IndexSlotName::Text {
+      slot: 1,
+      name: Some("status".to_string()),
+    },
+  ]);
+  let mut deps = setup_with(msg);
+  create(
+    &mut deps,
+    &mock_env(),
+    "child",
+    vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(1),
+    }],
+  );
+
+  let slot = |slot: Slot, name: Option<&str>, size: u64| SlotSchema {
+    slot,
+    name: name.map(|n| n.to_string()),
+    size,
+  };
+  let res: SchemaResponse = query_as(&deps, QueryMsg::Schema {});
+  assert_eq!(
+    res,
+    SchemaResponse {
+      uint64: vec![slot(0, Some("score"), 1), slot(2, None, 0)],
+      uint128: vec![],
+      text: vec![slot(1, Some("status"), 0)],
+      timestamp: vec![],
+      boolean: vec![],
+    }
+  );
+}
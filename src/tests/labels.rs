@@ -0,0 +1,100 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+
+use super::*;
+use crate::models::MAX_LABEL_LEN;
+use crate::msg::IsLabelTakenResponse;
+use crate::state::NEXT_ID;
+
+fn is_label_taken(
+  deps: &MockDeps,
+  label: &str,
+) -> bool {
+  let res: IsLabelTakenResponse = query_as(
+    deps,
+    QueryMsg::IsLabelTaken {
+      label: label.to_string(),
+    },
+  );
+  res.is_taken
+}
+
+fn create_labeled(
+  deps: &mut MockDeps,
+  label: Option<&str>,
+) -> Result<Response, ContractError> {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { label: l, .. } = &mut msg {
+    *l = label.map(|x| x.to_string());
+  }
+  execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg)
+}
+
+#[test]
+fn is_label_taken_reports_taken_and_free_labels() {
+  let mut deps = setup();
+  assert!(!is_label_taken(&deps, "custom"));
+
+  create_labeled(&mut deps, Some("custom")).unwrap();
+  create_labeled(&mut deps, None).unwrap();
+
+  assert!(is_label_taken(&deps, "custom"));
+  assert!(is_label_taken(&deps, "child-1"));
+  assert!(!is_label_taken(&deps, "child-2"));
+  assert!(!is_label_taken(&deps, "other"));
+
+  let err = create_labeled(&mut deps, Some("custom")).unwrap_err();
+  assert!(matches!(err, ContractError::LabelTaken {}));
+}
+
+#[test]
+fn preset_with_default_label_creates_more_than_once() {
+  let mut deps = setup();
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { save_as, .. } = &mut msg {
+    *save_as = Some("preset".to_string());
+  }
+  create_as(&mut deps, &mock_env(), CREATOR, "child-a", msg);
+
+  for contract_addr in ["child-b", "child-c"].iter() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create {
+      indices, preset, ..
+    } = &mut msg
+    {
+      *indices = None;
+      *preset = Some("preset".to_string());
+    }
+    create_as(&mut deps, &mock_env(), CREATOR, contract_addr, msg);
+  }
+  for label in ["child-0", "child-1", "child-2"].iter() {
+    assert!(is_label_taken(&deps, label));
+  }
+}
+
+fn submsg_label(res: &Response) -> String {
+  match &res.messages[0].msg {
+    CosmosMsg::Wasm(WasmMsg::Instantiate { label, .. }) => label.clone(),
+    other => panic!("not an instantiate: {:?}", other),
+  }
+}
+
+#[test]
+fn numbered_default_label_is_capped_at_the_max_length() {
+  let mut msg = instantiate_msg();
+  let default_label = "x".repeat(MAX_LABEL_LEN - 4);
+  msg.default_label = Some(default_label.clone());
+  let mut deps = setup_with(msg);
+
+  let res = create_labeled(&mut deps, None).unwrap();
+  assert_eq!(submsg_label(&res), format!("{}-0", default_label));
+
+  // a count with more digits overflows the max once numbered
+  NEXT_ID.save(&mut deps.storage, &100_000).unwrap();
+  let res = create_labeled(&mut deps, None);
+  assert!(matches!(
+    res.unwrap_err(),
+    ContractError::InvalidLabel { len } if len == MAX_LABEL_LEN + 3
+  ));
+  let res = create_labeled(&mut deps, Some("custom")).unwrap();
+  assert_eq!(submsg_label(&res), "custom");
+}
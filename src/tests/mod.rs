@@ -0,0 +1,255 @@
+//! Helpers for driving the contract through its entry points in unit tests.
+//! Child contracts don't exist here, so a create's reply is simulated with the
+//! instantiate event the chain would emit.
+
+mod admin;
+mod backfill;
+mod client;
+mod create;
+mod docs;
+mod errors;
+mod filter;
+mod instantiate;
+mod labels;
+mod presets;
+mod read;
+mod read_union;
+mod recount;
+mod remove;
+mod scan;
+mod slot_writer;
+mod stats;
+mod swap_slots;
+mod update;
+mod values;
+mod wide_values;
+
+use cosmwasm_std::testing::{
+  mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+  from_binary, Addr, CosmosMsg, Env, Event, Order, OwnedDeps, Reply, Response, Storage,
+  SubMsgResponse, SubMsgResult, Timestamp, WasmMsg,
+};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::error::ContractError;
+use crate::models::{ContractID, IndexSlotName, IndexSlotValue, ReplyKind};
+use crate::msg::{AdminMsg, ExecuteMsg, InstantiateMsg, QueryMsg, ReadResponse, Target};
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+pub const CREATOR: &str = "creator";
+pub const CODE_ID: u64 = 1;
+
+/// Instantiate msg with two allowed code IDs and slot 0 of every index type.
+pub fn instantiate_msg() -> InstantiateMsg {
+  InstantiateMsg {
+    acl_address: None,
+    default_label: Some("child".to_string()),
+    default_code_id: Some(CODE_ID),
+    code_ids: vec![CODE_ID, CODE_ID + 1],
+    indices: Some(vec![
+      IndexSlotName::Uint64 {
+        slot: 0,
+        name: None,
+      },
+      IndexSlotName::Uint128 {
+        slot: 0,
+        name: None,
+      },
+      IndexSlotName::Timestamp {
+        slot: 0,
+        name: None,
+      },
+      IndexSlotName::Text {
+        slot: 0,
+        name: None,
+      },
+      IndexSlotName::Boolean {
+        slot: 0,
+        name: None,
+      },
+    ]),
+    seed_updated_at: None,
+    validate_json: None,
+    reject_unverified: None,
+    require_base64_tags: None,
+    self_heal_indices: None,
+    exclusive_slots: None,
+  }
+}
+
+pub fn setup_with(msg: InstantiateMsg) -> MockDeps {
+  let mut deps = mock_dependencies();
+  instantiate(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+  deps
+}
+
+pub fn setup() -> MockDeps {
+  setup_with(instantiate_msg())
+}
+
+/// Env whose block time is the given number of seconds after genesis.
+pub fn env_at(seconds: u64) -> Env {
+  let mut env = mock_env();
+  env.block.time = Timestamp::from_seconds(seconds);
+  env.block.height = seconds;
+  env
+}
+
+/// Create msg with the given indices and nothing else set.
+pub fn create_msg(indices: Vec<IndexSlotValue>) -> ExecuteMsg {
+  ExecuteMsg::Create {
+    code_id: None,
+    msg: None,
+    admin: None,
+    label: None,
+    indices: Some(indices),
+    preset: None,
+    save_as: None,
+    tags: None,
+    relationships: None,
+    self_relationships: None,
+    counts_toward_total: None,
+    verify_interface: None,
+    reply_payload: None,
+    salt: None,
+  }
+}
+
+/// The contract ID a create response's instantiate submsg was issued for.
+pub fn pending_id(res: &Response) -> ContractID {
+  match ReplyKind::decode(res.messages[0].id) {
+    Some((ReplyKind::Create, id)) => id,
+    other => panic!("not a create submsg: {:?}", other),
+  }
+}
+
+/// Reply to a create's submsg as the chain would once the child exists.
+pub fn reply_created(
+  deps: &mut MockDeps,
+  env: &Env,
+  contract_id: ContractID,
+  contract_addr: &str,
+  code_id: u64,
+) -> Result<Response, ContractError> {
+  let event = Event::new("instantiate")
+    .add_attribute("_contract_address", contract_addr)
+    .add_attribute("code_id", code_id.to_string());
+  reply(
+    deps.as_mut(),
+    env.clone(),
+    Reply {
+      id: ReplyKind::Create.encode(contract_id),
+      result: SubMsgResult::Ok(SubMsgResponse {
+        events: vec![event],
+        data: None,
+      }),
+    },
+  )
+}
+
+/// Run a create msg as the repo creator and register the new contract under
+/// the given address, returning its ID.
+pub fn create_as(
+  deps: &mut MockDeps,
+  env: &Env,
+  sender: &str,
+  contract_addr: &str,
+  msg: ExecuteMsg,
+) -> ContractID {
+  let res = execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg).unwrap();
+  let code_id = match &res.messages[0].msg {
+    CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, .. }) => *code_id,
+    #[cfg(feature = "cosmwasm_1_2")]
+    CosmosMsg::Wasm(WasmMsg::Instantiate2 { code_id, .. }) => *code_id,
+    other => panic!("not an instantiate: {:?}", other),
+  };
+  let contract_id = pending_id(&res);
+  reply_created(deps, env, contract_id, contract_addr, code_id).unwrap();
+  contract_id
+}
+
+pub fn create(
+  deps: &mut MockDeps,
+  env: &Env,
+  contract_addr: &str,
+  indices: Vec<IndexSlotValue>,
+) -> ContractID {
+  create_as(deps, env, CREATOR, contract_addr, create_msg(indices))
+}
+
+/// Run an admin msg as the given sender.
+pub fn admin_as(
+  deps: &mut MockDeps,
+  sender: &str,
+  msg: AdminMsg,
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(sender, &[]),
+    ExecuteMsg::Admin(msg),
+  )
+}
+
+pub fn query_as<T: serde::de::DeserializeOwned>(
+  deps: &MockDeps,
+  msg: QueryMsg,
+) -> T {
+  from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+/// Read every page of raw keys for a read msg, returning the contract IDs in
+/// the order they were read.
+pub fn read_all_ids(
+  deps: &MockDeps,
+  msg: QueryMsg,
+) -> Vec<ContractID> {
+  let mut msg = msg;
+  let mut ids: Vec<ContractID> = vec![];
+  loop {
+    let page: ReadResponse = query_as(deps, msg.clone());
+    ids.extend(page.raw_keys.unwrap().into_iter().map(|(_, id)| id));
+    if !page.has_more {
+      return ids;
+    }
+    if let QueryMsg::Read { cursor, .. } = &mut msg {
+      *cursor = page.cursor;
+    }
+  }
+}
+
+/// Read msg for one page of raw keys, which never queries the contracts.
+pub fn read_keys_msg(
+  target: Target,
+  desc: Option<bool>,
+  limit: u32,
+  cursor: Option<(String, ContractID)>,
+) -> QueryMsg {
+  QueryMsg::Read {
+    target,
+    fields: None,
+    since: None,
+    limit: Some(limit),
+    desc,
+    cursor,
+    meta: None,
+    wallet: None,
+    raw_keys: Some(true),
+    count_only: None,
+    tiebreak_desc: None,
+    with_tags: None,
+    filter: None,
+  }
+}
+
+/// Every key and value in storage, for asserting that nothing was left behind.
+pub fn storage_snapshot(deps: &MockDeps) -> Vec<(Vec<u8>, Vec<u8>)> {
+  deps.storage.range(None, None, Order::Ascending).collect()
+}
+
+pub fn addr(s: &str) -> Addr {
+  Addr::unchecked(s)
+}
@@ -0,0 +1,239 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_vec, Order};
+use serde_json::to_value;
+
+use super::*;
+use crate::models::AddressTag;
+use crate::msg::AllPresetsResponse;
+use crate::state::{CONTRACT_RELATIONSHIPS, PRESETS, REL_BY_NAME};
+
+fn create_saving_preset(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  preset_name: &str,
+) {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { save_as, .. } = &mut msg {
+    *save_as = Some(preset_name.to_string());
+  }
+  create_as(deps, &mock_env(), CREATOR, contract_addr, msg);
+}
+
+fn preset_names(deps: &MockDeps) -> Vec<String> {
+  preset_names_of(deps, CREATOR)
+}
+
+fn preset_names_of(
+  deps: &MockDeps,
+  owner: &str,
+) -> Vec<String> {
+  PRESETS
+    .prefix(addr(owner))
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .map(|name| name.unwrap())
+    .collect()
+}
+
+#[test]
+fn remove_presets_removes_only_existing_names() {
+  let mut deps = setup();
+  for (i, name) in ["a", "b", "c"].iter().enumerate() {
+    create_saving_preset(&mut deps, &format!("child-{}", i), name);
+  }
+
+  let res = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    ExecuteMsg::RemovePresets {
+      names: vec!["a".to_string(), "missing".to_string(), "c".to_string()],
+    },
+  )
+  .unwrap();
+  let removed = res.attributes.iter().find(|a| a.key == "removed").unwrap();
+  assert_eq!(removed.value, "a,c");
+  assert_eq!(preset_names(&deps), vec!["b".to_string()]);
+}
+
+#[test]
+fn clear_presets_removes_only_the_senders_presets() {
+  let mut deps = setup();
+  for (i, name) in ["a", "b", "c"].iter().enumerate() {
+    create_saving_preset(&mut deps, &format!("child-{}", i), name);
+  }
+  // another wallet's preset of the same name
+  let preset = PRESETS
+    .load(&deps.storage, (addr(CREATOR), "a".to_string()))
+    .unwrap();
+  PRESETS
+    .save(&mut deps.storage, (addr("other"), "a".to_string()), &preset)
+    .unwrap();
+
+  let res = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    ExecuteMsg::ClearPresets {},
+  )
+  .unwrap();
+  let removed = res
+    .attributes
+    .iter()
+    .find(|a| a.key == "removed_count")
+    .unwrap();
+  assert_eq!(removed.value, "3");
+  assert!(preset_names(&deps).is_empty());
+  assert_eq!(preset_names_of(&deps, "other"), vec!["a".to_string()]);
+}
+
+#[test]
+fn create_from_a_missing_preset_names_it() {
+  let mut deps = setup();
+  create_saving_preset(&mut deps, "child-0", "a");
+  let from_preset = |name: &str| {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { preset, .. } = &mut msg {
+      *preset = Some(name.to_string());
+    }
+    msg
+  };
+
+  create_as(&mut deps, &mock_env(), CREATOR, "child-1", from_preset("a"));
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    from_preset("missing"),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::PresetNotFound { name } if name == "missing"));
+}
+
+#[test]
+fn all_presets_pages_across_owners() {
+  let mut deps = setup();
+  for (i, name) in ["a", "b"].iter().enumerate() {
+    create_saving_preset(&mut deps, &format!("child-{}", i), name);
+  }
+  let preset = PRESETS
+    .load(&deps.storage, (addr(CREATOR), "a".to_string()))
+    .unwrap();
+  for (owner, name) in [("owner-a", "x"), ("owner-b", "y"), ("owner-b", "z")].iter() {
+    PRESETS
+      .save(&mut deps.storage, (addr(owner), name.to_string()), &preset)
+      .unwrap();
+  }
+  let all_presets = |cursor: Option<(Addr, String)>, names_only: Option<bool>| {
+    query_as::<AllPresetsResponse>(
+      &deps,
+      QueryMsg::AllPresets {
+        limit: Some(2),
+        cursor,
+        names_only,
+      },
+    )
+  };
+
+  let mut entries: Vec<(String, String)> = vec![];
+  let mut cursor = None;
+  loop {
+    let page = all_presets(cursor, None);
+    assert!(page.presets.iter().all(|e| e.preset.is_some()));
+    entries.extend(
+      page
+        .presets
+        .into_iter()
+        .map(|e| (e.owner.to_string(), e.name)),
+    );
+    if page.cursor.is_none() {
+      break;
+    }
+    cursor = page.cursor;
+  }
+  let expected: Vec<(String, String)> = [
+    (CREATOR, "a"),
+    (CREATOR, "b"),
+    ("owner-a", "x"),
+    ("owner-b", "y"),
+    ("owner-b", "z"),
+  ]
+  .iter()
+  .map(|(owner, name)| (owner.to_string(), name.to_string()))
+  .collect();
+  assert_eq!(entries, expected);
+
+  let page = all_presets(None, Some(true));
+  assert_eq!(page.presets.len(), 2);
+  assert!(page.presets.iter().all(|e| e.preset.is_none()));
+  assert_eq!(page.cursor, Some((addr(CREATOR), "b".to_string())));
+}
+
+#[test]
+fn presets_restore_relationships_merged_with_explicit_ones() {
+  let mut deps = setup();
+  let relationship = |address: &str, name: &str| AddressTag {
+    address: addr(address),
+    tag: name.to_string(),
+  };
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    save_as,
+    relationships,
+    ..
+  } = &mut msg
+  {
+    *save_as = Some("related".to_string());
+    *relationships = Some(vec![relationship("owner", "owns")]);
+  }
+  create_as(&mut deps, &mock_env(), CREATOR, "child-0", msg);
+  let preset = PRESETS
+    .load(&deps.storage, (addr(CREATOR), "related".to_string()))
+    .unwrap();
+  assert_eq!(
+    preset.relationships,
+    Some(vec![relationship("owner", "owns")])
+  );
+
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create {
+    preset,
+    relationships,
+    ..
+  } = &mut msg
+  {
+    *preset = Some("related".to_string());
+    *relationships = Some(vec![
+      relationship("owner", "owns"),
+      relationship("fan", "likes"),
+    ]);
+  }
+  let id = create_as(&mut deps, &mock_env(), CREATOR, "child-1", msg);
+  let related: Vec<(Addr, String)> = CONTRACT_RELATIONSHIPS
+    .sub_prefix(id)
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .map(|k| k.unwrap())
+    .collect();
+  assert_eq!(
+    related,
+    vec![
+      (addr("fan"), "likes".to_string()),
+      (addr("owner"), "owns".to_string()),
+    ]
+  );
+  assert_eq!(
+    REL_BY_NAME
+      .load(&deps.storage, ("owns".to_string(), id))
+      .unwrap(),
+    1
+  );
+
+  // presets saved before relationships were stored load without them
+  let key = PRESETS.key((addr(CREATOR), "old".to_string()));
+  let mut old = to_value(&preset).unwrap();
+  old.as_object_mut().unwrap().remove("relationships");
+  deps.storage.set(&key, &to_vec(&old).unwrap());
+  let loaded = PRESETS
+    .load(&deps.storage, (addr(CREATOR), "old".to_string()))
+    .unwrap();
+  assert_eq!(loaded.relationships, None);
+}
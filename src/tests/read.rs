@@ -0,0 +1,1125 @@
+use std::cell::RefCell;
+
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Deps, QuerierWrapper, Record, Timestamp, Uint64};
+
+use super::*;
+use crate::models::{AddressTag, IndexBounds, RelativeBound};
+use crate::msg::{AnyMatchResponse, FeedItem, FeedResponse, Since};
+use crate::query::{DEFAULT_LIMIT, MAX_LIMIT, MIN_LIMIT};
+use crate::state::{CREATED_BY, METADATA};
+
+use super::update;
+
+fn create_n(
+  deps: &mut MockDeps,
+  n: usize,
+) -> Vec<ContractID> {
+  (0..n)
+    .map(|i| create(deps, &mock_env(), &format!("child-{}", i), vec![]))
+    .collect()
+}
+
+#[test]
+fn count_only_advances_cursor_like_a_full_read() {
+  let mut deps = setup();
+  create_n(&mut deps, 5);
+
+  let mut keys_cursor = None;
+  let mut count_cursor = None;
+  let mut counts: Vec<u64> = vec![];
+  loop {
+    let keys: ReadResponse = query_as(&deps, read_keys_msg(Target::All, None, 2, keys_cursor));
+    let mut msg = read_keys_msg(Target::All, None, 2, count_cursor);
+    if let QueryMsg::Read {
+      raw_keys,
+      count_only,
+      ..
+    } = &mut msg
+    {
+      *raw_keys = None;
+      *count_only = Some(true);
+    }
+    let page: ReadResponse = query_as(&deps, msg);
+
+    assert!(page.page.is_empty());
+    assert!(page.raw_keys.is_none());
+    assert_eq!(page.cursor, keys.cursor);
+    assert_eq!(page.has_more, keys.has_more);
+    counts.push(page.count);
+
+    if !page.has_more {
+      break;
+    }
+    keys_cursor = keys.cursor;
+    count_cursor = page.cursor;
+  }
+  assert_eq!(counts, vec![2, 2, 1]);
+}
+
+#[test]
+fn tiebreak_orders_ids_within_each_value() {
+  let mut deps = setup();
+  for (i, value) in [1u64, 2, 1, 2, 1].iter().enumerate() {
+    create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(*value),
+      }],
+    );
+  }
+
+  // (desc, tiebreak_desc) -> expected ID's
+  let cases: Vec<((bool, bool), Vec<ContractID>)> = vec![
+    ((false, false), vec![0, 2, 4, 1, 3]),
+    ((false, true), vec![4, 2, 0, 3, 1]),
+    ((true, false), vec![1, 3, 0, 2, 4]),
+    ((true, true), vec![3, 1, 4, 2, 0]),
+  ];
+  for ((desc, tiebreak), expected) in cases {
+    // page sizes that end pages inside and at the edges of a tie
+    for limit in [1, 2, 3, 5].iter().copied() {
+      let target = Target::Index(IndexBounds::Uint64 {
+        slot: 0,
+        between: None,
+        equals: None,
+      });
+      let mut msg = read_keys_msg(target, Some(desc), limit, None);
+      if let QueryMsg::Read { tiebreak_desc, .. } = &mut msg {
+        *tiebreak_desc = Some(tiebreak);
+      }
+      assert_eq!(
+        read_all_ids(&deps, msg),
+        expected,
+        "desc: {}, tiebreak_desc: {}, limit: {}",
+        desc,
+        tiebreak,
+        limit
+      );
+    }
+  }
+}
+
+#[test]
+fn created_by_equals_any_merges_creators_by_id() {
+  let mut deps = setup();
+  for (i, creator) in [
+    "alice", "bob", "dave", "carol", "alice", "bob", "dave", "carol",
+  ]
+  .iter()
+  .enumerate()
+  {
+    // without an ACL, only the repo's creator may create
+    CREATED_BY.save(&mut deps.storage, &addr(creator)).unwrap();
+    let contract_addr = format!("child-{}", i);
+    create_as(
+      &mut deps,
+      &mock_env(),
+      creator,
+      &contract_addr,
+      create_msg(vec![]),
+    );
+  }
+
+  let bounds = IndexBounds::CreatedBy {
+    between: None,
+    equals: None,
+    // duplicates are ignored
+    equals_any: Some(vec![
+      addr("carol"),
+      addr("alice"),
+      addr("bob"),
+      addr("alice"),
+    ]),
+  };
+  for limit in [1, 2, 4].iter().copied() {
+    let target = Target::Index(bounds.clone());
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target, None, limit, None)),
+      vec![0, 1, 3, 4, 5, 7],
+      "limit: {}",
+      limit
+    );
+    let target = Target::Index(bounds.clone());
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target, Some(true), limit, None)),
+      vec![7, 5, 4, 3, 1, 0],
+      "limit: {}",
+      limit
+    );
+  }
+}
+
+#[test]
+fn relationship_by_name_pages_within_the_name() {
+  let mut deps = setup();
+  let rels: Vec<Vec<(&str, &str)>> = vec![
+    vec![("alice", "owned_by")],
+    vec![("alice", "owned_by"), ("bob", "owned_by")],
+    vec![("bob", "managed_by")],
+    vec![("carol", "owned_by"), ("carol", "managed_by")],
+    vec![("alice", "managed_by")],
+    vec![("bob", "owned_by")],
+  ];
+  for (i, rels) in rels.iter().enumerate() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { relationships, .. } = &mut msg {
+      *relationships = Some(
+        rels
+          .iter()
+          .map(|(subject, name)| AddressTag {
+            address: addr(subject),
+            tag: name.to_string(),
+          })
+          .collect(),
+      );
+    }
+    create_as(
+      &mut deps,
+      &mock_env(),
+      CREATOR,
+      &format!("child-{}", i),
+      msg,
+    );
+  }
+
+  let owned_by = || Target::RelationshipByName("owned_by".to_string());
+  for limit in [1, 2, 3].iter().copied() {
+    // a contract related under the name more than once is listed once
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(owned_by(), None, limit, None)),
+      vec![0, 1, 3, 5],
+      "limit: {}",
+      limit
+    );
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(owned_by(), Some(true), limit, None)),
+      vec![5, 3, 1, 0],
+      "limit: {}",
+      limit
+    );
+  }
+
+  // a cursor from another name doesn't page through this one
+  let managed_by = Target::RelationshipByName("managed_by".to_string());
+  let page: ReadResponse = query_as(&deps, read_keys_msg(managed_by, None, 1, None));
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    read_keys_msg(owned_by(), None, 10, page.cursor),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidCursor {}));
+}
+
+#[test]
+fn code_id_recent_orders_by_creation_time_within_code_id() {
+  let mut deps = setup();
+  // (code ID, created at) for contracts 0..5
+  let contracts = [
+    (CODE_ID, 300),
+    (CODE_ID + 1, 400),
+    (CODE_ID, 100),
+    (CODE_ID, 200),
+    (CODE_ID, 200),
+  ];
+  for (i, (code_id, seconds)) in contracts.iter().enumerate() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { code_id: c, .. } = &mut msg {
+      *c = Some(*code_id);
+    }
+    create_as(
+      &mut deps,
+      &env_at(*seconds),
+      CREATOR,
+      &format!("child-{}", i),
+      msg,
+    );
+  }
+
+  let target = |desc| Target::CodeIdRecent {
+    code_id: CODE_ID,
+    desc,
+  };
+  for limit in [1, 2, 4].iter().copied() {
+    // newest first by default. Contracts created together follow the same order
+    // by ID.
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target(None), None, limit, None)),
+      vec![0, 4, 3, 2],
+      "limit: {}",
+      limit
+    );
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target(Some(false)), None, limit, None)),
+      vec![2, 3, 4, 0],
+      "limit: {}",
+      limit
+    );
+  }
+  assert_eq!(
+    read_all_ids(
+      &deps,
+      read_keys_msg(
+        Target::CodeIdRecent {
+          code_id: CODE_ID + 1,
+          desc: None,
+        },
+        None,
+        2,
+        None,
+      ),
+    ),
+    vec![1]
+  );
+}
+
+#[test]
+fn timestamp_bounds_in_seconds_are_rejected() {
+  let deps = setup();
+  let seconds = mock_env().block.time.seconds();
+  let target = Target::Index(IndexBounds::Timestamp {
+    slot: 0,
+    between: Some((Some(Timestamp::from_nanos(seconds)), None)),
+    equals: None,
+    relative_to_now: None,
+  });
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    read_keys_msg(target, None, 10, None),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::ImplausibleTimestamp { nanos } if nanos == seconds));
+}
+
+#[test]
+fn timestamp_slot_holding_seconds_pages_past_the_first_page() {
+  let mut deps = setup();
+  // a contract that wrote seconds where nanos were expected
+  for i in 0..3 {
+    create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![IndexSlotValue::Timestamp {
+        slot: 0,
+        value: Timestamp::from_nanos(1_000_000_000_000 + i),
+      }],
+    );
+  }
+  let target = Target::Index(IndexBounds::Timestamp {
+    slot: 0,
+    between: None,
+    equals: None,
+    relative_to_now: None,
+  });
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(target, None, 1, None)),
+    vec![0, 1, 2]
+  );
+}
+
+#[test]
+fn cursor_from_another_target_is_rejected() {
+  let mut deps = setup();
+  for i in 0..3 {
+    let mut msg = create_msg(vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(i),
+    }]);
+    if let ExecuteMsg::Create {
+      tags,
+      relationships,
+      ..
+    } = &mut msg
+    {
+      *tags = Some(vec!["a".to_string(), "b".to_string()]);
+      *relationships = Some(
+        ["alice", "bob"]
+          .iter()
+          .map(|subject| AddressTag {
+            address: addr(subject),
+            tag: "owned_by".to_string(),
+          })
+          .collect(),
+      );
+    }
+    create_as(
+      &mut deps,
+      &mock_env(),
+      CREATOR,
+      &format!("child-{}", i),
+      msg,
+    );
+  }
+  let u64_between = |upper: u64| {
+    Target::Index(IndexBounds::Uint64 {
+      slot: 0,
+      between: Some((None, Some(Uint64::new(upper)))),
+      equals: None,
+    })
+  };
+  let code_id_recent = |code_id: u64| Target::CodeIdRecent {
+    code_id,
+    desc: None,
+  };
+  let pairs = vec![
+    (Target::Tag("a".to_string()), Target::Tag("b".to_string())),
+    (
+      Target::Relationship((addr("alice"), "owned_by".to_string())),
+      Target::Relationship((addr("bob"), "owned_by".to_string())),
+    ),
+    (code_id_recent(CODE_ID), code_id_recent(CODE_ID + 1)),
+    (u64_between(10), u64_between(20)),
+  ];
+  for (from, to) in pairs {
+    let page: ReadResponse = query_as(&deps, read_keys_msg(from.clone(), None, 1, None));
+    assert!(page.cursor.is_some());
+    // the same target resumes from the cursor
+    query_as::<ReadResponse>(&deps, read_keys_msg(from, None, 1, page.cursor.clone()));
+    let err = query(
+      deps.as_ref(),
+      mock_env(),
+      read_keys_msg(to.clone(), None, 1, page.cursor),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidCursor {}), "{:?}", to);
+  }
+}
+
+#[test]
+fn any_match_recently_updated_since_rev() {
+  let mut deps = setup();
+  create_n(&mut deps, 2);
+  let any_since = |deps: &MockDeps, rev: u64| -> bool {
+    let res: AnyMatchResponse = query_as(
+      deps,
+      QueryMsg::AnyMatch {
+        target: Target::RecentlyUpdated {
+          since: Some(Since::Rev(rev)),
+        },
+      },
+    );
+    res.exists
+  };
+  assert!(!any_since(&deps, 0));
+
+  update::update_as(
+    &mut deps,
+    &mock_env(),
+    "child-1",
+    update::update_msg(vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(1),
+    }]),
+  )
+  .unwrap();
+  assert!(any_since(&deps, 0));
+  assert!(!any_since(&deps, 1));
+}
+
+#[test]
+fn recently_updated_orders_by_last_update() {
+  let mut deps = setup();
+  let t0 = mock_env().block.time.seconds();
+  let ids: Vec<ContractID> = (0..3)
+    .map(|i| create(&mut deps, &env_at(t0), &format!("child-{}", i), vec![]))
+    .collect();
+  for (i, n) in [1, 0, 2, 1].iter().enumerate() {
+    update::update_as(
+      &mut deps,
+      &env_at(t0 + 60 * (i as u64 + 1)),
+      &format!("child-{}", n),
+      update::update_msg(vec![IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(i as u64 + 1),
+      }]),
+    )
+    .unwrap();
+  }
+
+  let target = Target::RecentlyUpdated { since: None };
+  let newest_first = vec![ids[1], ids[2], ids[0]];
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(target.clone(), None, 2, None)),
+    newest_first
+  );
+  let oldest_first: Vec<ContractID> = newest_first.into_iter().rev().collect();
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(target, Some(false), 2, None)),
+    oldest_first
+  );
+}
+
+#[test]
+fn is_first_page_is_only_set_without_a_cursor() {
+  let mut deps = setup();
+  create_n(&mut deps, 3);
+
+  let first: ReadResponse = query_as(&deps, read_keys_msg(Target::All, None, 2, None));
+  assert!(first.is_first_page);
+  assert_eq!(first.page_size, 2);
+  assert!(first.has_more);
+
+  let second: ReadResponse = query_as(&deps, read_keys_msg(Target::All, None, 2, first.cursor));
+  assert!(!second.is_first_page);
+  assert!(!second.has_more);
+  assert_eq!(second.cursor, None);
+
+  // the limit is reported after clamping
+  let clamped: ReadResponse = query_as(&deps, read_keys_msg(Target::All, None, 1000, None));
+  assert_eq!(clamped.page_size, MAX_LIMIT);
+}
+
+#[test]
+fn timestamp_equals_is_exact_to_the_nanosecond() {
+  let mut deps = setup();
+  let hour = 1_700_000_000 - 1_700_000_000 % 3600;
+  let start = Timestamp::from_seconds(hour);
+  let times = [
+    start.minus_nanos(1),
+    start,
+    start.plus_nanos(1),
+    start.plus_seconds(1800),
+    start.plus_seconds(3600),
+  ];
+  let ids: Vec<ContractID> = times
+    .iter()
+    .enumerate()
+    .map(|(i, time)| {
+      create(
+        &mut deps,
+        &mock_env(),
+        &format!("child-{}", i),
+        vec![IndexSlotValue::Timestamp {
+          slot: 0,
+          value: *time,
+        }],
+      )
+    })
+    .collect();
+
+  let equals = IndexBounds::Timestamp {
+    slot: 0,
+    between: None,
+    equals: Some(start),
+    relative_to_now: None,
+  };
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(Target::Index(equals), None, 10, None)),
+    vec![ids[1]]
+  );
+
+  // the hour helper covers the whole hour, excluding the next one's start
+  let in_hour = IndexBounds::timestamp_in_hour(0, start.plus_seconds(60));
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(Target::Index(in_hour), None, 10, None)),
+    vec![ids[1], ids[2], ids[3]]
+  );
+}
+
+#[test]
+fn equals_pages_stay_inside_the_value_block() {
+  let mut deps = setup();
+  let mut block: Vec<ContractID> = vec![];
+  for i in 0..64 {
+    let value = match i {
+      0 | 1 => 1,
+      62 | 63 => 9,
+      _ => 5,
+    };
+    let id = create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(value),
+      }],
+    );
+    if value == 5 {
+      block.push(id);
+    }
+  }
+  let target = || {
+    Target::Index(IndexBounds::Uint64 {
+      slot: 0,
+      between: None,
+      equals: Some(Uint64::new(5)),
+    })
+  };
+
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(target(), Some(false), 25, None)),
+    block
+  );
+  let mut reversed = block.clone();
+  reversed.reverse();
+  assert_eq!(
+    read_all_ids(&deps, read_keys_msg(target(), Some(true), 25, None)),
+    reversed
+  );
+
+  // a cursor naming another value still resumes inside the block
+  let page: ReadResponse = query_as(&deps, read_keys_msg(target(), Some(false), 25, None));
+  let (value, id) = page.cursor.unwrap();
+  let (scope, _) = value.split_once(':').unwrap();
+  let cursor = (format!("{}:9", scope), id);
+  let rest = read_all_ids(
+    &deps,
+    read_keys_msg(target(), Some(false), 25, Some(cursor)),
+  );
+  assert_eq!(rest, block[25..].to_vec());
+}
+
+#[test]
+fn relative_to_now_resolves_against_the_block_time() {
+  let mut deps = setup();
+  let now = mock_env().block.time;
+  let expiries = [
+    now.minus_seconds(10),
+    now.minus_seconds(1),
+    now,
+    now.plus_seconds(1),
+    now.plus_seconds(10),
+  ];
+  for (i, expiry) in expiries.iter().enumerate() {
+    create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![IndexSlotValue::Timestamp {
+        slot: 0,
+        value: *expiry,
+      }],
+    );
+  }
+  let read_at = |time: Timestamp, relative_to_now: RelativeBound| -> Vec<ContractID> {
+    let target = Target::Index(IndexBounds::Timestamp {
+      slot: 0,
+      between: None,
+      equals: None,
+      relative_to_now: Some(relative_to_now),
+    });
+    let mut env = mock_env();
+    env.block.time = time;
+    let res = query(deps.as_ref(), env, read_keys_msg(target, None, 10, None)).unwrap();
+    let page: ReadResponse = from_binary(&res).unwrap();
+    page
+      .raw_keys
+      .unwrap()
+      .into_iter()
+      .map(|(_, id)| id)
+      .collect()
+  };
+
+  assert_eq!(read_at(now, RelativeBound::BeforeNow), vec![0, 1]);
+  assert_eq!(read_at(now, RelativeBound::AfterNow), vec![2, 3, 4]);
+
+  // the same query later on sees more of them expired
+  let later = now.plus_seconds(5);
+  assert_eq!(read_at(later, RelativeBound::BeforeNow), vec![0, 1, 2, 3]);
+  assert_eq!(read_at(later, RelativeBound::AfterNow), vec![4]);
+}
+
+#[test]
+fn equals_with_between_is_rejected() {
+  let mut deps = setup();
+  create(
+    &mut deps,
+    &mock_env(),
+    "child",
+    vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(5),
+    }],
+  );
+  let target = |equals: Option<u64>, between: Option<(Option<Uint64>, Option<Uint64>)>| {
+    Target::Index(IndexBounds::Uint64 {
+      slot: 0,
+      between,
+      equals: equals.map(Uint64::new),
+    })
+  };
+  let between = Some((Some(Uint64::new(1)), None));
+
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    read_keys_msg(target(Some(5), between.clone()), None, 10, None),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::ConflictingBounds {}));
+
+  // either one alone is fine
+  for target in [target(Some(5), None), target(None, between)].iter() {
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target.clone(), None, 10, None)),
+      vec![0]
+    );
+  }
+}
+
+#[test]
+fn since_reads_return_each_contracts_rev() {
+  let mut deps = setup();
+  create_n(&mut deps, 3);
+  for (child, n_updates) in [("child-1", 2u64), ("child-2", 1)].iter() {
+    for i in 0..*n_updates {
+      update::update_as(
+        &mut deps,
+        &mock_env(),
+        child,
+        update::update_msg(vec![IndexSlotValue::Uint64 {
+          slot: 0,
+          value: Uint64::new(i + 1),
+        }]),
+      )
+      .unwrap();
+    }
+  }
+  let read = |since: Option<Since>| -> Vec<(Addr, Option<u64>)> {
+    let mut msg = read_keys_msg(Target::All, None, 10, None);
+    if let QueryMsg::Read {
+      raw_keys,
+      since: maybe_since,
+      ..
+    } = &mut msg
+    {
+      *raw_keys = None;
+      *maybe_since = since;
+    }
+    let page: ReadResponse = query_as(&deps, msg);
+    assert!(page.page.iter().all(|e| e.meta.is_none()));
+    page.page.into_iter().map(|e| (e.address, e.rev)).collect()
+  };
+
+  assert_eq!(
+    read(Some(Since::Rev(0))),
+    vec![(addr("child-1"), Some(2)), (addr("child-2"), Some(1))]
+  );
+  assert_eq!(read(Some(Since::Rev(1))), vec![(addr("child-1"), Some(2))]);
+  assert_eq!(
+    read(None),
+    vec![
+      (addr("child-0"), None),
+      (addr("child-1"), None),
+      (addr("child-2"), None),
+    ]
+  );
+}
+
+#[test]
+fn raw_keys_are_the_index_keys_of_a_full_read() {
+  let mut deps = setup();
+  for (i, value) in [30u64, 10, 20].iter().enumerate() {
+    create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(*value),
+      }],
+    );
+  }
+  let target = Target::Index(IndexBounds::Uint64 {
+    slot: 0,
+    between: None,
+    equals: None,
+  });
+
+  let raw: ReadResponse = query_as(&deps, read_keys_msg(target.clone(), None, 2, None));
+  assert!(raw.page.is_empty());
+  assert_eq!(
+    raw.raw_keys,
+    Some(vec![("10".to_string(), 1), ("20".to_string(), 2)])
+  );
+  assert_eq!(raw.count, 2);
+
+  // a full read of the same page resolves the same keys, to the same cursor
+  let mut msg = read_keys_msg(target.clone(), None, 2, None);
+  if let QueryMsg::Read { raw_keys, .. } = &mut msg {
+    *raw_keys = None;
+  }
+  let full: ReadResponse = query_as(&deps, msg);
+  let addrs: Vec<Addr> = full.page.into_iter().map(|e| e.address).collect();
+  assert_eq!(addrs, vec![addr("child-1"), addr("child-2")]);
+  assert_eq!(full.cursor, raw.cursor);
+  assert_eq!(full.has_more, raw.has_more);
+
+  let mut msg = read_keys_msg(target, None, 2, None);
+  if let QueryMsg::Read { meta, .. } = &mut msg {
+    *meta = Some(true);
+  }
+  let err = query(deps.as_ref(), mock_env(), msg).unwrap_err();
+  assert!(matches!(err, ContractError::ConflictingParams { .. }));
+}
+
+#[test]
+fn code_id_equals_any_merges_code_ids_by_id() {
+  let mut deps = setup();
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::UpdateAllowedCodeIds {
+      code_ids: vec![1, 2, 3, 4],
+    },
+  )
+  .unwrap();
+  for (i, code_id) in [3u64, 1, 4, 2, 2, 3, 4, 1].iter().enumerate() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create {
+      code_id: maybe_code_id,
+      ..
+    } = &mut msg
+    {
+      *maybe_code_id = Some(*code_id);
+    }
+    let contract_addr = format!("child-{}", i);
+    create_as(&mut deps, &mock_env(), CREATOR, &contract_addr, msg);
+  }
+
+  let bounds = IndexBounds::CodeId {
+    between: None,
+    equals: None,
+    equals_any: Some(vec![Uint64::new(3), Uint64::new(1), Uint64::new(2)]),
+  };
+  for limit in [1, 2, 4].iter().copied() {
+    let target = Target::Index(bounds.clone());
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target, None, limit, None)),
+      vec![0, 1, 3, 4, 5, 7],
+      "limit: {}",
+      limit
+    );
+    let target = Target::Index(bounds.clone());
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(target, Some(true), limit, None)),
+      vec![7, 5, 4, 3, 1, 0],
+      "limit: {}",
+      limit
+    );
+  }
+}
+
+fn feed_msg(
+  desc: Option<bool>,
+  limit: u32,
+  cursor: Option<(Timestamp, ContractID)>,
+) -> QueryMsg {
+  QueryMsg::Feed {
+    desc,
+    limit: Some(limit),
+    cursor,
+  }
+}
+
+#[test]
+fn feed_lists_contracts_by_creation_time() {
+  let mut deps = setup();
+  for (contract_addr, seconds, code_id) in [
+    ("child-0", 100, CODE_ID),
+    ("child-1", 300, CODE_ID + 1),
+    ("child-2", 200, CODE_ID),
+  ]
+  .iter()
+  {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create {
+      code_id: maybe_code_id,
+      ..
+    } = &mut msg
+    {
+      *maybe_code_id = Some(*code_id);
+    }
+    create_as(&mut deps, &env_at(*seconds), CREATOR, contract_addr, msg);
+  }
+  let item = |contract_addr: &str, contract_id: ContractID, code_id: u64, seconds: u64| FeedItem {
+    addr: addr(contract_addr),
+    contract_id,
+    code_id,
+    created_at: Timestamp::from_seconds(seconds),
+  };
+
+  let page: FeedResponse = query_as(&deps, feed_msg(None, 2, None));
+  assert_eq!(
+    page.items,
+    vec![
+      item("child-1", 1, CODE_ID + 1, 300),
+      item("child-2", 2, CODE_ID, 200)
+    ]
+  );
+  assert_eq!(page.cursor, Some((Timestamp::from_seconds(200), 2)));
+  let page: FeedResponse = query_as(&deps, feed_msg(None, 2, page.cursor));
+  assert_eq!(page.items, vec![item("child-0", 0, CODE_ID, 100)]);
+  assert_eq!(page.cursor, None);
+
+  let page: FeedResponse = query_as(&deps, feed_msg(Some(false), 1, None));
+  assert_eq!(page.items, vec![item("child-0", 0, CODE_ID, 100)]);
+
+  // each item holds just the four fields a feed renders
+  let res = query(deps.as_ref(), mock_env(), feed_msg(None, 1, None)).unwrap();
+  let json: serde_json::Value = serde_json::from_slice(res.as_slice()).unwrap();
+  let mut keys: Vec<&String> = json["items"][0].as_object().unwrap().keys().collect();
+  keys.sort();
+  assert_eq!(keys, vec!["addr", "code_id", "contract_id", "created_at"]);
+}
+
+#[test]
+fn limits_are_clamped_between_min_and_max() {
+  let mut deps = setup();
+  create_n(&mut deps, (MAX_LIMIT + 1) as usize);
+  let page_len = |limit: Option<u32>| {
+    let mut msg = read_keys_msg(Target::All, None, 0, None);
+    if let QueryMsg::Read {
+      limit: maybe_limit, ..
+    } = &mut msg
+    {
+      *maybe_limit = limit;
+    }
+    let page: ReadResponse = query_as(&deps, msg);
+    assert_eq!(page.count, page.raw_keys.unwrap().len() as u64);
+    page.count as u32
+  };
+
+  assert_eq!(page_len(None), DEFAULT_LIMIT);
+  assert_eq!(page_len(Some(MAX_LIMIT + 1)), MAX_LIMIT);
+  assert_eq!(page_len(Some(0)), MIN_LIMIT);
+}
+
+#[test]
+fn all_pages_through_every_contract_by_id() {
+  let mut deps = setup();
+  let ids = create_n(&mut deps, 7);
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child-3"),
+    },
+  )
+  .unwrap();
+  let expected: Vec<ContractID> = ids.into_iter().filter(|id| *id != 3).collect();
+
+  for limit in [1, 3, 10].iter().copied() {
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(Target::All, None, limit, None)),
+      expected
+    );
+    let mut reversed = expected.clone();
+    reversed.reverse();
+    assert_eq!(
+      read_all_ids(&deps, read_keys_msg(Target::All, Some(true), limit, None)),
+      reversed
+    );
+  }
+}
+
+#[test]
+fn descending_text_pages_match_the_ascending_order_reversed() {
+  let mut deps = setup();
+  let values = ["b", "d", "a", "c", "b", "e", "d", "a", "c", "e", "b", "d"];
+  let mut rows: Vec<(String, ContractID)> = values
+    .iter()
+    .enumerate()
+    .map(|(i, value)| {
+      let id = create(
+        &mut deps,
+        &mock_env(),
+        &format!("child-{}", i),
+        vec![IndexSlotValue::Text {
+          slot: 0,
+          value: value.to_string(),
+        }],
+      );
+      (value.to_string(), id)
+    })
+    .collect();
+  rows.sort();
+
+  let ids_where = |keep: &dyn Fn(&str) -> bool| -> Vec<ContractID> {
+    rows
+      .iter()
+      .filter(|(value, _)| keep(value))
+      .map(|(_, id)| *id)
+      .collect()
+  };
+  let cases: Vec<(IndexBounds, Vec<ContractID>)> = vec![
+    (
+      IndexBounds::Text {
+        slot: 0,
+        between: None,
+        equals: None,
+      },
+      ids_where(&|_| true),
+    ),
+    (
+      IndexBounds::Text {
+        slot: 0,
+        between: Some((Some("b".to_string()), Some("e".to_string()))),
+        equals: None,
+      },
+      ids_where(&|v| v >= "b" && v < "e"),
+    ),
+    (
+      IndexBounds::Text {
+        slot: 0,
+        between: Some((None, Some("c".to_string()))),
+        equals: None,
+      },
+      ids_where(&|v| v < "c"),
+    ),
+    (
+      IndexBounds::Text {
+        slot: 0,
+        between: Some((Some("c".to_string()), None)),
+        equals: None,
+      },
+      ids_where(&|v| v >= "c"),
+    ),
+    (
+      IndexBounds::Text {
+        slot: 0,
+        between: None,
+        equals: Some("d".to_string()),
+      },
+      ids_where(&|v| v == "d"),
+    ),
+  ];
+
+  for (bounds, ascending) in cases {
+    let mut descending = ascending.clone();
+    descending.reverse();
+    for limit in [1, 2, 5].iter().copied() {
+      let target = || Target::Index(bounds.clone());
+      assert_eq!(
+        read_all_ids(&deps, read_keys_msg(target(), Some(false), limit, None)),
+        ascending,
+        "asc {:?}, limit: {}",
+        bounds,
+        limit
+      );
+      assert_eq!(
+        read_all_ids(&deps, read_keys_msg(target(), Some(true), limit, None)),
+        descending,
+        "desc {:?}, limit: {}",
+        bounds,
+        limit
+      );
+    }
+  }
+}
+
+/// Storage that records every key read through it, for counting how often a
+/// query loads each item.
+struct ReadLog<'a> {
+  inner: &'a MockStorage,
+  keys: RefCell<Vec<Vec<u8>>>,
+}
+
+impl Storage for ReadLog<'_> {
+  fn get(
+    &self,
+    key: &[u8],
+  ) -> Option<Vec<u8>> {
+    self.keys.borrow_mut().push(key.to_vec());
+    self.inner.get(key)
+  }
+
+  fn range<'a>(
+    &'a self,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+    order: Order,
+  ) -> Box<dyn Iterator<Item = Record> + 'a> {
+    self.inner.range(start, end, order)
+  }
+
+  fn set(
+    &mut self,
+    _key: &[u8],
+    _value: &[u8],
+  ) {
+    unreachable!("queries don't write")
+  }
+
+  fn remove(
+    &mut self,
+    _key: &[u8],
+  ) {
+    unreachable!("queries don't write")
+  }
+}
+
+#[test]
+fn since_and_meta_load_each_rows_metadata_once() {
+  let mut deps = setup();
+  let addrs = ["child-0", "child-1", "child-2"];
+  for contract_addr in addrs.iter() {
+    create(&mut deps, &mock_env(), contract_addr, vec![]);
+  }
+  update::update_as(
+    &mut deps,
+    &mock_env(),
+    "child-1",
+    update::update_msg(vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(1),
+    }]),
+  )
+  .unwrap();
+
+  let cases = [
+    (Some(Since::Rev(0)), Some(true), 1),
+    (Some(Since::Rev(0)), None, 1),
+    (None, Some(true), 3),
+  ];
+  for (since_param, meta_param, rows) in cases.iter() {
+    let mut msg = read_keys_msg(Target::All, None, 10, None);
+    if let QueryMsg::Read {
+      since,
+      meta,
+      raw_keys,
+      ..
+    } = &mut msg
+    {
+      *since = since_param.clone();
+      *meta = *meta_param;
+      *raw_keys = None;
+    }
+    let log = ReadLog {
+      inner: &deps.storage,
+      keys: RefCell::new(vec![]),
+    };
+    let page: ReadResponse = from_binary(
+      &query(
+        Deps {
+          storage: &log,
+          api: &deps.api,
+          querier: QuerierWrapper::new(&deps.querier),
+        },
+        mock_env(),
+        msg,
+      )
+      .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(page.page.len(), *rows);
+
+    let keys = log.keys.borrow();
+    for contract_addr in addrs.iter() {
+      let key = METADATA.key(addr(contract_addr));
+      let loads = keys.iter().filter(|k| k.as_slice() == &*key).count();
+      assert_eq!(
+        loads, 1,
+        "{}, since: {:?}, meta: {:?}",
+        contract_addr, since_param, meta_param
+      );
+    }
+  }
+}
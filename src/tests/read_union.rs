@@ -0,0 +1,109 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::msg::{ReadUnionResponse, UnionTarget};
+
+fn create_tagged(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  tags: &[&str],
+) -> ContractID {
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { tags: t, .. } = &mut msg {
+    *t = Some(tags.iter().map(|x| x.to_string()).collect());
+  }
+  create_as(deps, &mock_env(), CREATOR, contract_addr, msg)
+}
+
+fn union_ids(
+  deps: &MockDeps,
+  targets: &[UnionTarget],
+  desc: Option<bool>,
+  limit: u32,
+) -> Vec<Vec<ContractID>> {
+  let mut pages: Vec<Vec<ContractID>> = vec![];
+  let mut cursor: Option<ContractID> = None;
+  loop {
+    let res: ReadUnionResponse = query_as(
+      deps,
+      QueryMsg::ReadUnion {
+        targets: targets.to_vec(),
+        desc,
+        limit: Some(limit),
+        cursor,
+      },
+    );
+    pages.push(res.items.iter().map(|(id, _)| *id).collect());
+    if res.cursor.is_none() {
+      return pages;
+    }
+    cursor = res.cursor;
+  }
+}
+
+#[test]
+fn union_takes_each_target_in_its_own_order() {
+  let mut deps = setup();
+  let ids: Vec<ContractID> = (0..5)
+    .map(|i| create_tagged(&mut deps, &format!("child-{}", i), &["red", "blue"]))
+    .collect();
+
+  // the last two red contracts and the first two blue ones
+  let targets = vec![
+    UnionTarget {
+      target: Target::Tag("red".to_string()),
+      desc: Some(true),
+      limit: Some(2),
+    },
+    UnionTarget {
+      target: Target::Tag("blue".to_string()),
+      desc: Some(false),
+      limit: Some(2),
+    },
+  ];
+  assert_eq!(
+    union_ids(&deps, &targets, None, 3),
+    vec![vec![ids[0], ids[1], ids[3]], vec![ids[4]]]
+  );
+  assert_eq!(
+    union_ids(&deps, &targets, Some(true), 3),
+    vec![vec![ids[4], ids[3], ids[1]], vec![ids[0]]]
+  );
+}
+
+#[test]
+fn union_lists_a_contract_matched_twice_once() {
+  let mut deps = setup();
+  let a = create_tagged(&mut deps, "child-a", &["red", "blue"]);
+  let b = create_tagged(&mut deps, "child-b", &["blue"]);
+  let targets = vec![
+    UnionTarget {
+      target: Target::Tag("red".to_string()),
+      desc: None,
+      limit: None,
+    },
+    UnionTarget {
+      target: Target::Tag("blue".to_string()),
+      desc: None,
+      limit: None,
+    },
+  ];
+  assert_eq!(union_ids(&deps, &targets, None, 10), vec![vec![a, b]]);
+}
+
+#[test]
+fn union_rejects_an_empty_target_list() {
+  let deps = setup();
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    QueryMsg::ReadUnion {
+      targets: vec![],
+      desc: None,
+      limit: None,
+      cursor: None,
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidSetSize { .. }));
+}
@@ -0,0 +1,81 @@
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::state::COUNT;
+
+/// Run RecountTotal a chunk at a time until it's done.
+fn recount(
+  deps: &mut MockDeps,
+  limit: u32,
+  mut cursor: Option<ContractID>,
+) {
+  loop {
+    let res = admin_as(
+      deps,
+      CREATOR,
+      AdminMsg::RecountTotal {
+        limit: Some(limit),
+        cursor,
+      },
+    )
+    .unwrap();
+    let attr = |key: &str| {
+      res
+        .attributes
+        .iter()
+        .find(|a| a.key == key)
+        .unwrap()
+        .value
+        .clone()
+    };
+    if attr("done") == "true" {
+      return;
+    }
+    cursor = Some(attr("cursor").parse().unwrap());
+  }
+}
+
+#[test]
+fn recount_repairs_a_corrupted_count() {
+  let mut deps = setup();
+  for i in 0..3 {
+    create(&mut deps, &mock_env(), &format!("child-{}", i), vec![]);
+  }
+  COUNT.save(&mut deps.storage, &99).unwrap();
+
+  recount(&mut deps, 2, None);
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 3);
+}
+
+#[test]
+fn removal_between_chunks_is_not_counted() {
+  let mut deps = setup();
+  let ids: Vec<ContractID> = (0..4)
+    .map(|i| create(&mut deps, &mock_env(), &format!("child-{}", i), vec![]))
+    .collect();
+  COUNT.save(&mut deps.storage, &99).unwrap();
+
+  // count the first two, then remove one already counted and one not
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::RecountTotal {
+      limit: Some(2),
+      cursor: None,
+    },
+  )
+  .unwrap();
+  for contract_addr in ["child-0", "child-3"].iter() {
+    admin_as(
+      &mut deps,
+      CREATOR,
+      AdminMsg::Remove {
+        contract_addr: addr(contract_addr),
+      },
+    )
+    .unwrap();
+  }
+
+  recount(&mut deps, 2, Some(ids[1]));
+  assert_eq!(COUNT.load(&deps.storage).unwrap(), 2);
+}
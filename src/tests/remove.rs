@@ -0,0 +1,267 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{Binary, StdResult, Timestamp, Uint128, Uint64};
+
+use super::*;
+use crate::models::{AddressTag, TagUpdates};
+use crate::msg::CountResponse;
+use crate::state::{COUNT, IX_META_TIMESTAMP, IX_META_U64, METADATA, NEXT_ID};
+
+/// Index values in every type, with tags, a relationship, a label, a doc and
+/// a code ID change, so that a contract touches every part of the repo.
+fn create_everything(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+) -> ContractID {
+  let env = mock_env();
+  let mut msg = create_msg(vec![
+    IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(1),
+    },
+    IndexSlotValue::Uint128 {
+      slot: 0,
+      value: Uint128::new(2),
+    },
+    IndexSlotValue::Timestamp {
+      slot: 0,
+      value: env.block.time,
+    },
+    IndexSlotValue::Text {
+      slot: 0,
+      value: "text".to_string(),
+    },
+    IndexSlotValue::Boolean {
+      slot: 0,
+      value: true,
+    },
+  ]);
+  if let ExecuteMsg::Create {
+    label,
+    tags,
+    relationships,
+    ..
+  } = &mut msg
+  {
+    *label = Some(format!("{}-label", contract_addr));
+    *tags = Some(vec!["red".to_string()]);
+    *relationships = Some(vec![AddressTag {
+      address: addr("owner"),
+      tag: "owned_by".to_string(),
+    }]);
+  }
+  let id = create_as(deps, &env, CREATOR, contract_addr, msg);
+
+  let later = env_at(env.block.time.seconds() + 60);
+  execute(
+    deps.as_mut(),
+    later.clone(),
+    mock_info(contract_addr, &[]),
+    ExecuteMsg::Update {
+      values: Some(vec![
+        IndexSlotValue::Uint64 {
+          slot: 0,
+          value: Uint64::new(3),
+        },
+        IndexSlotValue::Timestamp {
+          slot: 0,
+          value: Timestamp::from_seconds(later.block.time.seconds()),
+        },
+      ]),
+      relationships: None,
+      tags: Some(TagUpdates {
+        added: Some(vec!["blue".to_string()]),
+        removed: None,
+      }),
+      code_id: Some(CODE_ID + 1),
+    },
+  )
+  .unwrap();
+  execute(
+    deps.as_mut(),
+    later,
+    mock_info(contract_addr, &[]),
+    ExecuteMsg::SetDoc {
+      contract_addr: addr(contract_addr),
+      doc: Binary::from(br#"{"a":1}"#.to_vec()),
+    },
+  )
+  .unwrap();
+  id
+}
+
+#[test]
+fn remove_leaves_nothing_behind() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "neighbor", vec![]);
+  let before = storage_snapshot(&deps);
+  let next_id = NEXT_ID.load(&deps.storage).unwrap();
+
+  create_everything(&mut deps, "child");
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+
+  // the removed contract's ID is never reused, so that's all that remains
+  assert_eq!(NEXT_ID.load(&deps.storage).unwrap(), next_id + 1);
+  NEXT_ID.save(&mut deps.storage, &next_id).unwrap();
+  // slot metadata records the slot's last write, which outlives the contract
+  for map in [IX_META_U64, IX_META_TIMESTAMP] {
+    map
+      .update(&mut deps.storage, 0, |meta| -> StdResult<_> {
+        let mut meta = meta.unwrap();
+        meta.updated_at = None;
+        meta.updated_key = None;
+        Ok(meta)
+      })
+      .unwrap();
+  }
+  assert_eq!(storage_snapshot(&deps), before);
+}
+
+#[test]
+fn remove_saturates_count_at_zero() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  // e.g. after a RecountTotal over a repo with a missing mapping
+  COUNT.save(&mut deps.storage, &0).unwrap();
+
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+  let count: CountResponse = query_as(&deps, QueryMsg::Count {});
+  assert_eq!(count.count, 0);
+}
+
+#[test]
+fn purge_orphan_leaves_nothing_behind() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "neighbor", vec![]);
+  let before = storage_snapshot(&deps);
+  let next_id = NEXT_ID.load(&deps.storage).unwrap();
+
+  // a create whose instantiate reply never arrives
+  let mut msg = create_msg(vec![IndexSlotValue::Text {
+    slot: 0,
+    value: "text".to_string(),
+  }]);
+  if let ExecuteMsg::Create {
+    tags,
+    relationships,
+    ..
+  } = &mut msg
+  {
+    *tags = Some(vec!["red".to_string()]);
+    *relationships = Some(vec![AddressTag {
+      address: addr("owner"),
+      tag: "owned_by".to_string(),
+    }]);
+  }
+  let resp = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap();
+  let contract_id = pending_id(&resp);
+  assert_ne!(storage_snapshot(&deps), before);
+
+  admin_as(&mut deps, CREATOR, AdminMsg::PurgeOrphan { contract_id }).unwrap();
+
+  assert_eq!(NEXT_ID.load(&deps.storage).unwrap(), next_id + 1);
+  NEXT_ID.save(&mut deps.storage, &next_id).unwrap();
+  assert_eq!(storage_snapshot(&deps), before);
+
+  let err = admin_as(&mut deps, CREATOR, AdminMsg::PurgeOrphan { contract_id }).unwrap_err();
+  assert!(matches!(err, ContractError::NotFound {}));
+}
+
+#[test]
+fn purge_orphan_rejects_registered_contracts() {
+  let mut deps = setup();
+  let contract_id = create(&mut deps, &mock_env(), "child", vec![]);
+
+  let err = admin_as(&mut deps, CREATOR, AdminMsg::PurgeOrphan { contract_id }).unwrap_err();
+  assert!(matches!(err, ContractError::NotAnOrphan { .. }));
+}
+
+#[test]
+fn removed_contract_cannot_update() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let meta = METADATA.load(&deps.storage, addr("child")).unwrap();
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+  // stale metadata, as left behind by older versions of remove
+  METADATA
+    .save(&mut deps.storage, addr("child"), &meta)
+    .unwrap();
+
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info("child", &[]),
+    ExecuteMsg::Update {
+      values: Some(vec![IndexSlotValue::Uint64 {
+        slot: 0,
+        value: Uint64::new(1),
+      }]),
+      relationships: None,
+      tags: None,
+      code_id: None,
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::ContractNotRegistered {}));
+}
+
+#[test]
+fn deregister_removes_the_calling_contract() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "neighbor", vec![]);
+  let before = storage_snapshot(&deps);
+  create(&mut deps, &mock_env(), "child", vec![]);
+
+  let deregister = |deps: &mut MockDeps| {
+    execute(
+      deps.as_mut(),
+      mock_env(),
+      mock_info("child", &[]),
+      ExecuteMsg::Deregister {},
+    )
+  };
+  deregister(&mut deps).unwrap();
+  NEXT_ID.save(&mut deps.storage, &1).unwrap();
+  assert_eq!(storage_snapshot(&deps), before);
+
+  let err = deregister(&mut deps).unwrap_err();
+  assert!(matches!(err, ContractError::ContractNotRegistered {}));
+}
+
+#[test]
+fn removed_contract_ids_are_not_reused() {
+  let mut deps = setup();
+  let first = create(&mut deps, &mock_env(), "child-0", vec![]);
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child-0"),
+    },
+  )
+  .unwrap();
+
+  let second = create(&mut deps, &mock_env(), "child-1", vec![]);
+  assert_ne!(second, first);
+  assert_eq!(second, first + 1);
+}
@@ -0,0 +1,189 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Uint128, Uint64};
+
+use super::*;
+use crate::models::IndexType;
+use crate::msg::{HistogramResponse, UnsetResponse};
+
+fn u64_value(value: u64) -> IndexSlotValue {
+  IndexSlotValue::Uint64 {
+    slot: 0,
+    value: Uint64::new(value),
+  }
+}
+
+fn text_value(value: &str) -> IndexSlotValue {
+  IndexSlotValue::Text {
+    slot: 0,
+    value: value.to_string(),
+  }
+}
+
+fn unset_msg(
+  kind: IndexType,
+  limit: Option<u32>,
+  cursor: Option<ContractID>,
+  max_scan: Option<u32>,
+) -> QueryMsg {
+  QueryMsg::Unset {
+    kind,
+    slot: 0,
+    limit,
+    cursor,
+    max_scan,
+  }
+}
+
+fn histogram_msg(
+  bucket_width: u128,
+  between: Option<(Option<Uint128>, Option<Uint128>)>,
+  max_scan: Option<u32>,
+  cursor: Option<(Uint128, ContractID)>,
+) -> QueryMsg {
+  QueryMsg::Histogram {
+    kind: IndexType::Uint64,
+    slot: 0,
+    bucket_width: Uint128::new(bucket_width),
+    between,
+    max_scan,
+    cursor,
+  }
+}
+
+#[test]
+fn unset_lists_contracts_without_a_value_in_the_slot() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-0", vec![text_value("a")]);
+  create(&mut deps, &env, "child-1", vec![u64_value(1)]);
+  create(
+    &mut deps,
+    &env,
+    "child-2",
+    vec![u64_value(2), text_value("b")],
+  );
+  create(&mut deps, &env, "child-3", vec![u64_value(3)]);
+
+  let res: UnsetResponse = query_as(&deps, unset_msg(IndexType::Text, None, None, None));
+  assert_eq!(res.contracts, vec![addr("child-1"), addr("child-3")]);
+  assert_eq!(res.cursor, None);
+  assert!(!res.truncated);
+
+  let res: UnsetResponse = query_as(&deps, unset_msg(IndexType::Uint64, None, None, None));
+  assert_eq!(res.contracts, vec![addr("child-0")]);
+
+  // a full page points at its last contract
+  let res: UnsetResponse = query_as(&deps, unset_msg(IndexType::Text, Some(1), None, None));
+  assert_eq!(res.contracts, vec![addr("child-1")]);
+  assert_eq!(res.cursor, Some(1));
+  let res: UnsetResponse = query_as(&deps, unset_msg(IndexType::Text, Some(1), Some(1), None));
+  assert_eq!(res.contracts, vec![addr("child-3")]);
+}
+
+#[test]
+fn histogram_counts_values_per_bucket() {
+  let mut deps = setup();
+  for (i, value) in [1u64, 4, 10, 12, 19, 35, 9].iter().enumerate() {
+    create(
+      &mut deps,
+      &mock_env(),
+      &format!("child-{}", i),
+      vec![u64_value(*value)],
+    );
+  }
+
+  let res: HistogramResponse = query_as(&deps, histogram_msg(10, None, None, None));
+  assert_eq!(
+    res.buckets,
+    vec![
+      (Uint128::new(0), 3),
+      (Uint128::new(10), 3),
+      (Uint128::new(30), 1),
+    ]
+  );
+  assert!(!res.truncated);
+  assert_eq!(res.cursor, None);
+
+  // bounds are inclusive below and exclusive above
+  let between = Some((Some(Uint128::new(4)), Some(Uint128::new(19))));
+  let res: HistogramResponse = query_as(&deps, histogram_msg(10, between, None, None));
+  assert_eq!(
+    res.buckets,
+    vec![(Uint128::new(0), 2), (Uint128::new(10), 2)]
+  );
+
+  let err = query(
+    deps.as_ref(),
+    mock_env(),
+    histogram_msg(0, None, None, None),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::InvalidBucketWidth {}));
+}
+
+#[test]
+fn scanning_queries_stop_at_max_scan_and_resume() {
+  let mut deps = setup();
+  let n: u64 = 40;
+  for i in 0..n {
+    // every third contract leaves the text slot unset
+    let mut values = vec![u64_value(i)];
+    if i % 3 != 0 {
+      values.push(text_value("set"));
+    }
+    create(&mut deps, &mock_env(), &format!("child-{}", i), values);
+  }
+
+  let mut unset: Vec<Addr> = vec![];
+  let mut cursor = None;
+  let mut n_calls = 0;
+  loop {
+    let res: UnsetResponse = query_as(&deps, unset_msg(IndexType::Text, None, cursor, Some(7)));
+    n_calls += 1;
+    unset.extend(res.contracts);
+    if res.cursor.is_none() {
+      assert!(!res.truncated);
+      break;
+    }
+    assert!(res.truncated);
+    cursor = res.cursor;
+  }
+  let expected: Vec<Addr> = (0..n)
+    .filter(|i| i % 3 == 0)
+    .map(|i| addr(&format!("child-{}", i)))
+    .collect();
+  assert_eq!(unset, expected);
+  assert_eq!(n_calls, 6);
+
+  let mut buckets: Vec<(Uint128, u64)> = vec![];
+  let mut cursor = None;
+  loop {
+    let res: HistogramResponse = query_as(&deps, histogram_msg(10, None, Some(7), cursor));
+    assert_eq!(res.truncated, res.cursor.is_some());
+    // the first bucket of a page can continue the last one of the previous
+    for (start, count) in res.buckets {
+      match buckets.last_mut() {
+        Some((last, total)) if *last == start => *total += count,
+        _ => buckets.push((start, count)),
+      }
+    }
+    if !res.truncated {
+      break;
+    }
+    cursor = res.cursor;
+  }
+  assert_eq!(
+    buckets,
+    vec![
+      (Uint128::new(0), 10),
+      (Uint128::new(10), 10),
+      (Uint128::new(20), 10),
+      (Uint128::new(30), 10),
+    ]
+  );
+
+  // a zero budget still makes progress
+  let res: HistogramResponse = query_as(&deps, histogram_msg(10, None, Some(0), None));
+  assert_eq!(res.buckets, vec![(Uint128::new(0), 1)]);
+  assert_eq!(res.cursor, Some((Uint128::new(0), 0)));
+}
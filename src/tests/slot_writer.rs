@@ -0,0 +1,113 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{to_binary, ContractResult, SystemResult, Uint64, WasmQuery};
+
+use super::*;
+use crate::models::IndexType;
+
+const ACTION: &str = "write_score";
+
+fn protect_slot(
+  deps: &mut MockDeps,
+  action: Option<&str>,
+) {
+  admin_as(
+    deps,
+    CREATOR,
+    AdminMsg::SetSlotWriter {
+      kind: IndexType::Uint64,
+      slot: 0,
+      action: action.map(|x| x.to_string()),
+    },
+  )
+  .unwrap();
+}
+
+fn score(value: u64) -> IndexSlotValue {
+  IndexSlotValue::Uint64 {
+    slot: 0,
+    value: Uint64::new(value),
+  }
+}
+
+fn update_as_contract(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  values: Vec<IndexSlotValue>,
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(contract_addr, &[]),
+    ExecuteMsg::Update {
+      values: Some(values),
+      relationships: None,
+      tags: None,
+      code_id: None,
+    },
+  )
+}
+
+#[test]
+fn protected_slot_is_closed_to_unauthorized_updates() {
+  let mut deps = setup();
+  protect_slot(&mut deps, Some(ACTION));
+
+  // without an ACL, the repo's creator is authorized to every action
+  create(&mut deps, &mock_env(), "child", vec![score(1)]);
+
+  // but the contract itself isn't
+  let before = storage_snapshot(&deps);
+  let err = update_as_contract(&mut deps, "child", vec![score(2)]).unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  assert_eq!(storage_snapshot(&deps), before);
+
+  // other slots stay open to it
+  update_as_contract(
+    &mut deps,
+    "child",
+    vec![IndexSlotValue::Text {
+      slot: 0,
+      value: "text".to_string(),
+    }],
+  )
+  .unwrap();
+
+  protect_slot(&mut deps, None);
+  update_as_contract(&mut deps, "child", vec![score(2)]).unwrap();
+}
+
+#[test]
+fn protected_slot_is_closed_to_unauthorized_creates() {
+  let mut deps = setup();
+  protect_slot(&mut deps, Some(ACTION));
+
+  // an ACL that grants everything but the slot's action
+  deps.querier.update_wasm(|query| match query {
+    WasmQuery::Smart { msg, .. } => {
+      let is_allowed = !String::from_utf8_lossy(msg.as_slice()).contains(ACTION);
+      SystemResult::Ok(ContractResult::Ok(to_binary(&is_allowed).unwrap()))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetAcl {
+      acl_contract_addr: addr("acl"),
+    },
+  )
+  .unwrap();
+
+  let before = storage_snapshot(&deps);
+  let err = execute(
+    deps.as_mut(),
+    mock_env(),
+    mock_info(CREATOR, &[]),
+    create_msg(vec![score(1)]),
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  assert_eq!(storage_snapshot(&deps), before);
+
+  create(&mut deps, &mock_env(), "child", vec![]);
+}
@@ -0,0 +1,285 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_slice, to_binary, ContractResult, SystemResult, Uint64, WasmQuery};
+
+use super::*;
+use crate::models::{AddressTag, IndexType};
+use crate::msg::{
+  BoolBreakdownResponse, BusiestIndicesResponse, IndexSize, RankResponse, SlotSizeResponse,
+  StatsResponse, WalletSummaryResponse,
+};
+use crate::state::get_u64_index;
+
+fn u64_value(value: u64) -> IndexSlotValue {
+  IndexSlotValue::Uint64 {
+    slot: 0,
+    value: Uint64::new(value),
+  }
+}
+
+fn text_value(value: &str) -> IndexSlotValue {
+  IndexSlotValue::Text {
+    slot: 0,
+    value: value.to_string(),
+  }
+}
+
+fn bool_value(value: bool) -> IndexSlotValue {
+  IndexSlotValue::Boolean { slot: 0, value }
+}
+
+#[test]
+fn busiest_indices_ranks_slots_by_size() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(
+    &mut deps,
+    &env,
+    "child-0",
+    vec![u64_value(1), text_value("a"), bool_value(true)],
+  );
+  create(
+    &mut deps,
+    &env,
+    "child-1",
+    vec![u64_value(2), text_value("b")],
+  );
+  create(&mut deps, &env, "child-2", vec![u64_value(3)]);
+
+  let res: BusiestIndicesResponse = query_as(&deps, QueryMsg::BusiestIndices { top_n: 3 });
+  let size = |kind: IndexType, size: u64| IndexSize {
+    kind,
+    slot: 0,
+    name: None,
+    size,
+  };
+  assert_eq!(
+    res.indices,
+    vec![
+      size(IndexType::Uint64, 3),
+      size(IndexType::Text, 2),
+      size(IndexType::Boolean, 1),
+    ]
+  );
+}
+
+#[test]
+fn bool_breakdown_counts_each_value() {
+  let mut deps = setup();
+  let env = mock_env();
+  for (i, value) in [true, false, true, true, false].iter().enumerate() {
+    create(
+      &mut deps,
+      &env,
+      &format!("child-{}", i),
+      vec![bool_value(*value)],
+    );
+  }
+  create(&mut deps, &env, "child-unset", vec![]);
+
+  let res: BoolBreakdownResponse = query_as(&deps, QueryMsg::BoolBreakdown { slot: 0 });
+  assert_eq!(res.true_count, 3);
+  assert_eq!(res.false_count, 2);
+  assert!(!res.truncated);
+}
+
+#[test]
+fn slot_size_matches_a_full_scan() {
+  let mut deps = setup();
+  let env = mock_env();
+  for i in 0..4 {
+    create(&mut deps, &env, &format!("child-{}", i), vec![u64_value(i)]);
+  }
+  create(&mut deps, &env, "child-unset", vec![]);
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child-0"),
+    },
+  )
+  .unwrap();
+
+  let res: SlotSizeResponse = query_as(
+    &deps,
+    QueryMsg::SlotSize {
+      kind: IndexType::Uint64,
+      slot: 0,
+    },
+  );
+  let n_scanned = get_u64_index(0)
+    .unwrap()
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .count() as u64;
+  assert_eq!(res.size, 3);
+  assert_eq!(res.size, n_scanned);
+}
+
+#[test]
+fn stats_sums_each_category() {
+  let mut deps = setup();
+  let env = mock_env();
+  let empty: StatsResponse = query_as(&deps, QueryMsg::Stats {});
+  assert_eq!(
+    empty,
+    StatsResponse {
+      count: 0,
+      virtual_count: 0,
+      total_indexed: 0,
+      total_tags: 0,
+      total_relationships: 0,
+      total_presets: 0,
+    }
+  );
+
+  let mut msg = create_msg(vec![u64_value(1), text_value("a")]);
+  if let ExecuteMsg::Create {
+    tags,
+    relationships,
+    save_as,
+    ..
+  } = &mut msg
+  {
+    *tags = Some(vec!["red".to_string(), "blue".to_string()]);
+    *relationships = Some(vec![AddressTag {
+      address: addr("friend"),
+      tag: "follows".to_string(),
+    }]);
+    *save_as = Some("preset".to_string());
+  }
+  create_as(&mut deps, &env, CREATOR, "child-0", msg);
+  create(&mut deps, &env, "child-1", vec![u64_value(2)]);
+  let mut msg = create_msg(vec![bool_value(true)]);
+  if let ExecuteMsg::Create {
+    counts_toward_total,
+    label,
+    ..
+  } = &mut msg
+  {
+    *counts_toward_total = Some(false);
+    *label = Some("virtual".to_string());
+  }
+  create_as(&mut deps, &env, CREATOR, "child-2", msg);
+
+  let stats: StatsResponse = query_as(&deps, QueryMsg::Stats {});
+  assert_eq!(
+    stats,
+    StatsResponse {
+      count: 2,
+      virtual_count: 1,
+      total_indexed: 4,
+      total_tags: 2,
+      total_relationships: 1,
+      total_presets: 1,
+    }
+  );
+}
+
+fn rank_msg(
+  contract_addr: &str,
+  desc: bool,
+) -> QueryMsg {
+  QueryMsg::Rank {
+    contract_addr: addr(contract_addr),
+    kind: IndexType::Uint64,
+    slot: 0,
+    desc: Some(desc),
+  }
+}
+
+#[test]
+fn rank_counts_the_contracts_before_it() {
+  let mut deps = setup();
+  let env = mock_env();
+  for (i, value) in [30u64, 10, 20, 10].iter().enumerate() {
+    create(
+      &mut deps,
+      &env,
+      &format!("child-{}", i),
+      vec![u64_value(*value)],
+    );
+  }
+  create(&mut deps, &env, "child-unset", vec![]);
+
+  // ties are ordered by ID, in the same direction as values
+  for (child, asc, desc) in [
+    ("child-0", 4, 1),
+    ("child-1", 1, 4),
+    ("child-2", 3, 2),
+    ("child-3", 2, 3),
+  ]
+  .iter()
+  {
+    let res: RankResponse = query_as(&deps, rank_msg(child, false));
+    assert_eq!((res.rank, res.capped), (*asc, false));
+    let res: RankResponse = query_as(&deps, rank_msg(child, true));
+    assert_eq!((res.rank, res.capped), (*desc, false));
+  }
+
+  let err = query(deps.as_ref(), mock_env(), rank_msg("child-unset", false)).unwrap_err();
+  assert!(matches!(err, ContractError::NotFound {}));
+}
+
+fn wallet_summary(
+  deps: &MockDeps,
+  wallet: &str,
+) -> WalletSummaryResponse {
+  query_as(
+    deps,
+    QueryMsg::WalletSummary {
+      wallet: addr(wallet),
+    },
+  )
+}
+
+#[test]
+fn wallet_summary_counts_a_wallets_contracts_and_presets() {
+  let mut deps = setup();
+  let env = mock_env();
+  create(&mut deps, &env, "child-0", vec![]);
+  let mut msg = create_msg(vec![]);
+  if let ExecuteMsg::Create { save_as, .. } = &mut msg {
+    *save_as = Some("preset".to_string());
+  }
+  create_as(&mut deps, &env, CREATOR, "child-1", msg);
+
+  assert_eq!(
+    wallet_summary(&deps, CREATOR),
+    WalletSummaryResponse {
+      created_count: 2,
+      preset_count: 1,
+      can_create: true,
+      can_remove: true,
+    }
+  );
+  assert_eq!(
+    wallet_summary(&deps, "nobody"),
+    WalletSummaryResponse {
+      created_count: 0,
+      preset_count: 0,
+      can_create: false,
+      can_remove: false,
+    }
+  );
+
+  // with an ACL, permissions are whatever it grants the wallet
+  deps.querier.update_wasm(|query| match query {
+    WasmQuery::Smart { msg, .. } => {
+      let (_, action): (String, String) = from_slice(msg.as_slice()).unwrap();
+      SystemResult::Ok(ContractResult::Ok(
+        to_binary(&(action == "create")).unwrap(),
+      ))
+    },
+    other => panic!("unexpected query: {:?}", other),
+  });
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetAcl {
+      acl_contract_addr: addr("acl"),
+    },
+  )
+  .unwrap();
+  let summary = wallet_summary(&deps, "nobody");
+  assert!(summary.can_create);
+  assert!(!summary.can_remove);
+}
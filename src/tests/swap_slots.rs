@@ -0,0 +1,114 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Uint64;
+
+use super::*;
+use crate::models::IndexType;
+use crate::state::{get_u64_index, ID_2_INDEXED_VALUES, IX_META_U64};
+
+fn swap_msg(cursor: Option<ContractID>) -> AdminMsg {
+  AdminMsg::SwapSlots {
+    kind: IndexType::Uint64,
+    a: 0,
+    b: 1,
+    limit: Some(2),
+    cursor,
+  }
+}
+
+fn attr_value(
+  res: &Response,
+  key: &str,
+) -> String {
+  res
+    .attributes
+    .iter()
+    .find(|a| a.key == key)
+    .unwrap()
+    .value
+    .clone()
+}
+
+#[test]
+fn swap_slots_resumes_from_cursor() {
+  let mut msg = instantiate_msg();
+  msg.indices = Some(vec![
+    IndexSlotName::Uint64 {
+      slot: 0,
+      name: Some("a".to_string()),
+    },
+    IndexSlotName::Uint64 {
+      slot: 1,
+      name: Some("b".to_string()),
+    },
+    IndexSlotName::Uint64 {
+      slot: 2,
+      name: None,
+    },
+  ]);
+  let mut deps = setup_with(msg);
+  let env = mock_env();
+  let u64_value = |slot, n| IndexSlotValue::Uint64 {
+    slot,
+    value: Uint64::new(n),
+  };
+  let c0 = create(&mut deps, &env, "c0", vec![u64_value(0, 10)]);
+  let c1 = create(
+    &mut deps,
+    &env,
+    "c1",
+    vec![u64_value(0, 20), u64_value(1, 5)],
+  );
+  let c2 = create(&mut deps, &env, "c2", vec![u64_value(1, 7)]);
+
+  // the first chunk covers c0 and c1
+  let res = admin_as(&mut deps, CREATOR, swap_msg(None)).unwrap();
+  assert_eq!(attr_value(&res, "cursor"), c1.to_string());
+  assert_eq!(attr_value(&res, "done"), "false");
+
+  // a swap in progress must be resumed from where it left off, before
+  // another can start
+  let err = admin_as(&mut deps, CREATOR, swap_msg(None)).unwrap_err();
+  assert!(matches!(err, ContractError::CursorMismatch {}));
+  let err = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SwapSlots {
+      kind: IndexType::Uint64,
+      a: 0,
+      b: 2,
+      limit: None,
+      cursor: None,
+    },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::SlotSwapInProgress {}));
+
+  let res = admin_as(&mut deps, CREATOR, swap_msg(Some(c1))).unwrap();
+  assert_eq!(attr_value(&res, "cursor"), "");
+  assert_eq!(attr_value(&res, "done"), "true");
+
+  let (slot_0, slot_1) = (get_u64_index(0).unwrap(), get_u64_index(1).unwrap());
+  assert!(slot_1.has(&deps.storage, (10, c0)));
+  assert!(slot_1.has(&deps.storage, (20, c1)));
+  assert!(slot_0.has(&deps.storage, (5, c1)));
+  assert!(slot_0.has(&deps.storage, (7, c2)));
+  assert!(!slot_0.has(&deps.storage, (10, c0)));
+  assert!(!slot_1.has(&deps.storage, (7, c2)));
+
+  let values = ID_2_INDEXED_VALUES.load(&deps.storage, c0).unwrap();
+  assert_eq!(values.uint64[0], None);
+  assert_eq!(values.uint64[1], Some(10));
+
+  // sizes and names follow the values
+  let (meta_0, meta_1) = (
+    IX_META_U64.load(&deps.storage, 0).unwrap(),
+    IX_META_U64.load(&deps.storage, 1).unwrap(),
+  );
+  assert_eq!((meta_0.size, meta_1.size), (2, 2));
+  assert_eq!(meta_0.name, Some("b".to_string()));
+  assert_eq!(meta_1.name, Some("a".to_string()));
+
+  // once done, the cursor is no longer accepted
+  let err = admin_as(&mut deps, CREATOR, swap_msg(Some(c1))).unwrap_err();
+  assert!(matches!(err, ContractError::NoSlotSwapInProgress {}));
+}
@@ -0,0 +1,401 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{Binary, Timestamp, Uint64};
+
+use super::*;
+use crate::models::{AddressTag, RelationshipUpdates, TagUpdates, MAX_BATCH_SIZE, MAX_TAG_LEN};
+use crate::msg::CodeIdHistoryResponse;
+use crate::state::{
+  get_bool_index, get_u64_index, IX_CODE_ID, IX_META_BOOL, IX_META_STRING, IX_META_U64, IX_REV,
+  IX_UPDATED_AT, METADATA,
+};
+
+pub fn update_msg(values: Vec<IndexSlotValue>) -> ExecuteMsg {
+  ExecuteMsg::Update {
+    values: Some(values),
+    relationships: None,
+    tags: None,
+    code_id: None,
+  }
+}
+
+pub fn update_as(
+  deps: &mut MockDeps,
+  env: &Env,
+  contract_addr: &str,
+  msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+  execute(
+    deps.as_mut(),
+    env.clone(),
+    mock_info(contract_addr, &[]),
+    msg,
+  )
+}
+
+/// Drop a contract from the rev and updated_at indices, as a botched
+/// migration might.
+fn desync_indices(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  contract_id: ContractID,
+) {
+  let meta = METADATA.load(&deps.storage, addr(contract_addr)).unwrap();
+  IX_REV.remove(&mut deps.storage, (meta.rev, contract_id));
+  IX_UPDATED_AT.remove(&mut deps.storage, (meta.updated_at.nanos(), contract_id));
+}
+
+fn score(value: u64) -> IndexSlotValue {
+  IndexSlotValue::Uint64 {
+    slot: 0,
+    value: Uint64::new(value),
+  }
+}
+
+#[test]
+fn update_fails_on_desynced_indices_by_default() {
+  let mut deps = setup();
+  let id = create(&mut deps, &mock_env(), "child", vec![]);
+  desync_indices(&mut deps, "child", id);
+
+  let err = update_as(&mut deps, &mock_env(), "child", update_msg(vec![score(1)])).unwrap_err();
+  assert!(matches!(err, ContractError::NotInIndex { .. }));
+}
+
+#[test]
+fn self_heal_reinserts_desynced_indices() {
+  let mut deps = setup();
+  let id = create(&mut deps, &mock_env(), "child", vec![]);
+  desync_indices(&mut deps, "child", id);
+
+  let err = admin_as(
+    &mut deps,
+    "stranger",
+    AdminMsg::SetSelfHealIndices { enabled: true },
+  )
+  .unwrap_err();
+  assert!(matches!(err, ContractError::NotAuthorized {}));
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetSelfHealIndices { enabled: true },
+  )
+  .unwrap();
+
+  let env = env_at(mock_env().block.time.seconds() + 60);
+  update_as(&mut deps, &env, "child", update_msg(vec![score(1)])).unwrap();
+  assert!(IX_REV.has(&deps.storage, (1, id)));
+  assert!(IX_UPDATED_AT.has(&deps.storage, (env.block.time.nanos(), id)));
+
+  // and back to strict
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::SetSelfHealIndices { enabled: false },
+  )
+  .unwrap();
+  desync_indices(&mut deps, "child", id);
+  let err = update_as(&mut deps, &env, "child", update_msg(vec![score(2)])).unwrap_err();
+  assert!(matches!(err, ContractError::NotInIndex { .. }));
+}
+
+#[test]
+fn code_id_history_keeps_a_return_to_an_earlier_code_id() {
+  let mut deps = setup();
+  let t0 = mock_env().block.time.seconds();
+  create(&mut deps, &env_at(t0), "child", vec![]);
+  for (i, code_id) in [2, 1].iter().enumerate() {
+    let msg = ExecuteMsg::Update {
+      values: None,
+      relationships: None,
+      tags: None,
+      code_id: Some(*code_id),
+    };
+    update_as(&mut deps, &env_at(t0 + 60 * (i as u64 + 1)), "child", msg).unwrap();
+  }
+
+  let res: CodeIdHistoryResponse = query_as(
+    &deps,
+    QueryMsg::CodeIdHistory {
+      contract_addr: addr("child"),
+    },
+  );
+  assert_eq!(
+    res.history,
+    vec![
+      (1, Timestamp::from_seconds(t0)),
+      (2, Timestamp::from_seconds(t0 + 60)),
+      (1, Timestamp::from_seconds(t0 + 120)),
+    ]
+  );
+}
+
+#[test]
+fn updated_bool_is_removed_with_its_contract() {
+  let mut deps = setup();
+  create(
+    &mut deps,
+    &mock_env(),
+    "child",
+    vec![IndexSlotValue::Boolean {
+      slot: 0,
+      value: true,
+    }],
+  );
+  let msg = update_msg(vec![IndexSlotValue::Boolean {
+    slot: 0,
+    value: false,
+  }]);
+  update_as(&mut deps, &mock_env(), "child", msg).unwrap();
+  admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::Remove {
+      contract_addr: addr("child"),
+    },
+  )
+  .unwrap();
+
+  let n_entries = get_bool_index(0)
+    .unwrap()
+    .keys(&deps.storage, None, None, Order::Ascending)
+    .count();
+  assert_eq!(n_entries, 0);
+}
+
+#[test]
+fn fresh_bool_grows_only_the_bool_index() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let text_size = IX_META_STRING.load(&deps.storage, 0).unwrap().size;
+
+  let msg = update_msg(vec![IndexSlotValue::Boolean {
+    slot: 0,
+    value: true,
+  }]);
+  update_as(&mut deps, &mock_env(), "child", msg).unwrap();
+
+  assert_eq!(IX_META_BOOL.load(&deps.storage, 0).unwrap().size, 1);
+  assert_eq!(
+    IX_META_STRING.load(&deps.storage, 0).unwrap().size,
+    text_size
+  );
+}
+
+fn code_id_msg(code_id: u64) -> ExecuteMsg {
+  ExecuteMsg::Update {
+    values: None,
+    relationships: None,
+    tags: None,
+    code_id: Some(code_id),
+  }
+}
+
+#[test]
+fn reported_code_id_corrects_metadata_and_index() {
+  let mut deps = setup();
+  let id = create(&mut deps, &mock_env(), "child", vec![]);
+
+  update_as(&mut deps, &mock_env(), "child", code_id_msg(2)).unwrap();
+  let meta = METADATA.load(&deps.storage, addr("child")).unwrap();
+  assert_eq!(meta.code_id, 2);
+  assert!(!IX_CODE_ID.has(&deps.storage, (CODE_ID, id)));
+  assert!(IX_CODE_ID.has(&deps.storage, (2, id)));
+
+  let err = update_as(&mut deps, &mock_env(), "child", code_id_msg(99)).unwrap_err();
+  assert!(matches!(err, ContractError::CodeIdNotAllowed {}));
+}
+
+#[test]
+fn batch_set_indices_moves_each_contracts_keys() {
+  let mut deps = setup();
+  let env = mock_env();
+  let ids: Vec<ContractID> = (0..3)
+    .map(|i| create(&mut deps, &env, &format!("child-{}", i), vec![score(1)]))
+    .collect();
+  let text = IndexSlotValue::Text {
+    slot: 0,
+    value: "b".to_string(),
+  };
+
+  let res = admin_as(
+    &mut deps,
+    CREATOR,
+    AdminMsg::BatchSetIndices {
+      updates: vec![
+        (addr("child-0"), vec![score(2)]),
+        (addr("child-1"), vec![score(2), text]),
+      ],
+    },
+  )
+  .unwrap();
+  let updated = res
+    .attributes
+    .iter()
+    .find(|a| a.key == "updated_count")
+    .unwrap();
+  assert_eq!(updated.value, "2");
+
+  let ix = get_u64_index(0).unwrap();
+  assert!(ix.has(&deps.storage, (2, ids[0])));
+  assert!(ix.has(&deps.storage, (2, ids[1])));
+  assert!(ix.has(&deps.storage, (1, ids[2])));
+  assert!(!ix.has(&deps.storage, (1, ids[0])));
+  assert!(!ix.has(&deps.storage, (1, ids[1])));
+  assert_eq!(IX_META_U64.load(&deps.storage, 0).unwrap().size, 3);
+  assert_eq!(IX_META_STRING.load(&deps.storage, 0).unwrap().size, 1);
+  assert_eq!(
+    METADATA.load(&deps.storage, addr("child-1")).unwrap().rev,
+    1
+  );
+  assert_eq!(
+    METADATA.load(&deps.storage, addr("child-2")).unwrap().rev,
+    0
+  );
+
+  let updates = (0..=MAX_BATCH_SIZE)
+    .map(|_| (addr("child-0"), vec![score(3)]))
+    .collect();
+  let err = admin_as(&mut deps, CREATOR, AdminMsg::BatchSetIndices { updates }).unwrap_err();
+  assert!(matches!(err, ContractError::BatchSizeExceeded { .. }));
+}
+
+#[test]
+fn no_op_update_leaves_storage_untouched() {
+  let mut deps = setup();
+  let t0 = mock_env().block.time.seconds();
+  let mut msg = create_msg(vec![score(1)]);
+  if let ExecuteMsg::Create { tags, .. } = &mut msg {
+    *tags = Some(vec!["a".to_string()]);
+  }
+  let id = create_as(&mut deps, &env_at(t0), CREATOR, "child", msg);
+  let before = storage_snapshot(&deps);
+
+  // the same value and an already present tag change nothing, not even rev
+  let msg = ExecuteMsg::Update {
+    values: Some(vec![score(1)]),
+    relationships: None,
+    tags: Some(TagUpdates {
+      added: Some(vec!["a".to_string()]),
+      removed: None,
+    }),
+    code_id: None,
+  };
+  update_as(&mut deps, &env_at(t0 + 60), "child", msg).unwrap();
+  assert_eq!(storage_snapshot(&deps), before);
+
+  let env = env_at(t0 + 120);
+  update_as(&mut deps, &env, "child", update_msg(vec![score(2)])).unwrap();
+  let meta = METADATA.load(&deps.storage, addr("child")).unwrap();
+  assert_eq!(meta.rev, 1);
+  assert_eq!(meta.updated_at, env.block.time);
+  assert!(IX_REV.has(&deps.storage, (1, id)));
+  assert!(!IX_REV.has(&deps.storage, (0, id)));
+  let ix = get_u64_index(0).unwrap();
+  assert!(ix.has(&deps.storage, (2, id)));
+  assert!(!ix.has(&deps.storage, (1, id)));
+  assert_eq!(IX_META_U64.load(&deps.storage, 0).unwrap().size, 1);
+}
+
+#[test]
+fn oversized_and_unencoded_tags_are_rejected() {
+  let mut msg = instantiate_msg();
+  msg.require_base64_tags = Some(true);
+  let mut deps = setup_with(msg);
+  let tag_msg = |tag: &str| ExecuteMsg::Update {
+    values: None,
+    relationships: None,
+    tags: Some(TagUpdates {
+      added: Some(vec![tag.to_string()]),
+      removed: None,
+    }),
+    code_id: None,
+  };
+  let relate_msg = |name: &str| ExecuteMsg::Update {
+    values: None,
+    relationships: Some(RelationshipUpdates {
+      added: Some(vec![AddressTag {
+        address: addr("wallet"),
+        tag: name.to_string(),
+      }]),
+      removed: None,
+    }),
+    tags: None,
+    code_id: None,
+  };
+  let oversized = "A".repeat(MAX_TAG_LEN + 4);
+  let encoded = Binary::from(b"ok".to_vec()).to_base64();
+
+  // create checks tags and relationships before anything is instantiated
+  for bad in [oversized.as_str(), "", "not base64!"].iter() {
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { tags, .. } = &mut msg {
+      *tags = Some(vec![bad.to_string()]);
+    }
+    let err = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap_err();
+    assert!(
+      matches!(err, ContractError::InvalidTag { .. }),
+      "tag: {:?}",
+      bad
+    );
+
+    let mut msg = create_msg(vec![]);
+    if let ExecuteMsg::Create { relationships, .. } = &mut msg {
+      *relationships = Some(vec![AddressTag {
+        address: addr("wallet"),
+        tag: bad.to_string(),
+      }]);
+    }
+    let err = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &[]), msg).unwrap_err();
+    assert!(
+      matches!(err, ContractError::InvalidTag { .. }),
+      "name: {:?}",
+      bad
+    );
+  }
+
+  create(&mut deps, &mock_env(), "child", vec![]);
+  for bad in [oversized.as_str(), "not base64!"].iter() {
+    let err = update_as(&mut deps, &mock_env(), "child", tag_msg(bad)).unwrap_err();
+    assert!(
+      matches!(err, ContractError::InvalidTag { .. }),
+      "tag: {:?}",
+      bad
+    );
+    let err = update_as(&mut deps, &mock_env(), "child", relate_msg(bad)).unwrap_err();
+    assert!(
+      matches!(err, ContractError::InvalidTag { .. }),
+      "name: {:?}",
+      bad
+    );
+  }
+  update_as(&mut deps, &mock_env(), "child", tag_msg(&encoded)).unwrap();
+  update_as(&mut deps, &mock_env(), "child", relate_msg(&encoded)).unwrap();
+}
+
+#[test]
+fn unencoded_tags_are_allowed_unless_required() {
+  let mut deps = setup();
+  create(&mut deps, &mock_env(), "child", vec![]);
+  let msg = ExecuteMsg::Update {
+    values: None,
+    relationships: None,
+    tags: Some(TagUpdates {
+      added: Some(vec!["not base64!".to_string()]),
+      removed: None,
+    }),
+    code_id: None,
+  };
+  update_as(&mut deps, &mock_env(), "child", msg).unwrap();
+
+  let msg = ExecuteMsg::Update {
+    values: None,
+    relationships: None,
+    tags: Some(TagUpdates {
+      added: Some(vec!["A".repeat(MAX_TAG_LEN + 1)]),
+      removed: None,
+    }),
+    code_id: None,
+  };
+  let err = update_as(&mut deps, &mock_env(), "child", msg).unwrap_err();
+  assert!(matches!(err, ContractError::InvalidTag { .. }));
+}
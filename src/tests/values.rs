@@ -0,0 +1,65 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::Uint64;
+
+use super::*;
+use crate::models::MAX_BATCH_SIZE;
+use crate::msg::{ValuesByIdsResponse, ValuesResponse};
+
+fn values_of(
+  deps: &MockDeps,
+  contract_addr: &str,
+) -> ValuesResponse {
+  query_as(
+    deps,
+    QueryMsg::Values {
+      contract_addr: addr(contract_addr),
+    },
+  )
+}
+
+#[test]
+fn values_by_ids_keeps_the_order_given() {
+  let mut deps = setup();
+  let env = mock_env();
+  let a = create(
+    &mut deps,
+    &env,
+    "child-a",
+    vec![IndexSlotValue::Uint64 {
+      slot: 0,
+      value: Uint64::new(5),
+    }],
+  );
+  let b = create(
+    &mut deps,
+    &env,
+    "child-b",
+    vec![IndexSlotValue::Text {
+      slot: 0,
+      value: "b".to_string(),
+    }],
+  );
+
+  let res: ValuesByIdsResponse = query_as(
+    &deps,
+    QueryMsg::ValuesByIds {
+      ids: vec![b, 99, a],
+    },
+  );
+  assert_eq!(
+    res.values,
+    vec![
+      (b, Some(values_of(&deps, "child-b").values)),
+      (99, None),
+      (a, Some(values_of(&deps, "child-a").values)),
+    ]
+  );
+  assert_eq!(
+    res.values[2].1.as_ref().unwrap().uint64[0],
+    Some(Uint64::new(5))
+  );
+
+  let ids = (0..=MAX_BATCH_SIZE as ContractID).collect();
+  let err = query(deps.as_ref(), env, QueryMsg::ValuesByIds { ids }).unwrap_err();
+  assert!(matches!(err, ContractError::BatchSizeExceeded { .. }));
+}
@@ -0,0 +1,123 @@
+use cosmwasm_std::from_slice;
+use cosmwasm_std::testing::mock_env;
+
+use super::*;
+use crate::msg::ValuesResponse;
+
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn create_json(
+  deps: &mut MockDeps,
+  contract_addr: &str,
+  json: &str,
+) -> ContractID {
+  let msg: ExecuteMsg = from_slice(json.as_bytes()).unwrap();
+  create_as(deps, &mock_env(), CREATOR, contract_addr, msg)
+}
+
+fn read_json_ids(
+  deps: &MockDeps,
+  json: &str,
+) -> Vec<ContractID> {
+  read_all_ids(deps, from_slice(json.as_bytes()).unwrap())
+}
+
+#[test]
+fn values_above_2_pow_53_round_trip_as_strings() {
+  let mut deps = setup();
+  let a = MAX_SAFE_INTEGER + 1;
+  let b = MAX_SAFE_INTEGER + 2;
+  let big_u128 = u128::MAX;
+  let nanos = u64::MAX - 1;
+
+  let id_a = create_json(
+    &mut deps,
+    "child-a",
+    &format!(
+      r#"{{"create":{{"indices":[{{"uint64":{{"slot":0,"value":"{}"}}}}]}}}}"#,
+      a
+    ),
+  );
+  let id_b = create_json(
+    &mut deps,
+    "child-b",
+    &format!(
+      r#"{{"create":{{"indices":[
+        {{"uint64":{{"slot":0,"value":"{}"}}}},
+        {{"uint128":{{"slot":0,"value":"{}"}}}},
+        {{"timestamp":{{"slot":0,"value":"{}"}}}}
+      ]}}}}"#,
+      b, big_u128, nanos
+    ),
+  );
+
+  let res: ValuesResponse = query_as(
+    &deps,
+    QueryMsg::Values {
+      contract_addr: addr("child-b"),
+    },
+  );
+  assert_eq!(res.values.uint64[0], Some(b.into()));
+  assert_eq!(res.values.uint128[0], Some(big_u128.into()));
+  assert_eq!(res.values.timestamp[0], Some(nanos.into()));
+
+  // and they go back out as strings, too
+  let json = query(
+    deps.as_ref(),
+    mock_env(),
+    QueryMsg::Values {
+      contract_addr: addr("child-b"),
+    },
+  )
+  .unwrap();
+  let json = String::from_utf8(json.to_vec()).unwrap();
+  assert!(json.contains(&format!(r#""uint64":["{}""#, b)), "{}", json);
+  assert!(
+    json.contains(&format!(r#""uint128":["{}""#, big_u128)),
+    "{}",
+    json
+  );
+  assert!(
+    json.contains(&format!(r#""timestamp":["{}""#, nanos)),
+    "{}",
+    json
+  );
+
+  // values one apart, which a double can't tell apart, are matched exactly
+  let ids = read_json_ids(
+    &deps,
+    &format!(
+      r#"{{"read":{{"target":{{"index":{{"uint64":{{"slot":0,"equals":"{}"}}}}}},"raw_keys":true}}}}"#,
+      a
+    ),
+  );
+  assert_eq!(ids, vec![id_a]);
+  let ids = read_json_ids(
+    &deps,
+    &format!(
+      r#"{{"read":{{"target":{{"index":{{"uint64":{{"slot":0,"between":["{}",null]}}}}}},"raw_keys":true}}}}"#,
+      b
+    ),
+  );
+  assert_eq!(ids, vec![id_b]);
+  let ids = read_json_ids(
+    &deps,
+    &format!(
+      r#"{{"read":{{"target":{{"index":{{"uint128":{{"slot":0,"equals":"{}"}}}}}},"raw_keys":true}}}}"#,
+      big_u128
+    ),
+  );
+  assert_eq!(ids, vec![id_b]);
+
+  // timestamp bounds go through the u64 paginator, with an exclusive upper
+  // bound
+  let ids = read_json_ids(
+    &deps,
+    &format!(
+      r#"{{"read":{{"target":{{"index":{{"timestamp":{{"slot":0,"between":["{}","{}"]}}}}}},"raw_keys":true}}}}"#,
+      nanos,
+      nanos + 1
+    ),
+  );
+  assert_eq!(ids, vec![id_b]);
+}